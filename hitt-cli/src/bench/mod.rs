@@ -0,0 +1,310 @@
+/// Reason a request of a benchmark did not get a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BenchError {
+    Connection,
+    Request,
+    Timeout,
+}
+
+impl core::fmt::Display for BenchError {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Connection => write!(f, "connection error"),
+            Self::Request => write!(f, "request error"),
+            Self::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+impl From<&reqwest::Error> for BenchError {
+    #[inline]
+    fn from(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Self::Timeout
+        } else if error.is_connect() {
+            Self::Connection
+        } else {
+            Self::Request
+        }
+    }
+}
+
+/// Outcome of a single request of a benchmark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BenchOutcome {
+    Error(BenchError),
+    Response(reqwest::StatusCode),
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BenchReport {
+    /// Latency of every request that got a response, sorted from fastest to slowest.
+    pub latencies: Vec<core::time::Duration>,
+    pub status_codes: std::collections::BTreeMap<u16, usize>,
+    pub errors: std::collections::BTreeMap<BenchError, usize>,
+    /// Time from sending the first request until the last request finished.
+    pub elapsed: core::time::Duration,
+}
+
+impl BenchReport {
+    /// Number of requests that were sent, including requests without a response.
+    #[inline]
+    pub fn request_count(&self) -> usize {
+        self.status_codes.values().sum::<usize>() + self.errors.values().sum::<usize>()
+    }
+
+    /// Requests finished per second.
+    #[inline]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+
+        if seconds == 0.0 {
+            return 0.0;
+        }
+
+        self.request_count() as f64 / seconds
+    }
+}
+
+#[inline]
+pub fn build_report(
+    samples: Vec<(core::time::Duration, BenchOutcome)>,
+    elapsed: core::time::Duration,
+) -> BenchReport {
+    let mut report = BenchReport {
+        elapsed,
+        ..BenchReport::default()
+    };
+
+    for (latency, outcome) in samples {
+        match outcome {
+            BenchOutcome::Error(error) => *report.errors.entry(error).or_default() += 1,
+            BenchOutcome::Response(status_code) => {
+                *report.status_codes.entry(status_code.as_u16()).or_default() += 1;
+
+                report.latencies.push(latency);
+            }
+        }
+    }
+
+    report.latencies.sort_unstable();
+
+    report
+}
+
+#[cfg(test)]
+mod test_build_report {
+    use core::time::Duration;
+
+    use super::{BenchError, BenchOutcome, build_report};
+
+    #[test]
+    fn it_should_count_outcomes() {
+        let report = build_report(
+            vec![
+                (
+                    Duration::from_millis(30),
+                    BenchOutcome::Response(reqwest::StatusCode::OK),
+                ),
+                (
+                    Duration::from_millis(10),
+                    BenchOutcome::Response(reqwest::StatusCode::OK),
+                ),
+                (
+                    Duration::from_millis(20),
+                    BenchOutcome::Response(reqwest::StatusCode::SERVICE_UNAVAILABLE),
+                ),
+                (
+                    Duration::from_secs(5),
+                    BenchOutcome::Error(BenchError::Timeout),
+                ),
+            ],
+            Duration::from_secs(2),
+        );
+
+        assert_eq!(
+            vec![
+                Duration::from_millis(10),
+                Duration::from_millis(20),
+                Duration::from_millis(30)
+            ],
+            report.latencies
+        );
+        assert_eq!(
+            std::collections::BTreeMap::from([(200, 2), (503, 1)]),
+            report.status_codes
+        );
+        assert_eq!(
+            std::collections::BTreeMap::from([(BenchError::Timeout, 1)]),
+            report.errors
+        );
+
+        assert_eq!(4, report.request_count());
+        assert!((report.throughput() - 2.0).abs() < f64::EPSILON);
+    }
+}
+
+/// Returns the latency that `percentile` percent of the sorted latencies are at or below.
+#[inline]
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss
+)]
+pub fn percentile(
+    latencies: &[core::time::Duration],
+    percentile: f64,
+) -> Option<core::time::Duration> {
+    let rank = (percentile.clamp(0.0, 100.0) / 100.0 * latencies.len() as f64).ceil() as usize;
+
+    latencies.get(rank.saturating_sub(1)).copied()
+}
+
+#[cfg(test)]
+mod test_percentile {
+    use core::time::Duration;
+
+    use super::percentile;
+
+    #[test]
+    fn it_should_use_nearest_rank() {
+        let latencies = (1..=100).map(Duration::from_millis).collect::<Vec<_>>();
+
+        for (input, expected) in [(0.0, 1), (50.0, 50), (90.0, 90), (99.0, 99), (100.0, 100)] {
+            assert_eq!(
+                Some(Duration::from_millis(expected)),
+                percentile(&latencies, input)
+            );
+        }
+
+        let latencies = [Duration::from_millis(10), Duration::from_millis(20)];
+
+        assert_eq!(
+            Some(Duration::from_millis(10)),
+            percentile(&latencies, 50.0)
+        );
+        assert_eq!(
+            Some(Duration::from_millis(20)),
+            percentile(&latencies, 99.0)
+        );
+    }
+
+    #[test]
+    fn it_should_return_none_without_latencies() {
+        assert_eq!(None, percentile(&[], 50.0));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HistogramBucket {
+    pub start: core::time::Duration,
+    pub end: core::time::Duration,
+    pub count: usize,
+}
+
+/// Splits the range of the sorted latencies into buckets of equal width.
+#[inline]
+pub fn build_histogram(
+    latencies: &[core::time::Duration],
+    bucket_count: usize,
+) -> Vec<HistogramBucket> {
+    let (Some(min), Some(max)) = (latencies.first(), latencies.last()) else {
+        return Vec::new();
+    };
+
+    let range = max.saturating_sub(*min).as_nanos();
+
+    if range == 0 || bucket_count <= 1 {
+        return vec![HistogramBucket {
+            start: *min,
+            end: *max,
+            count: latencies.len(),
+        }];
+    }
+
+    let bucket_count_u128 = u128::try_from(bucket_count).unwrap_or(u128::MAX);
+
+    let bucket_start = |index: u128| {
+        let offset = range * index / bucket_count_u128;
+
+        *min + core::time::Duration::from_nanos(u64::try_from(offset).unwrap_or(u64::MAX))
+    };
+
+    let mut buckets = (0..bucket_count_u128)
+        .map(|index| HistogramBucket {
+            start: bucket_start(index),
+            end: bucket_start(index + 1),
+            count: 0,
+        })
+        .collect::<Vec<_>>();
+
+    for latency in latencies {
+        let index = (latency.saturating_sub(*min).as_nanos() * bucket_count_u128 / range)
+            .min(bucket_count_u128 - 1);
+
+        if let Some(bucket) = usize::try_from(index)
+            .ok()
+            .and_then(|index| buckets.get_mut(index))
+        {
+            bucket.count += 1;
+        }
+    }
+
+    buckets
+}
+
+#[cfg(test)]
+mod test_build_histogram {
+    use core::time::Duration;
+
+    use super::{HistogramBucket, build_histogram};
+
+    #[test]
+    fn it_should_split_range_into_buckets() {
+        let latencies = [10, 12, 15, 19, 20, 30, 50].map(Duration::from_millis);
+
+        assert_eq!(
+            vec![
+                HistogramBucket {
+                    start: Duration::from_millis(10),
+                    end: Duration::from_millis(20),
+                    count: 4
+                },
+                HistogramBucket {
+                    start: Duration::from_millis(20),
+                    end: Duration::from_millis(30),
+                    count: 1
+                },
+                HistogramBucket {
+                    start: Duration::from_millis(30),
+                    end: Duration::from_millis(40),
+                    count: 1
+                },
+                HistogramBucket {
+                    start: Duration::from_millis(40),
+                    end: Duration::from_millis(50),
+                    count: 1
+                },
+            ],
+            build_histogram(&latencies, 4)
+        );
+    }
+
+    #[test]
+    fn it_should_use_single_bucket_for_equal_latencies() {
+        let latencies = [Duration::from_millis(5); 3];
+
+        assert_eq!(
+            vec![HistogramBucket {
+                start: Duration::from_millis(5),
+                end: Duration::from_millis(5),
+                count: 3
+            }],
+            build_histogram(&latencies, 10)
+        );
+
+        assert!(build_histogram(&[], 10).is_empty());
+    }
+}
@@ -0,0 +1,529 @@
+use hitt_parser::{HittRequest, ParseOptions};
+use hitt_request::{retry::RetryPolicy, send_request};
+
+use crate::{
+    bench::{BenchError, BenchOutcome, BenchReport, build_report},
+    commands::run::{get_requests, read_input_variables},
+    config::{
+        BenchCommandArguments,
+        proxy::configure_proxies,
+        tls::{build_tls_options, configure_tls, request_tls_options},
+        unix_socket::{configure_unix_socket, request_unix_socket},
+    },
+    error::HittCliError,
+    fs::DiscoveryOptions,
+    terminal::{
+        bench::{print_bench_report, print_bench_start},
+        print_parse_warning,
+        redact::redact_secrets,
+    },
+};
+
+const DEFAULT_REQUEST_COUNT: usize = 100;
+
+/// Returns the request with the name, or the number starting at 1, of the selector.
+///
+/// The selector can be left out for files containing a single request.
+#[inline]
+fn select_request(
+    path: &std::path::Path,
+    requests: Vec<HittRequest>,
+    selector: Option<&str>,
+) -> Result<HittRequest, HittCliError> {
+    let Some(selector) = selector else {
+        return match requests.len() {
+            0 => Err(HittCliError::BenchNoRequests(path.to_path_buf())),
+            1 => requests
+                .into_iter()
+                .next()
+                .ok_or_else(|| HittCliError::BenchNoRequests(path.to_path_buf())),
+            count => Err(HittCliError::BenchMultipleRequests(
+                path.to_path_buf(),
+                count,
+            )),
+        };
+    };
+
+    let index = requests
+        .iter()
+        .position(|req| req.name.as_deref() == Some(selector))
+        .or_else(|| {
+            selector
+                .parse::<usize>()
+                .ok()
+                .and_then(|number| number.checked_sub(1))
+        });
+
+    index
+        .and_then(|index| requests.into_iter().nth(index))
+        .ok_or_else(|| HittCliError::BenchMissingRequest(path.to_path_buf(), selector.to_owned()))
+}
+
+#[cfg(test)]
+mod test_select_request {
+    use hitt_parser::HittRequest;
+
+    use super::select_request;
+    use crate::error::HittCliError;
+
+    fn build_requests(names: &[Option<&str>]) -> Vec<HittRequest> {
+        names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| HittRequest {
+                method: http::Method::GET,
+                uri: http::Uri::try_from(format!("https://mhouge.dk/{index}"))
+                    .expect("it to be a valid uri"),
+                headers: http::HeaderMap::new(),
+                body: None,
+                http_version: None,
+                secrets: Vec::new(),
+                warnings: Vec::new(),
+                name: name.map(str::to_owned),
+                max_redirects: None,
+                tls: hitt_parser::tls::RequestTls::default(),
+                unix_socket: None,
+                output: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn it_should_select_by_name_or_number() {
+        let path = std::path::Path::new("users.http");
+
+        for (selector, expected) in [("login", "/0"), ("2", "/1"), ("3", "/2")] {
+            let req = select_request(
+                path,
+                build_requests(&[Some("login"), None, Some("logout")]),
+                Some(selector),
+            )
+            .expect("it to find the request");
+
+            assert_eq!(expected, req.uri.path());
+        }
+    }
+
+    #[test]
+    fn it_should_select_single_request_without_selector() {
+        let req = select_request(
+            std::path::Path::new("users.http"),
+            build_requests(&[None]),
+            None,
+        )
+        .expect("it to find the request");
+
+        assert_eq!("/0", req.uri.path());
+    }
+
+    #[test]
+    fn it_should_reject_ambiguous_selectors() {
+        let path = std::path::Path::new("users.http");
+
+        let error = select_request(path, build_requests(&[None, None]), None)
+            .expect_err("it to require a selector");
+
+        assert_eq!(
+            "'users.http' contains 2 requests - select one using --request <NAME>",
+            error.to_string()
+        );
+        assert!(matches!(error, HittCliError::BenchMultipleRequests(_, 2)));
+
+        for selector in ["0", "3", "missing"] {
+            let error = select_request(path, build_requests(&[None, None]), Some(selector))
+                .expect_err("it to reject the selector");
+
+            assert_eq!(
+                format!("no request named or numbered '{selector}' in 'users.http'"),
+                error.to_string()
+            );
+            assert!(matches!(error, HittCliError::BenchMissingRequest(_, _)));
+        }
+
+        let error = select_request(path, Vec::new(), None).expect_err("it to reject empty files");
+
+        assert_eq!(
+            "'users.http' does not contain any requests",
+            error.to_string()
+        );
+        assert!(matches!(error, HittCliError::BenchNoRequests(_)));
+    }
+}
+
+/// How many requests a benchmark sends, and how fast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BenchPlan {
+    /// Total number of requests, unless `duration` is set.
+    requests: usize,
+    duration: Option<core::time::Duration>,
+    concurrency: usize,
+    /// Maximum number of requests started per second.
+    rate: Option<u32>,
+    timeout: Option<core::time::Duration>,
+}
+
+impl BenchPlan {
+    #[inline]
+    fn description(&self) -> String {
+        let mut output = match self.duration {
+            Some(duration) => format!("for {}s", duration.as_secs()),
+            None => format!("{} requests", self.requests),
+        };
+
+        output.push_str(&format!(", {} at a time", self.concurrency));
+
+        if let Some(rate) = self.rate {
+            output.push_str(&format!(", at most {rate}/s"));
+        }
+
+        output
+    }
+}
+
+/// Sends the request using `concurrency` workers until the plan is done.
+///
+/// Requests still running when the duration ends are waited for, and included in the report.
+#[inline]
+async fn run_benchmark(
+    http_client: &reqwest::Client,
+    req: &HittRequest,
+    plan: &BenchPlan,
+) -> BenchReport {
+    let next_index = core::sync::atomic::AtomicUsize::new(0);
+
+    let retry_policy = RetryPolicy::default();
+
+    let max_redirects = req
+        .max_redirects
+        .unwrap_or(hitt_request::redirect::DEFAULT_MAX_REDIRECTS);
+
+    let start = tokio::time::Instant::now();
+
+    let deadline = plan.duration.map(|duration| start + duration);
+
+    let worker = || async {
+        let mut samples = Vec::new();
+
+        loop {
+            let index = next_index.fetch_add(1, core::sync::atomic::Ordering::Relaxed);
+
+            if deadline.is_none() && index >= plan.requests {
+                break;
+            }
+
+            if let Some(rate) = plan.rate {
+                let offset = u64::try_from(index)
+                    .unwrap_or(u64::MAX)
+                    .saturating_mul(1_000_000_000)
+                    / u64::from(rate);
+
+                let send_at = start + core::time::Duration::from_nanos(offset);
+
+                if deadline.is_some_and(|deadline| send_at >= deadline) {
+                    break;
+                }
+
+                tokio::time::sleep_until(send_at).await;
+            }
+
+            if deadline.is_some_and(|deadline| tokio::time::Instant::now() >= deadline) {
+                break;
+            }
+
+            let sent_at = tokio::time::Instant::now();
+
+            let result = send_request(
+                http_client,
+                req,
+                plan.timeout.as_ref(),
+                None,
+                max_redirects,
+                &retry_policy,
+                |_| {},
+            )
+            .await;
+
+            samples.push((
+                sent_at.elapsed(),
+                match result {
+                    Ok(response) => BenchOutcome::Response(response.status_code),
+                    Err(error) => BenchOutcome::Error(BenchError::from(&error)),
+                },
+            ));
+        }
+
+        samples
+    };
+
+    let samples = futures::future::join_all((0..plan.concurrency).map(|_| worker()))
+        .await
+        .concat();
+
+    build_report(samples, start.elapsed())
+}
+
+#[cfg(test)]
+mod test_run_benchmark {
+    use std::io::{BufRead, Write};
+
+    use super::{BenchPlan, run_benchmark};
+    use crate::bench::BenchError;
+
+    /// Responds with `200` to every request but every fourth, which gets a `503`.
+    fn start_server() -> std::net::SocketAddr {
+        let listener =
+            std::net::TcpListener::bind("127.0.0.1:0").expect("it to bind to a local port");
+
+        let addr = listener.local_addr().expect("it to have a local address");
+
+        std::thread::spawn(move || {
+            let mut request_count = 0_usize;
+
+            for stream in listener.incoming().flatten() {
+                let mut reader = std::io::BufReader::new(&stream);
+
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|len| len > 2) {
+                    line.clear();
+                }
+
+                request_count += 1;
+
+                let status = if request_count.is_multiple_of(4) {
+                    "503 Service Unavailable"
+                } else {
+                    "200 OK"
+                };
+
+                let _ = (&stream).write_all(
+                    format!("HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                        .as_bytes(),
+                );
+            }
+        });
+
+        addr
+    }
+
+    fn build_request(url: &str) -> hitt_parser::HittRequest {
+        hitt_parser::HittRequest {
+            method: http::Method::GET,
+            uri: http::Uri::try_from(url).expect("it to be a valid uri"),
+            headers: http::HeaderMap::new(),
+            body: None,
+            http_version: None,
+            secrets: Vec::new(),
+            warnings: Vec::new(),
+            name: None,
+            max_redirects: None,
+            tls: hitt_parser::tls::RequestTls::default(),
+            unix_socket: None,
+            output: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_send_number_of_requests() {
+        let addr = start_server();
+
+        let report = run_benchmark(
+            &reqwest::Client::new(),
+            &build_request(&format!("http://{addr}/")),
+            &BenchPlan {
+                requests: 20,
+                duration: None,
+                concurrency: 3,
+                rate: None,
+                timeout: None,
+            },
+        )
+        .await;
+
+        assert_eq!(20, report.request_count());
+        assert_eq!(20, report.latencies.len());
+        assert_eq!(
+            std::collections::BTreeMap::from([(200, 15), (503, 5)]),
+            report.status_codes
+        );
+        assert!(report.errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_should_use_max_redirects_of_request() {
+        let listener =
+            std::net::TcpListener::bind("127.0.0.1:0").expect("it to bind to a local port");
+
+        let addr = listener.local_addr().expect("it to have a local address");
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = std::io::BufReader::new(&stream);
+
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|len| len > 2) {
+                    line.clear();
+                }
+
+                let _ = (&stream).write_all(
+                    b"HTTP/1.1 302 Found\r\nlocation: /\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                );
+            }
+        });
+
+        let report = run_benchmark(
+            &reqwest::Client::builder()
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .expect("it to build a client"),
+            &hitt_parser::HittRequest {
+                max_redirects: Some(0),
+                ..build_request(&format!("http://{addr}/"))
+            },
+            &BenchPlan {
+                requests: 3,
+                duration: None,
+                concurrency: 1,
+                rate: None,
+                timeout: None,
+            },
+        )
+        .await;
+
+        assert_eq!(
+            std::collections::BTreeMap::from([(302, 3)]),
+            report.status_codes
+        );
+        assert!(report.errors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn it_should_limit_rate() {
+        let addr = start_server();
+
+        let report = run_benchmark(
+            &reqwest::Client::new(),
+            &build_request(&format!("http://{addr}/")),
+            &BenchPlan {
+                requests: 100,
+                duration: Some(core::time::Duration::from_millis(500)),
+                concurrency: 4,
+                rate: Some(20),
+                timeout: None,
+            },
+        )
+        .await;
+
+        // requests start every 50ms, so 10 fit within the duration
+        assert!((9..=11).contains(&report.request_count()));
+    }
+
+    #[tokio::test]
+    async fn it_should_count_connection_errors() {
+        // binding and dropping the listener leaves a port nothing listens on
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .expect("it to bind to a local port")
+            .local_addr()
+            .expect("it to have a local address");
+
+        let report = run_benchmark(
+            &reqwest::Client::new(),
+            &build_request(&format!("http://{addr}/")),
+            &BenchPlan {
+                requests: 5,
+                duration: None,
+                concurrency: 2,
+                rate: None,
+                timeout: None,
+            },
+        )
+        .await;
+
+        assert_eq!(
+            std::collections::BTreeMap::from([(BenchError::Connection, 5)]),
+            report.errors
+        );
+        assert!(report.latencies.is_empty());
+    }
+}
+
+#[inline]
+pub async fn bench_command<W: std::io::Write + Send>(
+    term: &mut W,
+    args: &BenchCommandArguments,
+) -> Result<(), HittCliError> {
+    let (vars, input_secrets) = read_input_variables(&args.variables).await?;
+
+    let (path, requests) = get_requests(
+        term,
+        core::slice::from_ref(&args.path),
+        false,
+        &DiscoveryOptions::default(),
+        vars,
+        &ParseOptions::default(),
+    )
+    .await?
+    .into_iter()
+    .next()
+    .ok_or_else(|| HittCliError::BenchNoRequests(args.path.clone()))?;
+
+    let req = select_request(&path, requests, args.request.as_deref())?;
+
+    for warning in &req.warnings {
+        print_parse_warning(term, warning)?;
+    }
+
+    let mut secrets = req.secrets.clone();
+    secrets.extend_from_slice(&input_secrets);
+
+    let tls_options = request_tls_options(&build_tls_options(&args.tls), &req.tls, &path);
+
+    let unix_socket = request_unix_socket(
+        args.unix_socket.as_deref(),
+        req.unix_socket.as_deref(),
+        &path,
+    );
+
+    // redirects are followed by send_request
+    let http_client = configure_tls(
+        configure_unix_socket(
+            configure_proxies(
+                reqwest::ClientBuilder::new()
+                    .http09_responses()
+                    .redirect(reqwest::redirect::Policy::none()),
+                &args.proxy,
+            )?,
+            unix_socket.as_deref(),
+        )?,
+        &tls_options,
+        None,
+    )?
+    .build()
+    .map_err(HittCliError::HttpClient)?;
+
+    let plan = BenchPlan {
+        requests: args
+            .requests
+            .map_or(DEFAULT_REQUEST_COUNT, core::num::NonZeroUsize::get),
+        duration: args
+            .duration
+            .map(|seconds| core::time::Duration::from_secs(seconds.get())),
+        concurrency: args.concurrency.get(),
+        rate: args.rate.map(core::num::NonZeroU32::get),
+        timeout: args.timeout.map(core::time::Duration::from_millis),
+    };
+
+    let uri = req.uri.to_string();
+
+    print_bench_start(
+        term,
+        req.method.as_str(),
+        &redact_secrets(&uri, &secrets),
+        &plan.description(),
+    )?;
+
+    let report = run_benchmark(&http_client, &req, &plan).await;
+
+    print_bench_report(term, &report)?;
+
+    Ok(())
+}
@@ -5,6 +5,7 @@ use crate::{
     error::HittCliError,
 };
 
+mod bench;
 mod completions;
 mod run;
 mod sse;
@@ -14,12 +15,14 @@ pub async fn execute_command<W: std::io::Write + Send>(term: &mut W) -> Result<(
     let cli = Cli::parse();
 
     match cli.command {
+        HittCommand::Bench(args) => bench::bench_command(term, &args).await,
+
         HittCommand::Completions(args) => {
             completions::completion_command(term, &args).map_err(HittCliError::Io)
         }
 
         HittCommand::Run(args) => run::run_command(term, &args).await,
 
-        HittCommand::ServerSentEvent(args) => sse::sse_command(term, args).await,
+        HittCommand::ServerSentEvent(args) => sse::sse_command(term, *args).await,
     }
 }
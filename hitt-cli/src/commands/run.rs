@@ -1,12 +1,33 @@
+use std::io::IsTerminal;
+
 use crossterm::{QueueableCommand, style::Print};
-use hitt_parser::HittRequest;
-use hitt_request::send_request;
+use futures::StreamExt;
+use hitt_parser::{HittRequest, ParseOptions, prompt::PromptDeclaration, value::VariableValue};
+use hitt_request::{
+    HittResponse, cookie::CookieJar, retry::RetryPolicy, send_request_streaming,
+    timing::TimingRecorder, tls::TlsOptions,
+};
 
 use crate::{
-    config::{RunCommandArguments, variables::parse_variable_argument},
+    config::{
+        RunCommandArguments, VariableArguments,
+        cookies::{configure_cookies, read_cookie_jar, write_cookie_jar},
+        proxy::configure_proxies,
+        tls::{build_tls_options, configure_tls, request_tls_options},
+        unix_socket::{configure_unix_socket, request_unix_socket},
+        variables::parse_variable_argument,
+    },
     error::HittCliError,
-    fs::{find_http_files, parse_files},
-    terminal::{handle_response, print_running_file},
+    fs::{
+        DiscoveryOptions, find_http_files, find_prompts, output_file_name, output_file_stem,
+        parse_files, read_variable_file, unique_file_stem, write_response,
+    },
+    terminal::{
+        body::StreamingBodyPrinter, content_type_header, finish_streamed_response, handle_response,
+        print_parse_warning, print_response_head, print_running_file, print_saved_response,
+        print_unsaved_response, prompt::prompt_variable, redact::redact_secrets,
+        status::print_retry,
+    },
 };
 
 #[inline]
@@ -49,11 +70,235 @@ mod test_build_variable_map {
     }
 }
 
+/// Collects the strings and numbers of a secret value, including the fields of objects and
+/// arrays, since each of them can be used on its own.
+#[inline]
+fn collect_secret_values(value: &VariableValue, secrets: &mut Vec<String>) {
+    match value {
+        VariableValue::Null | VariableValue::Bool(_) => {}
+        VariableValue::Number(number) => secrets.push(number.to_string()),
+        VariableValue::String(string) => {
+            if !string.is_empty() {
+                secrets.push(string.clone());
+            }
+        }
+        VariableValue::Array(items) => {
+            for item in items {
+                collect_secret_values(item, secrets);
+            }
+        }
+        VariableValue::Object(fields) => {
+            for field in fields.values() {
+                collect_secret_values(field, secrets);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_collect_secret_values {
+    use hitt_parser::value::VariableValue;
+
+    use super::collect_secret_values;
+
+    #[test]
+    fn it_should_collect_nested_values() {
+        let mut secrets = Vec::new();
+
+        collect_secret_values(
+            &VariableValue::from(serde_json::json!({
+                "token": "hunter2",
+                "pin": 1234,
+                "keys": ["abc", ""],
+                "enabled": true,
+                "expires": null
+            })),
+            &mut secrets,
+        );
+
+        secrets.sort_unstable();
+
+        assert_eq!(vec!["1234", "abc", "hunter2"], secrets);
+    }
+}
+
+/// Reads the variables passed using `--var-file`, `--secret-var-file`, `--var` and
+/// `--secret-var`.
+///
+/// The values of the secret variables are returned as well, so they can be redacted from the output.
+#[inline]
+pub async fn read_input_variables(
+    args: &VariableArguments,
+) -> Result<
+    (
+        std::collections::HashMap<String, VariableValue>,
+        Vec<String>,
+    ),
+    HittCliError,
+> {
+    let mut vars = std::collections::HashMap::new();
+
+    let mut input_secrets = Vec::new();
+
+    for path in args.var_file.iter().flatten() {
+        vars.extend(read_variable_file(path).await?);
+    }
+
+    for path in args.secret_var_file.iter().flatten() {
+        let secret_vars = read_variable_file(path).await?;
+
+        for value in secret_vars.values() {
+            collect_secret_values(value, &mut input_secrets);
+        }
+
+        vars.extend(secret_vars);
+    }
+
+    let secret_vars = build_variable_map(args.secret_var.as_ref())?;
+
+    input_secrets.extend(secret_vars.values().cloned());
+
+    vars.extend(
+        build_variable_map(args.var.as_ref())?
+            .into_iter()
+            .chain(secret_vars)
+            .map(|(name, value)| (name, VariableValue::from(value))),
+    );
+
+    Ok((vars, input_secrets))
+}
+
+#[cfg(test)]
+mod test_read_input_variables {
+    use super::read_input_variables;
+    use crate::config::VariableArguments;
+
+    #[tokio::test]
+    async fn it_should_return_secret_values() {
+        let args = VariableArguments {
+            var: Some(vec!["name=hougesen".to_owned()]),
+            secret_var: Some(vec!["token=hunter2".to_owned()]),
+            var_file: None,
+            secret_var_file: None,
+        };
+
+        let (vars, secrets) = read_input_variables(&args)
+            .await
+            .expect("it to read the variables");
+
+        assert_eq!(Some(&"hougesen".into()), vars.get("name"));
+        assert_eq!(Some(&"hunter2".into()), vars.get("token"));
+
+        assert_eq!(vec!["hunter2".to_owned()], secrets);
+    }
+
+    #[tokio::test]
+    async fn it_should_return_secret_file_values() {
+        let f = tempfile::Builder::new()
+            .prefix("hitt-")
+            .suffix(".json")
+            .tempfile()
+            .expect("it to create a file");
+
+        std::fs::write(f.path(), r#"{ "auth": { "token": "hunter2" } }"#)
+            .expect("it to write successfully");
+
+        let args = VariableArguments {
+            var: None,
+            secret_var: None,
+            var_file: None,
+            secret_var_file: Some(vec![f.path().to_path_buf()]),
+        };
+
+        let (vars, secrets) = read_input_variables(&args)
+            .await
+            .expect("it to read the variables");
+
+        assert!(vars.contains_key("auth"));
+
+        assert_eq!(vec!["hunter2".to_owned()], secrets);
+    }
+}
+
 #[inline]
-async fn get_requests(
+fn resolve_prompts<W: std::io::Write + Send>(
+    term: &mut W,
+    prompts: &[PromptDeclaration],
+    vars: &mut std::collections::HashMap<String, VariableValue>,
+    interactive: bool,
+) -> Result<(), HittCliError> {
+    for prompt in prompts {
+        if vars.contains_key(&prompt.name) {
+            continue;
+        }
+
+        if !interactive {
+            return Err(HittCliError::PromptNotInteractive(prompt.name.clone()));
+        }
+
+        let value = prompt_variable(term, prompt)?;
+
+        vars.insert(prompt.name.clone(), VariableValue::from(value));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_resolve_prompts {
+    use hitt_parser::prompt::PromptDeclaration;
+
+    use super::resolve_prompts;
+    use crate::error::HittCliError;
+
+    #[test]
+    fn it_should_skip_variables_passed_as_arguments() {
+        let prompts = [PromptDeclaration {
+            name: "otp".to_owned(),
+            description: Some("Enter the 2FA code".to_owned()),
+        }];
+
+        let mut vars = std::collections::HashMap::from([("otp".to_owned(), "123456".into())]);
+
+        let mut term = Vec::new();
+
+        resolve_prompts(&mut term, &prompts, &mut vars, false).expect("it not to prompt");
+
+        assert!(term.is_empty());
+
+        assert_eq!(vars.get("otp"), Some(&"123456".into()));
+    }
+
+    #[test]
+    fn it_should_reject_when_not_interactive() {
+        let prompts = [PromptDeclaration {
+            name: "otp".to_owned(),
+            description: None,
+        }];
+
+        let mut vars = std::collections::HashMap::new();
+
+        let mut term = Vec::new();
+
+        let error = resolve_prompts(&mut term, &prompts, &mut vars, false)
+            .expect_err("it to return an error");
+
+        assert_eq!(
+            "variable 'otp' must be prompted for, but the terminal is not interactive - pass it using '--var otp=<VALUE>'",
+            error.to_string()
+        );
+        assert!(matches!(error, HittCliError::PromptNotInteractive(name) if name == "otp"));
+    }
+}
+
+#[inline]
+pub async fn get_requests<W: std::io::Write + Send>(
+    term: &mut W,
     input_paths: &[std::path::PathBuf],
     recursive: bool,
-    var_input: Option<&Vec<String>>,
+    discovery_options: &DiscoveryOptions,
+    mut vars: std::collections::HashMap<String, VariableValue>,
+    options: &ParseOptions,
 ) -> Result<Vec<(std::path::PathBuf, Vec<HittRequest>)>, HittCliError> {
     let mut found_paths = Vec::new();
 
@@ -65,7 +310,7 @@ async fn get_requests(
                 return Err(HittCliError::RecursiveNotEnabled);
             }
 
-            for path in find_http_files(input_path) {
+            for path in find_http_files(input_path, discovery_options)? {
                 if !found_paths.contains(&path) {
                     found_paths.push(path);
                 }
@@ -75,12 +320,18 @@ async fn get_requests(
         }
     }
 
-    parse_files(found_paths, build_variable_map(var_input)?).await
+    let prompts = find_prompts(&found_paths).await?;
+
+    resolve_prompts(term, &prompts, &mut vars, std::io::stdin().is_terminal())?;
+
+    parse_files(found_paths, vars, options).await
 }
 
 #[cfg(test)]
 mod test_get_requests {
-    use crate::{commands::run::get_requests, error::HittCliError};
+    use hitt_parser::ParseOptions;
+
+    use crate::{commands::run::get_requests, error::HittCliError, fs::DiscoveryOptions};
 
     #[tokio::test]
     async fn it_should_return_a_list_of_requests() {
@@ -93,9 +344,16 @@ mod test_get_requests {
 
         std::fs::write(f.path(), "GET https://mhouge.dk/").expect("it to write successfully");
 
-        let files = get_requests(&[f.path().to_path_buf()], false, None)
-            .await
-            .expect("it to return a list of requests");
+        let files = get_requests(
+            &mut Vec::new(),
+            &[f.path().to_path_buf()],
+            false,
+            &DiscoveryOptions::default(),
+            std::collections::HashMap::new(),
+            &ParseOptions::default(),
+        )
+        .await
+        .expect("it to return a list of requests");
 
         assert_eq!(1, files.len());
 
@@ -124,9 +382,16 @@ mod test_get_requests {
 
         let p = dir.path();
 
-        let err = get_requests(&[p.to_path_buf()], false, None)
-            .await
-            .expect_err("expect it to return a missing recursive arg error");
+        let err = get_requests(
+            &mut Vec::new(),
+            &[p.to_path_buf()],
+            false,
+            &DiscoveryOptions::default(),
+            std::collections::HashMap::new(),
+            &ParseOptions::default(),
+        )
+        .await
+        .expect_err("expect it to return a missing recursive arg error");
 
         assert_eq!(
             "received directory path but --recursive is not enabled",
@@ -150,9 +415,16 @@ mod test_get_requests {
 
         std::fs::write(&file_path, "GET https://mhouge.dk/").expect("it to write successfully");
 
-        let files = get_requests(&[dir_path.to_path_buf()], true, None)
-            .await
-            .expect("it to return a list of requests");
+        let files = get_requests(
+            &mut Vec::new(),
+            &[dir_path.to_path_buf()],
+            true,
+            &DiscoveryOptions::default(),
+            std::collections::HashMap::new(),
+            &ParseOptions::default(),
+        )
+        .await
+        .expect("it to return a list of requests");
 
         assert_eq!(1, files.len());
 
@@ -174,49 +446,1072 @@ mod test_get_requests {
     }
 }
 
+/// Writes the response to the file of a `>>` line and to `--output-dir`.
+///
+/// Responses with an error status code are not written, unless it is forced using `>>!`, so a
+/// failing request does not replace a good fixture.
 #[inline]
-pub async fn run_command<W: std::io::Write + Send>(
+fn write_response_outputs<W: std::io::Write + Send>(
     term: &mut W,
+    http_file: &std::path::Path,
+    output_stem: &str,
+    req: &HittRequest,
+    response: &HittResponse,
     args: &RunCommandArguments,
+    secrets: &[String],
 ) -> Result<(), HittCliError> {
-    let http_client = reqwest::ClientBuilder::new()
-        .http09_responses()
+    let is_skipped = (response.status_code.is_client_error()
+        || response.status_code.is_server_error())
+        && !req.output.as_ref().is_some_and(|output| output.force);
+
+    let mut write_output = |path: &std::path::Path| -> Result<(), HittCliError> {
+        if is_skipped {
+            print_unsaved_response(term, path, response.status_code.as_u16())?;
+        } else {
+            write_response(path, response, args.output_headers, secrets)?;
+
+            print_saved_response(term, path)?;
+        }
+
+        Ok(())
+    };
+
+    if let Some(output) = &req.output {
+        // paths are relative to the file containing the request
+        write_output(
+            &http_file
+                .parent()
+                .unwrap_or_else(|| std::path::Path::new("."))
+                .join(&output.path),
+        )?;
+    }
+
+    if let Some(output_dir) = &args.output_dir {
+        let content_type = response
+            .headers
+            .get("content-type")
+            .and_then(|value| value.to_str().ok());
+
+        write_output(&output_dir.join(output_file_name(output_stem, content_type)))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_write_response_outputs {
+    use hitt_parser::output::ResponseOutput;
+    use hitt_request::HittResponse;
+    use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+
+    use super::write_response_outputs;
+    use crate::config::parse_run_arguments;
+
+    fn build_response(status_code: StatusCode) -> HittResponse {
+        HittResponse {
+            url: "https://mhouge.dk/users/1".to_owned(),
+            method: "GET".to_owned(),
+            status_code,
+            duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
+            headers: HeaderMap::from_iter([(
+                HeaderName::from_static("content-type"),
+                HeaderValue::from_static("application/json"),
+            )]),
+            http_version: http::Version::HTTP_11,
+            body: "{}".to_owned(),
+            raw_body: b"{}".to_vec(),
+            truncated: false,
+        }
+    }
+
+    fn build_request(output: Option<ResponseOutput>) -> hitt_parser::HittRequest {
+        hitt_parser::HittRequest {
+            method: http::Method::GET,
+            uri: http::Uri::from_static("https://mhouge.dk/users/1"),
+            headers: HeaderMap::new(),
+            body: None,
+            http_version: None,
+            secrets: Vec::new(),
+            warnings: Vec::new(),
+            name: Some("get-user".to_owned()),
+            max_redirects: None,
+            tls: hitt_parser::tls::RequestTls::default(),
+            unix_socket: None,
+            output,
+        }
+    }
+
+    #[test]
+    fn it_should_write_to_redirect_relative_to_http_file() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to return a valid dir");
+
+        let http_file = dir.path().join("users.http");
+
+        for (force, status_code, is_written) in [
+            (false, StatusCode::OK, true),
+            (false, StatusCode::INTERNAL_SERVER_ERROR, false),
+            (true, StatusCode::NOT_FOUND, true),
+        ] {
+            let output_path = dir.path().join("out/user.json");
+
+            if std::fs::exists(&output_path).expect("it to check the path") {
+                std::fs::remove_file(&output_path).expect("it to remove the file");
+            }
+
+            let req = build_request(Some(ResponseOutput {
+                path: std::path::PathBuf::from("./out/user.json"),
+                force,
+            }));
+
+            let mut term = Vec::new();
+
+            write_response_outputs(
+                &mut term,
+                &http_file,
+                "get-user",
+                &req,
+                &build_response(status_code),
+                &parse_run_arguments(&["requests.http"]),
+                &[],
+            )
+            .expect("it not to return an error");
+
+            assert_eq!(
+                std::fs::exists(&output_path).expect("it to check the path"),
+                is_written
+            );
+
+            assert_eq!(
+                String::from_utf8_lossy(&term).contains("hitt: response not saved to"),
+                !is_written
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_write_to_output_dir() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to return a valid dir");
+
+        let path = dir.path().join("get-user.json");
+
+        let mut term = Vec::new();
+
+        write_response_outputs(
+            &mut term,
+            std::path::Path::new("users.http"),
+            "get-user",
+            &build_request(None),
+            &build_response(StatusCode::INTERNAL_SERVER_ERROR),
+            &parse_run_arguments(&[
+                "--output-dir",
+                dir.path().to_str().expect("it to be a valid path"),
+                "requests.http",
+            ]),
+            &[],
+        )
+        .expect("it not to return an error");
+
+        assert!(!std::fs::exists(&path).expect("it to check the path"));
+
+        assert!(String::from_utf8_lossy(&term).contains(&format!(
+            "hitt: response not saved to '{}' - status code 500 is an error",
+            path.display()
+        )));
+
+        let mut term = Vec::new();
+
+        write_response_outputs(
+            &mut term,
+            std::path::Path::new("users.http"),
+            "get-user",
+            &build_request(None),
+            &build_response(StatusCode::OK),
+            &parse_run_arguments(&[
+                "--output-dir",
+                dir.path().to_str().expect("it to be a valid path"),
+                "requests.http",
+            ]),
+            &[],
+        )
+        .expect("it not to return an error");
+
+        assert_eq!(std::fs::read(&path).expect("it to read the file"), b"{}");
+
+        assert!(
+            String::from_utf8_lossy(&term)
+                .contains(&format!("hitt: saved response to '{}'", path.display()))
+        );
+    }
+}
+
+#[inline]
+fn build_http_client(
+    args: &RunCommandArguments,
+    tls_options: &TlsOptions,
+    unix_socket: Option<&std::path::Path>,
+    cookie_jar: Option<&std::sync::Arc<CookieJar>>,
+    timing_recorder: Option<&TimingRecorder>,
+) -> Result<reqwest::Client, HittCliError> {
+    // redirects are followed by send_request, so every hop can be printed
+    let client_builder = configure_cookies(
+        configure_unix_socket(
+            configure_proxies(
+                reqwest::ClientBuilder::new()
+                    .http09_responses()
+                    .redirect(reqwest::redirect::Policy::none()),
+                &args.proxy,
+            )?,
+            unix_socket,
+        )?,
+        cookie_jar,
+    );
+
+    configure_tls(client_builder, tls_options, timing_recorder)?
         .build()
-        .unwrap_or_else(|_| reqwest::Client::new());
+        .map_err(HittCliError::HttpClient)
+}
 
-    let timeout = args.timeout.map(core::time::Duration::from_millis);
+/// State shared by every request of a run.
+struct RunContext<'a> {
+    args: &'a RunCommandArguments,
+    timing_recorder: Option<TimingRecorder>,
+    timeout: Option<core::time::Duration>,
+    max_redirects: usize,
+    retry_policy: RetryPolicy,
+    input_secrets: Vec<String>,
+}
 
-    let mut request_count: u16 = 0;
+/// Requests of a single file that are sent one after another, sharing their output.
+struct RequestGroup {
+    path: std::path::PathBuf,
+    /// Number of requests of the run before the group.
+    request_count: usize,
+    /// The requests, their client and the file name stem used for `--output-dir`.
+    requests: Vec<(HittRequest, reqwest::Client, String)>,
+}
 
-    for (path, file) in get_requests(&args.paths, args.recursive, args.var.as_ref()).await? {
-        if !args.vim {
-            if request_count > 0 {
-                term.queue(Print('\n'))?;
+#[inline]
+async fn run_request<W: std::io::Write + Send>(
+    term: &mut W,
+    context: &RunContext<'_>,
+    path: &std::path::Path,
+    output_stem: &str,
+    req: HittRequest,
+    http_client: &reqwest::Client,
+) -> Result<(), HittCliError> {
+    let args = context.args;
+
+    for warning in &req.warnings {
+        print_parse_warning(term, warning)?;
+    }
+
+    let mut secrets = req.secrets.clone();
+    secrets.extend_from_slice(&context.input_secrets);
+
+    let mut retry_output = Ok(());
+
+    let result = send_request_streaming(
+        http_client,
+        &req,
+        context.timeout.as_ref(),
+        context.timing_recorder.as_ref(),
+        context.max_redirects,
+        &context.retry_policy,
+        |attempt| {
+            if retry_output.is_ok() {
+                retry_output = print_retry(term, attempt, &secrets);
             }
+        },
+    )
+    .await;
+
+    retry_output?;
 
-            print_running_file(term, &path)?;
+    let result = match result {
+        Ok(streaming) if args.stream => {
+            print_response_head(term, &streaming.response, args, &secrets)?;
 
             term.flush()?;
+
+            let mut printer = (!args.hide_body).then(|| {
+                StreamingBodyPrinter::new(
+                    content_type_header(&streaming.response),
+                    args.disable_formatting,
+                    &secrets,
+                )
+            });
+
+            let mut body_output = Ok(());
+
+            let result = streaming
+                .read_body(args.max_body_size, |chunk| {
+                    if let Some(printer) = printer.as_mut()
+                        && body_output.is_ok()
+                    {
+                        body_output = printer.write_chunk(term, chunk);
+                    }
+                })
+                .await;
+
+            body_output?;
+
+            match result {
+                Ok(response) => {
+                    finish_streamed_response(term, &response, args, printer)?;
+
+                    Ok(response)
+                }
+                Err(error) => {
+                    if let Some(printer) = printer {
+                        printer.finish(term)?;
+                    }
+
+                    Err(error)
+                }
+            }
+        }
+        Ok(streaming) => streaming.read_body(args.max_body_size, |_| {}).await,
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(response) => {
+            let result = if args.stream {
+                Ok(())
+            } else {
+                handle_response(term, &response, args, &secrets)
+            };
+
+            write_response_outputs(term, path, output_stem, &req, &response, args, &secrets)?;
+
+            result
         }
+        Err(request_error) => {
+            let uri = req.uri.to_string();
 
-        for req in file {
-            if !args.vim || request_count != 0 {
-                term.queue(Print('\n'))?;
+            let redacted_uri = redact_secrets(&uri, &secrets);
+
+            // the error message includes the url, which might contain a secret
+            let error = if redacted_uri == uri {
+                request_error
+            } else {
+                request_error.without_url()
+            };
+
+            if error.is_timeout() {
+                return Err(HittCliError::RequestTimeout(
+                    req.method,
+                    redacted_uri.into_owned(),
+                ));
             }
 
-            match send_request(&http_client, &req, timeout.as_ref()).await {
-                Ok(response) => handle_response(term, &response, args),
-                Err(request_error) => {
-                    if request_error.is_timeout() {
-                        return Err(HittCliError::RequestTimeout(req.method, req.uri));
-                    }
+            Err(HittCliError::Reqwest(
+                req.method,
+                redacted_uri.into_owned(),
+                error,
+            ))
+        }
+    }
+}
+
+#[inline]
+async fn run_group<W: std::io::Write + Send>(
+    term: &mut W,
+    context: &RunContext<'_>,
+    group: RequestGroup,
+) -> Result<(), HittCliError> {
+    let vim = context.args.vim;
+
+    if !vim {
+        if group.request_count > 0 {
+            term.queue(Print('\n'))?;
+        }
+
+        print_running_file(term, &group.path)?;
+
+        term.flush()?;
+    }
+
+    for (offset, (req, http_client, output_stem)) in group.requests.into_iter().enumerate() {
+        if !vim || group.request_count + offset != 0 {
+            term.queue(Print('\n'))?;
+        }
+
+        run_request(term, context, &group.path, &output_stem, req, &http_client).await?;
+    }
 
-                    Err(HittCliError::Reqwest(req.method, req.uri, request_error))
+    Ok(())
+}
+
+/// Groups the requests of every file, so files can run at the same time while the requests
+/// within a file are sent in order.
+///
+/// Every request is paired with a client using its tls options and unix socket, sharing the
+/// cookie jar, and a file name stem for `--output-dir` that is unique within the run. The stems
+/// are picked in file order, so they do not depend on `--parallel`.
+#[inline]
+fn build_request_groups(
+    files: Vec<(std::path::PathBuf, Vec<HittRequest>)>,
+    args: &RunCommandArguments,
+    tls_options: &TlsOptions,
+    http_client: reqwest::Client,
+    cookie_jar: Option<&std::sync::Arc<CookieJar>>,
+    timing_recorder: Option<&TimingRecorder>,
+) -> Result<Vec<RequestGroup>, HittCliError> {
+    // requests overriding the tls options or unix socket using directives get a client of their own
+    let mut clients = std::collections::HashMap::from([(
+        (tls_options.clone(), args.unix_socket.clone()),
+        http_client,
+    )]);
+
+    let mut groups = Vec::new();
+
+    let mut request_count = 0;
+
+    let mut output_stems = std::collections::HashSet::new();
+
+    for (path, file) in files {
+        let mut requests = Vec::with_capacity(file.len());
+
+        for (request_index, req) in file.into_iter().enumerate() {
+            let client_key = (
+                request_tls_options(tls_options, &req.tls, &path),
+                request_unix_socket(
+                    args.unix_socket.as_deref(),
+                    req.unix_socket.as_deref(),
+                    &path,
+                ),
+            );
+
+            let client = match clients.get(&client_key) {
+                Some(client) => client.clone(),
+                None => {
+                    let client = build_http_client(
+                        args,
+                        &client_key.0,
+                        client_key.1.as_deref(),
+                        cookie_jar,
+                        timing_recorder,
+                    )?;
+
+                    clients.insert(client_key, client.clone());
+
+                    client
                 }
-            }?;
-            request_count += 1;
+            };
+
+            let output_stem = unique_file_stem(
+                &mut output_stems,
+                output_file_stem(req.name.as_deref(), &path, request_index),
+            );
+
+            requests.push((req, client, output_stem));
+        }
+
+        let file_request_count = requests.len();
+
+        groups.push(RequestGroup {
+            path,
+            request_count,
+            requests,
+        });
+
+        request_count += file_request_count;
+    }
+
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod test_build_request_groups {
+    use hitt_request::tls::TlsOptions;
+
+    use super::build_request_groups;
+    use crate::config::parse_run_arguments;
+
+    fn build_requests(count: usize) -> Vec<hitt_parser::HittRequest> {
+        (0..count)
+            .map(|index| hitt_parser::HittRequest {
+                method: http::Method::GET,
+                uri: http::Uri::try_from(format!("https://mhouge.dk/{index}"))
+                    .expect("it to be a valid uri"),
+                headers: http::HeaderMap::new(),
+                body: None,
+                http_version: None,
+                secrets: Vec::new(),
+                warnings: Vec::new(),
+                name: None,
+                max_redirects: None,
+                tls: hitt_parser::tls::RequestTls::default(),
+                unix_socket: None,
+                output: None,
+            })
+            .collect()
+    }
+
+    fn build_files() -> Vec<(std::path::PathBuf, Vec<hitt_parser::HittRequest>)> {
+        vec![
+            (std::path::PathBuf::from("a.http"), build_requests(2)),
+            (std::path::PathBuf::from("b.http"), build_requests(0)),
+            (std::path::PathBuf::from("c.http"), build_requests(3)),
+        ]
+    }
+
+    #[test]
+    fn it_should_group_requests_by_file() {
+        let groups = build_request_groups(
+            build_files(),
+            &parse_run_arguments(&["--parallel", "4", "requests.http"]),
+            &TlsOptions::default(),
+            reqwest::Client::new(),
+            None,
+            None,
+        )
+        .expect("it to build the groups");
+
+        assert_eq!(
+            vec![("a.http", 0, 2), ("b.http", 2, 0), ("c.http", 2, 3)],
+            groups
+                .iter()
+                .map(|group| (
+                    group.path.to_str().expect("it to be a valid path"),
+                    group.request_count,
+                    group.requests.len()
+                ))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_should_pick_unique_output_stems() {
+        let mut named_requests = build_requests(2);
+
+        for req in &mut named_requests {
+            req.name = Some("get-user".to_owned());
+        }
+
+        let groups = build_request_groups(
+            vec![
+                (std::path::PathBuf::from("a/users.http"), build_requests(2)),
+                (std::path::PathBuf::from("b/users.http"), build_requests(1)),
+                (std::path::PathBuf::from("c/named.http"), named_requests),
+            ],
+            &parse_run_arguments(&["--parallel", "4", "requests.http"]),
+            &TlsOptions::default(),
+            reqwest::Client::new(),
+            None,
+            None,
+        )
+        .expect("it to build the groups");
+
+        assert_eq!(
+            vec!["users-1", "users-2", "users-1-2", "get-user", "get-user-2"],
+            groups
+                .iter()
+                .flat_map(|group| group.requests.iter())
+                .map(|(_, _, output_stem)| output_stem.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+#[inline]
+pub async fn run_command<W: std::io::Write + Send>(
+    term: &mut W,
+    args: &RunCommandArguments,
+) -> Result<(), HittCliError> {
+    let timing_recorder = args.timings.then(TimingRecorder::new);
+
+    let tls_options = build_tls_options(&args.tls);
+
+    let cookie_jar = read_cookie_jar(args).await?;
+
+    let http_client = build_http_client(
+        args,
+        &tls_options,
+        args.unix_socket.as_deref(),
+        cookie_jar.as_ref(),
+        timing_recorder.as_ref(),
+    )?;
+
+    let timeout = args.timeout.map(core::time::Duration::from_millis);
+
+    let max_redirects = if args.no_follow {
+        0
+    } else {
+        args.max_redirects
+    };
+
+    let retry_policy = RetryPolicy {
+        retries: args.retries,
+        status_codes: args
+            .retry_status
+            .iter()
+            .filter_map(|status_code| reqwest::StatusCode::from_u16(*status_code).ok())
+            .collect(),
+        ..RetryPolicy::default()
+    };
+
+    let (vars, input_secrets) = read_input_variables(&args.variables).await?;
+
+    let parse_options = ParseOptions {
+        override_variables: args.var_override,
+        strict: args.strict,
+        base_url: args.base_url.clone(),
+    };
+
+    let discovery_options = DiscoveryOptions {
+        extensions: args.extension.clone(),
+        include: args.include.clone(),
+        exclude: args.exclude.clone(),
+        markdown: args.markdown,
+    };
+
+    let files = get_requests(
+        term,
+        &args.paths,
+        args.recursive,
+        &discovery_options,
+        vars,
+        &parse_options,
+    )
+    .await?;
+
+    let groups = build_request_groups(
+        files,
+        args,
+        &tls_options,
+        http_client,
+        cookie_jar.as_ref(),
+        timing_recorder.as_ref(),
+    )?;
+
+    // bodies are printed as they arrive, which only works while a single file is running
+    if args.stream && args.parallel.get() > 1 && groups.len() > 1 {
+        return Err(HittCliError::StreamParallel);
+    }
+
+    let context = RunContext {
+        args,
+        timing_recorder,
+        timeout,
+        max_redirects,
+        retry_policy,
+        input_secrets,
+    };
+
+    let result = run_groups(term, &context, groups).await;
+
+    // cookies are written even if the run failed, so a successful login is kept
+    if let (Some(path), Some(jar)) = (&args.cookie_jar, &cookie_jar) {
+        write_cookie_jar(path, jar).await?;
+    }
+
+    result
+}
+
+#[inline]
+async fn run_groups<W: std::io::Write + Send>(
+    term: &mut W,
+    context: &RunContext<'_>,
+    groups: Vec<RequestGroup>,
+) -> Result<(), HittCliError> {
+    if context.args.parallel.get() == 1 || groups.len() == 1 {
+        for group in groups {
+            run_group(term, context, group).await?;
         }
+
+        return Ok(());
+    }
+
+    // the output of every group is buffered, so it can be printed in order
+    let mut outputs = futures::stream::iter(groups)
+        .map(|group| async {
+            let mut output = Vec::new();
+
+            let result = run_group(&mut output, context, group).await;
+
+            (output, result)
+        })
+        .buffered(context.args.parallel.get());
+
+    while let Some((output, result)) = outputs.next().await {
+        term.write_all(&output)?;
+        term.flush()?;
+
+        // returning drops the groups that are still running
+        result?;
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod test_run_command {
+    use std::io::{BufRead, Write};
+
+    use super::run_command;
+
+    /// Serves `/slow` only after `/fast` has been requested, which requires the requests to be
+    /// sent at the same time.
+    fn start_server() -> std::net::SocketAddr {
+        let listener =
+            std::net::TcpListener::bind("127.0.0.1:0").expect("it to bind to a local port");
+
+        let addr = listener.local_addr().expect("it to have a local address");
+
+        let (sender, receiver) = std::sync::mpsc::channel::<()>();
+        let receiver = std::sync::Arc::new(std::sync::Mutex::new(receiver));
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                let receiver = std::sync::Arc::clone(&receiver);
+
+                std::thread::spawn(move || {
+                    let mut reader = std::io::BufReader::new(&stream);
+
+                    let mut request_line = String::new();
+                    reader
+                        .read_line(&mut request_line)
+                        .expect("it to read the request");
+
+                    let mut line = String::new();
+                    while reader.read_line(&mut line).is_ok_and(|len| len > 2) {
+                        line.clear();
+                    }
+
+                    let status = if request_line.contains("/slow") {
+                        let is_concurrent = receiver
+                            .lock()
+                            .expect("it to lock the receiver")
+                            .recv_timeout(core::time::Duration::from_secs(5))
+                            .is_ok();
+
+                        if is_concurrent {
+                            "200 OK"
+                        } else {
+                            "500 Internal Server Error"
+                        }
+                    } else {
+                        let _ = sender.send(());
+
+                        "200 OK"
+                    };
+
+                    let _ = (&stream).write_all(
+                        format!(
+                            "HTTP/1.1 {status}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"
+                        )
+                        .as_bytes(),
+                    );
+                });
+            }
+        });
+
+        addr
+    }
+
+    #[tokio::test]
+    async fn it_should_print_parallel_files_in_order() {
+        let addr = start_server();
+
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to create a dir");
+
+        let slow_path = dir.path().join("a.http");
+        let fast_path = dir.path().join("b.http");
+
+        std::fs::write(&slow_path, format!("GET http://{addr}/slow"))
+            .expect("it to write successfully");
+        std::fs::write(&fast_path, format!("GET http://{addr}/fast"))
+            .expect("it to write successfully");
+
+        let args = crate::config::parse_run_arguments(&[
+            "--parallel",
+            "2",
+            "--fail-fast",
+            slow_path.to_str().expect("it to be a valid path"),
+            fast_path.to_str().expect("it to be a valid path"),
+        ]);
+
+        let mut term = Vec::new();
+
+        run_command(&mut term, &args)
+            .await
+            .expect("it to send the requests at the same time");
+
+        let output = String::from_utf8_lossy(&term);
+
+        let slow_index = output.find("/slow").expect("it to print the slow request");
+        let fast_index = output.find("/fast").expect("it to print the fast request");
+
+        assert!(slow_index < fast_index);
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn it_should_send_requests_over_unix_sockets() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to create a dir");
+
+        let listener = std::os::unix::net::UnixListener::bind(dir.path().join("api.sock"))
+            .expect("it to bind to the unix socket");
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = std::io::BufReader::new(&stream);
+
+                let mut request_line = String::new();
+                reader
+                    .read_line(&mut request_line)
+                    .expect("it to read the request");
+
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|len| len > 2) {
+                    line.clear();
+                }
+
+                let body = request_line.trim_end();
+
+                let _ = (&stream).write_all(
+                    format!(
+                        "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                        body.len()
+                    )
+                    .as_bytes(),
+                );
+            }
+        });
+
+        let path = dir.path().join("socket.http");
+
+        std::fs::write(
+            &path,
+            "# @unix-socket api.sock\nGET http://localhost/containers/json",
+        )
+        .expect("it to write successfully");
+
+        let args = crate::config::parse_run_arguments(&[
+            "--fail-fast",
+            path.to_str().expect("it to be a valid path"),
+        ]);
+
+        let mut term = Vec::new();
+
+        run_command(&mut term, &args)
+            .await
+            .expect("it to send the request over the unix socket");
+
+        assert!(String::from_utf8_lossy(&term).contains("GET /containers/json HTTP/1.1"));
+    }
+
+    /// Sets a cookie on `/login`, and echoes the cookie header of every other request.
+    fn start_cookie_server() -> std::net::SocketAddr {
+        let listener =
+            std::net::TcpListener::bind("127.0.0.1:0").expect("it to bind to a local port");
+
+        let addr = listener.local_addr().expect("it to have a local address");
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = std::io::BufReader::new(&stream);
+
+                let mut request_line = String::new();
+                reader
+                    .read_line(&mut request_line)
+                    .expect("it to read the request");
+
+                let mut cookie = String::new();
+
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|len| len > 2) {
+                    if let Some(value) = line.to_lowercase().strip_prefix("cookie: ") {
+                        cookie = value.trim().to_owned();
+                    }
+
+                    line.clear();
+                }
+
+                let response = if request_line.contains("/login") {
+                    "HTTP/1.1 204 No Content\r\nset-cookie: session=abc123; Path=/; HttpOnly\r\nconnection: close\r\n\r\n".to_owned()
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{cookie}",
+                        cookie.len()
+                    )
+                };
+
+                let _ = (&stream).write_all(response.as_bytes());
+            }
+        });
+
+        addr
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_cookies_between_requests_and_runs() {
+        let addr = start_cookie_server();
+
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to create a dir");
+
+        let login_path = dir.path().join("login.http");
+        let me_path = dir.path().join("me.http");
+        let jar_path = dir.path().join("cookies.txt");
+
+        std::fs::write(
+            &login_path,
+            format!("POST http://{addr}/login\n\n###\n\nGET http://{addr}/me"),
+        )
+        .expect("it to write successfully");
+        std::fs::write(&me_path, format!("GET http://{addr}/me"))
+            .expect("it to write successfully");
+
+        let build_args = |path: &std::path::Path, no_cookies: bool| {
+            let mut command = Vec::new();
+
+            if no_cookies {
+                command.push("--no-cookies");
+            } else {
+                command.extend([
+                    "--cookie-jar",
+                    jar_path.to_str().expect("it to be a valid path"),
+                ]);
+            }
+
+            command.push(path.to_str().expect("it to be a valid path"));
+
+            crate::config::parse_run_arguments(&command)
+        };
+
+        let mut term = Vec::new();
+
+        run_command(&mut term, &build_args(&login_path, false))
+            .await
+            .expect("it to send the requests");
+
+        let output = String::from_utf8_lossy(&term);
+
+        assert!(output.contains("cookie\u{1b}[39m session=<redacted>; Path=/; HttpOnly\n"));
+        assert!(output.contains("session=abc123"));
+
+        assert!(
+            std::fs::read_to_string(&jar_path)
+                .expect("it to write the cookie jar")
+                .contains("#HttpOnly_127.0.0.1\tFALSE\t/\tFALSE\t0\tsession\tabc123\n")
+        );
+
+        let mut term = Vec::new();
+
+        run_command(&mut term, &build_args(&me_path, false))
+            .await
+            .expect("it to send the request");
+
+        assert!(String::from_utf8_lossy(&term).contains("session=abc123"));
+
+        let mut term = Vec::new();
+
+        run_command(&mut term, &build_args(&login_path, true))
+            .await
+            .expect("it to send the requests");
+
+        assert!(!String::from_utf8_lossy(&term).contains("session=abc123"));
+    }
+
+    #[test]
+    fn it_should_allow_parallel_timings() {
+        use clap::Parser;
+
+        crate::config::Cli::try_parse_from([
+            "hitt",
+            "run",
+            "--parallel",
+            "2",
+            "--timings",
+            "requests.http",
+        ])
+        .expect("it to accept the arguments");
+    }
+
+    #[tokio::test]
+    async fn it_should_only_reject_stream_when_running_files_in_parallel() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to create a dir");
+
+        let first_path = dir.path().join("a.http");
+        let second_path = dir.path().join("b.http");
+
+        std::fs::write(&first_path, "GET http://localhost/a").expect("it to write successfully");
+        std::fs::write(&second_path, "GET http://localhost/b").expect("it to write successfully");
+
+        let first_path = first_path.to_str().expect("it to be a valid path");
+        let second_path = second_path.to_str().expect("it to be a valid path");
+
+        let error = run_command(
+            &mut Vec::new(),
+            &crate::config::parse_run_arguments(&[
+                "--stream",
+                "--parallel",
+                "2",
+                first_path,
+                second_path,
+            ]),
+        )
+        .await
+        .expect_err("it to reject streaming parallel files");
+
+        assert!(matches!(error, crate::error::HittCliError::StreamParallel));
+
+        let args = crate::config::parse_run_arguments(&[
+            "--stream",
+            "--parallel",
+            "1",
+            first_path,
+            second_path,
+        ]);
+
+        assert!(args.stream);
+        assert_eq!(1, args.parallel.get());
+    }
+
+    #[test]
+    fn it_should_reject_save_binary_with_output_dir() {
+        use clap::Parser;
+
+        let error = crate::config::Cli::try_parse_from([
+            "hitt",
+            "run",
+            "--save-binary",
+            "downloads",
+            "--output-dir",
+            "responses",
+            "requests.http",
+        ])
+        .expect_err("it to reject the arguments");
+
+        assert_eq!(clap::error::ErrorKind::ArgumentConflict, error.kind());
+    }
+}
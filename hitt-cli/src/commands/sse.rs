@@ -2,7 +2,12 @@ use hitt_formatter::ContentType;
 use tokio::sync::mpsc::unbounded_channel;
 
 use crate::{
-    config::SSECommandArguments,
+    config::{
+        SSECommandArguments,
+        proxy::configure_proxies,
+        tls::{build_tls_options, configure_tls},
+        unix_socket::configure_unix_socket,
+    },
     error::HittCliError,
     terminal::{self, sse::print_sse_connection_open},
 };
@@ -14,9 +19,21 @@ pub async fn sse_command<W: std::io::Write + Send>(
 ) -> Result<(), HittCliError> {
     match reqwest::Url::parse(&args.url) {
         Ok(sse_url) => {
+            let http_client = configure_tls(
+                configure_unix_socket(
+                    configure_proxies(reqwest::ClientBuilder::new(), &args.proxy)?,
+                    args.unix_socket.as_deref(),
+                )?,
+                &build_tls_options(&args.tls),
+                None,
+            )?
+            .build()
+            .map_err(HittCliError::HttpClient)?;
+
             let (tx, mut rx) = unbounded_channel::<hitt_sse::Event>();
 
-            let _sse_listener = tokio::spawn(async move { hitt_sse::start_sse(sse_url, tx).await });
+            let _sse_listener =
+                tokio::spawn(async move { hitt_sse::start_sse(http_client, sse_url, tx).await });
 
             while let Some(sse_event) = rx.recv().await {
                 match sse_event {
@@ -40,7 +57,10 @@ pub async fn sse_command<W: std::io::Write + Send>(
 
 #[cfg(test)]
 mod test_sse_command {
-    use crate::{config::SSECommandArguments, error::HittCliError};
+    use crate::{
+        config::{ProxyArguments, SSECommandArguments, TlsArguments},
+        error::HittCliError,
+    };
 
     #[tokio::test]
     async fn it_should_reject_invalid_urls() {
@@ -52,6 +72,9 @@ mod test_sse_command {
             &mut buffer,
             SSECommandArguments {
                 url: url.to_string(),
+                proxy: ProxyArguments::default(),
+                tls: TlsArguments::default(),
+                unix_socket: None,
             },
         )
         .await
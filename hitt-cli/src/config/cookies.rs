@@ -0,0 +1,167 @@
+use hitt_request::cookie::CookieJar;
+
+use crate::{config::RunCommandArguments, error::HittCliError};
+
+/// Builds the cookie jar of a run, reading `--cookie-jar` if the file exists.
+///
+/// Returns `None` if cookies are disabled using `--no-cookies`.
+#[inline]
+pub async fn read_cookie_jar(
+    args: &RunCommandArguments,
+) -> Result<Option<std::sync::Arc<CookieJar>>, HittCliError> {
+    if args.no_cookies {
+        return Ok(None);
+    }
+
+    let Some(path) = &args.cookie_jar else {
+        return Ok(Some(std::sync::Arc::default()));
+    };
+
+    let input = match tokio::fs::read_to_string(path).await {
+        Ok(input) => input,
+        // the file is created when the cookies are written
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(HittCliError::IoRead(path.clone(), error)),
+    };
+
+    CookieJar::from_netscape(&input)
+        .map(|jar| Some(std::sync::Arc::new(jar)))
+        .map_err(|error| HittCliError::CookieJar(path.clone(), error))
+}
+
+#[cfg(test)]
+mod test_read_cookie_jar {
+    use reqwest::cookie::CookieStore;
+
+    use super::read_cookie_jar;
+    use crate::{config::parse_run_arguments, error::HittCliError};
+
+    #[tokio::test]
+    async fn it_should_enable_cookies_by_default() {
+        assert!(
+            read_cookie_jar(&parse_run_arguments(&["requests.http"]))
+                .await
+                .expect("it to build a cookie jar")
+                .is_some()
+        );
+
+        assert!(
+            read_cookie_jar(&parse_run_arguments(&["--no-cookies", "requests.http"]))
+                .await
+                .expect("it to disable cookies")
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_read_cookie_files() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to create a dir");
+
+        let path = dir.path().join("cookies.txt");
+        let path_str = path.to_str().expect("it to be a valid path");
+
+        let jar = read_cookie_jar(&parse_run_arguments(&[
+            "--cookie-jar",
+            path_str,
+            "requests.http",
+        ]))
+        .await
+        .expect("it to accept missing files")
+        .expect("it to build a cookie jar");
+
+        assert_eq!(
+            None,
+            jar.cookies(&reqwest::Url::parse("http://localhost/").expect("it to be a valid url"))
+        );
+
+        std::fs::write(&path, "localhost\tFALSE\t/\tFALSE\t0\tsession\tabc\n")
+            .expect("it to write successfully");
+
+        let jar = read_cookie_jar(&parse_run_arguments(&[
+            "--cookie-jar",
+            path_str,
+            "requests.http",
+        ]))
+        .await
+        .expect("it to read the file")
+        .expect("it to build a cookie jar");
+
+        assert_eq!(
+            Some(reqwest::header::HeaderValue::from_static("session=abc")),
+            jar.cookies(&reqwest::Url::parse("http://localhost/").expect("it to be a valid url"))
+        );
+
+        std::fs::write(&path, "not a cookie\n").expect("it to write successfully");
+
+        let error = read_cookie_jar(&parse_run_arguments(&[
+            "--cookie-jar",
+            path_str,
+            "requests.http",
+        ]))
+        .await
+        .expect_err("it to reject the file");
+
+        assert_eq!(
+            format!(
+                "error reading cookie jar '{}' - line 1 is not a cookie in the Netscape format",
+                path.display()
+            ),
+            error.to_string()
+        );
+        assert!(matches!(error, HittCliError::CookieJar(_, _)));
+    }
+}
+
+#[inline]
+pub async fn write_cookie_jar(path: &std::path::Path, jar: &CookieJar) -> Result<(), HittCliError> {
+    tokio::fs::write(path, jar.to_netscape())
+        .await
+        .map_err(|error| HittCliError::IoWrite(path.to_path_buf(), error))
+}
+
+#[cfg(test)]
+mod test_write_cookie_jar {
+    use hitt_request::cookie::CookieJar;
+
+    use super::write_cookie_jar;
+
+    #[tokio::test]
+    async fn it_should_write_netscape_files() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to create a dir");
+
+        let path = dir.path().join("cookies.txt");
+
+        let jar = CookieJar::from_netscape("localhost\tFALSE\t/\tFALSE\t0\tsession\tabc\n")
+            .expect("it to read the cookies");
+
+        write_cookie_jar(&path, &jar)
+            .await
+            .expect("it to write the cookies");
+
+        assert!(
+            std::fs::read_to_string(&path)
+                .expect("it to read the file")
+                .ends_with("\nlocalhost\tFALSE\t/\tFALSE\t0\tsession\tabc\n")
+        );
+    }
+}
+
+/// Stores and sends cookies using the jar, if cookies are enabled.
+#[inline]
+pub fn configure_cookies(
+    builder: reqwest::ClientBuilder,
+    jar: Option<&std::sync::Arc<CookieJar>>,
+) -> reqwest::ClientBuilder {
+    match jar {
+        Some(jar) => builder.cookie_provider(std::sync::Arc::clone(jar)),
+        None => builder,
+    }
+}
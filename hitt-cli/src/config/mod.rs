@@ -1,5 +1,10 @@
 use clap::{Args, Parser, Subcommand};
 
+pub mod cookies;
+pub mod proxy;
+pub mod size;
+pub mod tls;
+pub mod unix_socket;
 pub mod variables;
 
 const HELP_TEMPLATE: &str = "\
@@ -20,10 +25,12 @@ pub struct Cli {
 
 #[derive(Subcommand, Debug)]
 pub enum HittCommand {
-    Run(RunCommandArguments),
+    Run(Box<RunCommandArguments>),
+
+    Bench(Box<BenchCommandArguments>),
 
     #[command(name = "sse")]
-    ServerSentEvent(SSECommandArguments),
+    ServerSentEvent(Box<SSECommandArguments>),
 
     Completions(CompletionsCommandArguments),
 }
@@ -40,14 +47,90 @@ pub struct RunCommandArguments {
     #[arg(long, value_name = "TIMEOUT_MS")]
     pub timeout: Option<u64>,
 
-    /// Variables to pass to request
-    #[arg(long, value_name = "KEY>=<VALUE")]
-    pub var: Option<Vec<String>>,
+    #[command(flatten)]
+    pub variables: VariableArguments,
+
+    /// Let variables passed using --var, --secret-var, --var-file and --secret-var-file overwrite variables declared in .http files
+    #[arg(long, default_value_t = false)]
+    pub var_override: bool,
+
+    /// Reject unknown HTTP versions and unexpected input on the request line
+    #[arg(long, default_value_t = false)]
+    pub strict: bool,
+
+    #[command(flatten)]
+    pub proxy: ProxyArguments,
+
+    #[command(flatten)]
+    pub tls: TlsArguments,
+
+    /// Connect through a unix domain socket instead of tcp, keeping the host and path of the url
+    #[arg(long, value_name = "PATH")]
+    pub unix_socket: Option<std::path::PathBuf>,
+
+    /// Maximum number of redirects to follow
+    #[arg(long, value_name = "N", default_value_t = hitt_request::redirect::DEFAULT_MAX_REDIRECTS)]
+    pub max_redirects: usize,
+
+    /// Do not follow redirects
+    #[arg(long, default_value_t = false, conflicts_with = "max_redirects")]
+    pub no_follow: bool,
+
+    /// Read cookies from a Netscape cookie file, and write the cookies of the run back to it
+    #[arg(long, value_name = "PATH")]
+    pub cookie_jar: Option<std::path::PathBuf>,
+
+    /// Do not store cookies set by responses, or send them with later requests
+    #[arg(long, default_value_t = false, conflicts_with = "cookie_jar")]
+    pub no_cookies: bool,
+
+    /// Retry requests failing with a connection error, a timeout or a status code of --retry-status, waiting longer between each attempt
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub retries: usize,
+
+    /// Comma separated status codes to retry
+    #[arg(
+        long,
+        value_name = "CODES",
+        value_delimiter = ',',
+        default_values_t = hitt_request::retry::DEFAULT_RETRY_STATUS_CODES,
+        value_parser = clap::value_parser!(u16).range(100..=999)
+    )]
+    pub retry_status: Vec<u16>,
+
+    /// Number of files to run at the same time, output is still printed in file order
+    #[arg(long, value_name = "N", default_value_t = core::num::NonZeroUsize::MIN)]
+    pub parallel: core::num::NonZeroUsize,
+
+    /// Base url of requests using a relative url, unless a Host header or a baseUrl variable is set
+    #[arg(long, value_name = "URL")]
+    pub base_url: Option<String>,
 
     /// Enable to run directory recursively
     #[arg(long, short, default_value_t = false)]
     pub recursive: bool,
 
+    /// Include fenced http blocks in Markdown files when running directories
+    #[arg(long, default_value_t = false)]
+    pub markdown: bool,
+
+    /// File extensions to run when running directories
+    #[arg(
+        long,
+        value_name = "EXT",
+        value_delimiter = ',',
+        default_value = "http"
+    )]
+    pub extension: Vec<String>,
+
+    /// Only run files matching the glob pattern when running directories
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Skip files matching the glob pattern when running directories
+    #[arg(long, value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
     /// Exit on error response status code
     #[arg(long, default_value_t = false)]
     pub fail_fast: bool,
@@ -60,6 +143,30 @@ pub struct RunCommandArguments {
     #[arg(long, default_value_t = false)]
     pub hide_headers: bool,
 
+    /// Show a breakdown of the request duration into redirects, dns, tcp connect, tls handshake, time to first byte and download
+    #[arg(long, default_value_t = false)]
+    pub timings: bool,
+
+    /// Print response bodies while they are received, instead of once the whole body has been read
+    #[arg(long, default_value_t = false)]
+    pub stream: bool,
+
+    /// Stop reading response bodies after the number of bytes, such as 512K or 10M
+    #[arg(long, value_name = "SIZE", value_parser = size::parse_byte_size)]
+    pub max_body_size: Option<usize>,
+
+    /// Write binary response bodies to the directory
+    #[arg(long, value_name = "DIR", conflicts_with = "output_dir")]
+    pub save_binary: Option<std::path::PathBuf>,
+
+    /// Write every response body to the directory, named after the request
+    #[arg(long, value_name = "DIR")]
+    pub output_dir: Option<std::path::PathBuf>,
+
+    /// Also write the response status and headers when writing response bodies to files
+    #[arg(long, default_value_t = false)]
+    pub output_headers: bool,
+
     /// Disable pretty printing of response body
     #[arg(long, default_value_t = false)]
     pub disable_formatting: bool,
@@ -115,6 +222,51 @@ impl clap::ValueEnum for TerminalShell {
     }
 }
 
+/// Send a request repeatedly and report its throughput and latency
+#[derive(Args, Debug)]
+pub struct BenchCommandArguments {
+    /// Path to .http file
+    #[arg()]
+    pub path: std::path::PathBuf,
+
+    /// Name or number of the request to send, required when the file contains multiple requests
+    #[arg(long, value_name = "NAME")]
+    pub request: Option<String>,
+
+    /// Number of requests to send [default: 100]
+    #[arg(long, short = 'n', value_name = "N", conflicts_with = "duration")]
+    pub requests: Option<core::num::NonZeroUsize>,
+
+    /// Send requests for the number of seconds instead of a fixed number of requests
+    #[arg(long, value_name = "SECONDS")]
+    pub duration: Option<core::num::NonZeroU64>,
+
+    /// Number of requests to send at the same time
+    #[arg(long, short, value_name = "N", default_value = "10")]
+    pub concurrency: core::num::NonZeroUsize,
+
+    /// Maximum number of requests to start per second
+    #[arg(long, value_name = "N")]
+    pub rate: Option<core::num::NonZeroU32>,
+
+    /// Request timeout in milliseconds
+    #[arg(long, value_name = "TIMEOUT_MS")]
+    pub timeout: Option<u64>,
+
+    #[command(flatten)]
+    pub variables: VariableArguments,
+
+    #[command(flatten)]
+    pub proxy: ProxyArguments,
+
+    #[command(flatten)]
+    pub tls: TlsArguments,
+
+    /// Connect through a unix domain socket instead of tcp, keeping the host and path of the url
+    #[arg(long, value_name = "PATH")]
+    pub unix_socket: Option<std::path::PathBuf>,
+}
+
 /// Generate shell completions
 #[derive(Args, Debug)]
 pub struct CompletionsCommandArguments {
@@ -127,4 +279,87 @@ pub struct CompletionsCommandArguments {
 pub struct SSECommandArguments {
     #[arg()]
     pub url: String,
+
+    #[command(flatten)]
+    pub proxy: ProxyArguments,
+
+    #[command(flatten)]
+    pub tls: TlsArguments,
+
+    /// Connect through a unix domain socket instead of tcp, keeping the host and path of the url
+    #[arg(long, value_name = "PATH")]
+    pub unix_socket: Option<std::path::PathBuf>,
+}
+
+#[derive(Args, Debug, Default, Clone)]
+pub struct VariableArguments {
+    /// Variables to pass to request
+    #[arg(long, value_name = "KEY>=<VALUE")]
+    pub var: Option<Vec<String>>,
+
+    /// Variables to pass to request, with their values redacted from the output
+    #[arg(long, value_name = "KEY>=<VALUE")]
+    pub secret_var: Option<Vec<String>>,
+
+    /// JSON files containing an object of variables to pass to request
+    #[arg(long, value_name = "PATH")]
+    pub var_file: Option<Vec<std::path::PathBuf>>,
+
+    /// JSON files containing an object of variables to pass to request, with their values redacted from the output
+    #[arg(long, value_name = "PATH")]
+    pub secret_var_file: Option<Vec<std::path::PathBuf>>,
+}
+
+#[derive(Args, Debug, Default, Clone)]
+pub struct ProxyArguments {
+    /// Send requests through a http, https or socks5 proxy, instead of the proxy set using HTTP_PROXY, HTTPS_PROXY or ALL_PROXY
+    #[arg(long, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Credentials of the proxy, unless they are part of the proxy url
+    #[arg(long, value_name = "USER:PASSWORD")]
+    pub proxy_user: Option<String>,
+
+    /// Comma separated hosts that should not use the proxy, or '*' to disable proxies. Overrides NO_PROXY
+    #[arg(long, value_name = "HOSTS")]
+    pub no_proxy: Option<String>,
+}
+
+#[derive(Args, Debug, Default, Clone)]
+pub struct TlsArguments {
+    /// Trust the certificates in a PEM file instead of the system certificates
+    #[arg(long, value_name = "PATH")]
+    pub cacert: Option<std::path::PathBuf>,
+
+    /// Client certificate as a PEM file or a PKCS#12 archive
+    #[arg(long, value_name = "PATH")]
+    pub cert: Option<std::path::PathBuf>,
+
+    /// Private key of the client certificate as a PEM file, unless it is part of --cert
+    #[arg(long, value_name = "PATH", requires = "cert")]
+    pub key: Option<std::path::PathBuf>,
+
+    /// Password of the PKCS#12 archive passed using --cert
+    #[arg(long, value_name = "PASSWORD", requires = "cert")]
+    pub cert_password: Option<String>,
+
+    /// Skip verifying the certificate of the server
+    #[arg(long, default_value_t = false)]
+    pub insecure: bool,
+}
+
+/// Parses the arguments of `hitt run`, for tests to build [`RunCommandArguments`] from.
+#[cfg(test)]
+pub fn parse_run_arguments(arguments: &[&str]) -> RunCommandArguments {
+    let mut command = vec!["hitt", "run"];
+
+    command.extend_from_slice(arguments);
+
+    match Cli::try_parse_from(command)
+        .expect("it to parse the arguments")
+        .command
+    {
+        HittCommand::Run(args) => *args,
+        _ => unreachable!("it to be a run command"),
+    }
 }
@@ -0,0 +1,58 @@
+/// Parses a number of bytes, optionally followed by a `K`, `M` or `G` unit of 1024 bytes,
+/// such as `512`, `64K` or `10MiB`.
+#[inline]
+pub fn parse_byte_size(input: &str) -> Result<usize, String> {
+    let input = input.trim();
+
+    let digits_end = input
+        .find(|ch: char| !ch.is_ascii_digit())
+        .unwrap_or(input.len());
+
+    let (digits, unit) = input.split_at(digits_end);
+
+    let multiplier: usize = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        _ => return Err(format!("unknown unit '{unit}' - expected K, M or G")),
+    };
+
+    digits
+        .parse::<usize>()
+        .map_err(|error| format!("invalid size '{input}' - {error}"))?
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("size '{input}' is too large"))
+}
+
+#[cfg(test)]
+mod test_parse_byte_size {
+    use super::parse_byte_size;
+
+    #[test]
+    fn it_should_parse_sizes() {
+        for (input, expected) in [
+            ("0", 0),
+            ("512", 512),
+            ("512B", 512),
+            ("64K", 64 * 1024),
+            ("64kb", 64 * 1024),
+            ("10MiB", 10 * 1024 * 1024),
+            ("1 G", 1024 * 1024 * 1024),
+        ] {
+            assert_eq!(Ok(expected), parse_byte_size(input));
+        }
+    }
+
+    #[test]
+    fn it_should_reject_invalid_sizes() {
+        assert_eq!(
+            Err("unknown unit 'TB' - expected K, M or G".to_owned()),
+            parse_byte_size("1TB")
+        );
+
+        for input in ["", "M", "-1", "1.5M"] {
+            assert!(parse_byte_size(input).is_err());
+        }
+    }
+}
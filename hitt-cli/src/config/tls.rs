@@ -0,0 +1,178 @@
+use hitt_parser::tls::RequestTls;
+use hitt_request::{
+    timing::TimingRecorder,
+    tls::{HTTP_VERSIONS, TlsOptions, build_tls_config},
+};
+
+use crate::{config::TlsArguments, error::HittCliError};
+
+#[inline]
+pub fn build_tls_options(args: &TlsArguments) -> TlsOptions {
+    TlsOptions {
+        ca_certificate: args.cacert.clone(),
+        client_certificate: args.cert.clone(),
+        client_key: args.key.clone(),
+        certificate_password: args.cert_password.clone(),
+        insecure: args.insecure,
+    }
+}
+
+/// Applies the tls directives of a request to the options of the client.
+///
+/// Paths are relative to the file containing the request. A client certificate set by the request
+/// replaces the key and password of the client certificate of the client.
+#[inline]
+pub fn request_tls_options(
+    options: &TlsOptions,
+    tls: &RequestTls,
+    http_file: &std::path::Path,
+) -> TlsOptions {
+    let dir = http_file
+        .parent()
+        .unwrap_or_else(|| std::path::Path::new("."));
+
+    let mut output = options.clone();
+
+    if let Some(ca_certificate) = &tls.ca_certificate {
+        output.ca_certificate = Some(dir.join(ca_certificate));
+    }
+
+    if let Some(client_certificate) = &tls.client_certificate {
+        output.client_certificate = Some(dir.join(client_certificate));
+        output.client_key = None;
+        output.certificate_password = None;
+    }
+
+    if let Some(client_key) = &tls.client_key {
+        output.client_key = Some(dir.join(client_key));
+    }
+
+    if let Some(certificate_password) = &tls.certificate_password {
+        output.certificate_password = Some(certificate_password.clone());
+    }
+
+    output.insecure |= tls.insecure;
+
+    output
+}
+
+#[cfg(test)]
+mod test_request_tls_options {
+    use hitt_parser::tls::RequestTls;
+    use hitt_request::tls::TlsOptions;
+
+    use super::request_tls_options;
+
+    #[test]
+    fn it_should_keep_options_without_directives() {
+        let options = TlsOptions {
+            ca_certificate: Some(std::path::PathBuf::from("ca.pem")),
+            client_certificate: Some(std::path::PathBuf::from("client.pem")),
+            client_key: Some(std::path::PathBuf::from("client.key")),
+            certificate_password: None,
+            insecure: false,
+        };
+
+        assert_eq!(
+            options,
+            request_tls_options(
+                &options,
+                &RequestTls::default(),
+                std::path::Path::new("requests/users.http")
+            )
+        );
+    }
+
+    #[test]
+    fn it_should_resolve_paths_relative_to_http_file() {
+        let options = TlsOptions {
+            ca_certificate: Some(std::path::PathBuf::from("ca.pem")),
+            client_certificate: Some(std::path::PathBuf::from("client.pem")),
+            client_key: Some(std::path::PathBuf::from("client.key")),
+            certificate_password: None,
+            insecure: false,
+        };
+
+        let tls = RequestTls {
+            ca_certificate: None,
+            client_certificate: Some(std::path::PathBuf::from("certs/client.p12")),
+            client_key: None,
+            certificate_password: Some("hunter2".to_owned()),
+            insecure: true,
+        };
+
+        assert_eq!(
+            TlsOptions {
+                ca_certificate: Some(std::path::PathBuf::from("ca.pem")),
+                client_certificate: Some(std::path::PathBuf::from("requests/certs/client.p12")),
+                client_key: None,
+                certificate_password: Some("hunter2".to_owned()),
+                insecure: true,
+            },
+            request_tls_options(&options, &tls, std::path::Path::new("requests/users.http"))
+        );
+    }
+}
+
+/// Configures the tls options, and the timing recorder if it is set.
+///
+/// The default tls config of reqwest is kept when neither is set.
+#[inline]
+pub fn configure_tls(
+    builder: reqwest::ClientBuilder,
+    options: &TlsOptions,
+    recorder: Option<&TimingRecorder>,
+) -> Result<reqwest::ClientBuilder, HittCliError> {
+    if recorder.is_none() && *options == TlsOptions::default() {
+        return Ok(builder);
+    }
+
+    let tls_config = build_tls_config(options, &HTTP_VERSIONS).map_err(HittCliError::Tls)?;
+
+    Ok(match recorder {
+        Some(recorder) => recorder.install(builder, tls_config),
+        None => builder.tls_backend_preconfigured(tls_config),
+    })
+}
+
+#[cfg(test)]
+mod test_configure_tls {
+    use hitt_request::{timing::TimingRecorder, tls::TlsOptions};
+
+    use super::configure_tls;
+    use crate::error::HittCliError;
+
+    #[test]
+    fn it_should_build_clients() {
+        let recorder = TimingRecorder::new();
+
+        for recorder in [None, Some(&recorder)] {
+            configure_tls(
+                reqwest::ClientBuilder::new(),
+                &TlsOptions::default(),
+                recorder,
+            )
+            .expect("it to configure the client")
+            .build()
+            .expect("it to build a client");
+        }
+    }
+
+    #[test]
+    fn it_should_reject_missing_certificates() {
+        let options = TlsOptions {
+            ca_certificate: Some(std::path::PathBuf::from("missing-ca.pem")),
+            ..TlsOptions::default()
+        };
+
+        let error = configure_tls(reqwest::ClientBuilder::new(), &options, None)
+            .expect_err("it to reject the certificate");
+
+        assert!(
+            error
+                .to_string()
+                .starts_with("error configuring tls - unable to read 'missing-ca.pem' - ")
+        );
+        assert!(matches!(error, HittCliError::Tls(_)));
+    }
+}
@@ -0,0 +1,132 @@
+use crate::error::HittCliError;
+
+/// Returns the unix socket of a request, preferring the `# @unix-socket` directive over the
+/// `--unix-socket` argument.
+///
+/// Paths of directives are relative to the file containing the request.
+#[inline]
+pub fn request_unix_socket(
+    unix_socket: Option<&std::path::Path>,
+    request_unix_socket: Option<&std::path::Path>,
+    http_file: &std::path::Path,
+) -> Option<std::path::PathBuf> {
+    match request_unix_socket {
+        Some(path) => Some(
+            http_file
+                .parent()
+                .unwrap_or_else(|| std::path::Path::new("."))
+                .join(path),
+        ),
+        None => unix_socket.map(std::path::Path::to_path_buf),
+    }
+}
+
+#[cfg(test)]
+mod test_request_unix_socket {
+    use super::request_unix_socket;
+
+    #[test]
+    fn it_should_use_argument_without_directive() {
+        assert_eq!(
+            Some(std::path::PathBuf::from("/var/run/docker.sock")),
+            request_unix_socket(
+                Some(std::path::Path::new("/var/run/docker.sock")),
+                None,
+                std::path::Path::new("requests/users.http")
+            )
+        );
+
+        assert_eq!(
+            None,
+            request_unix_socket(None, None, std::path::Path::new("requests/users.http"))
+        );
+    }
+
+    #[test]
+    fn it_should_resolve_directive_relative_to_http_file() {
+        for (directive, expected) in [
+            ("api.sock", "requests/api.sock"),
+            ("/var/run/docker.sock", "/var/run/docker.sock"),
+        ] {
+            assert_eq!(
+                Some(std::path::PathBuf::from(expected)),
+                request_unix_socket(
+                    Some(std::path::Path::new("other.sock")),
+                    Some(std::path::Path::new(directive)),
+                    std::path::Path::new("requests/users.http")
+                )
+            );
+        }
+    }
+}
+
+/// Connects every request of the client through the unix socket, if it is set.
+///
+/// Proxies are ignored by clients using a unix socket.
+#[inline]
+#[cfg(unix)]
+#[allow(clippy::unnecessary_wraps)]
+pub fn configure_unix_socket(
+    builder: reqwest::ClientBuilder,
+    unix_socket: Option<&std::path::Path>,
+) -> Result<reqwest::ClientBuilder, HittCliError> {
+    Ok(match unix_socket {
+        Some(path) => builder.unix_socket(path.to_path_buf()),
+        None => builder,
+    })
+}
+
+/// Connects every request of the client through the unix socket, if it is set.
+///
+/// Proxies are ignored by clients using a unix socket.
+#[inline]
+#[cfg(not(unix))]
+pub fn configure_unix_socket(
+    builder: reqwest::ClientBuilder,
+    unix_socket: Option<&std::path::Path>,
+) -> Result<reqwest::ClientBuilder, HittCliError> {
+    match unix_socket {
+        Some(path) => Err(HittCliError::UnsupportedUnixSocket(path.to_path_buf())),
+        None => Ok(builder),
+    }
+}
+
+#[cfg(test)]
+mod test_configure_unix_socket {
+    use super::configure_unix_socket;
+
+    #[test]
+    fn it_should_build_clients_without_unix_socket() {
+        configure_unix_socket(reqwest::ClientBuilder::new(), None)
+            .expect("it to configure the client")
+            .build()
+            .expect("it to build a client");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_should_build_clients_with_unix_socket() {
+        configure_unix_socket(
+            reqwest::ClientBuilder::new(),
+            Some(std::path::Path::new("/var/run/docker.sock")),
+        )
+        .expect("it to configure the client")
+        .build()
+        .expect("it to build a client");
+    }
+
+    #[cfg(not(unix))]
+    #[test]
+    fn it_should_reject_unix_sockets() {
+        let error = configure_unix_socket(
+            reqwest::ClientBuilder::new(),
+            Some(std::path::Path::new("docker.sock")),
+        )
+        .expect_err("it to reject the unix socket");
+
+        assert!(matches!(
+            error,
+            crate::error::HittCliError::UnsupportedUnixSocket(_)
+        ));
+    }
+}
@@ -1,18 +1,38 @@
 #[derive(Debug)]
 pub enum HittCliError {
+    BenchMissingRequest(std::path::PathBuf, String),
+    BenchMultipleRequests(std::path::PathBuf, usize),
+    BenchNoRequests(std::path::PathBuf),
+    CookieJar(std::path::PathBuf, hitt_request::cookie::CookieJarError),
     FailFast,
+    HttpClient(reqwest::Error),
+    InvalidGlob(String, ignore::Error),
+    InvalidProxy(String, reqwest::Error),
     InvalidVariableArgument(String),
     Io(std::io::Error),
     IoRead(std::path::PathBuf, std::io::Error),
+    IoWrite(std::path::PathBuf, std::io::Error),
     Join(tokio::task::JoinError),
     Parse(std::path::PathBuf, hitt_parser::error::RequestParseError),
+    ParseMarkdown(
+        std::path::PathBuf,
+        usize,
+        hitt_parser::error::RequestParseError,
+    ),
+    PromptNotInteractive(String),
     RecursiveNotEnabled,
-    RequestTimeout(http::Method, http::Uri),
-    Reqwest(http::Method, http::Uri, reqwest::Error),
+    RequestTimeout(http::Method, String),
+    Reqwest(http::Method, String, reqwest::Error),
     SSEError(Box<hitt_sse::Error>),
     SSEParseUrl(String),
+    StreamParallel,
+    Tls(hitt_request::tls::TlsError),
+    UnsupportedProxyScheme(String),
+    #[cfg(not(unix))]
+    UnsupportedUnixSocket(std::path::PathBuf),
     VariableArgumentKeyIndexing(String),
     VariableArgumentValueIndexing(String),
+    VariableFile(std::path::PathBuf, serde_json::Error),
 }
 
 impl core::error::Error for HittCliError {}
@@ -21,7 +41,26 @@ impl core::fmt::Display for HittCliError {
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
+            Self::BenchMissingRequest(path, selector) => write!(
+                f,
+                "no request named or numbered '{selector}' in '{}'",
+                path.display()
+            ),
+            Self::BenchMultipleRequests(path, count) => write!(
+                f,
+                "'{}' contains {count} requests - select one using --request <NAME>",
+                path.display()
+            ),
+            Self::BenchNoRequests(path) => {
+                write!(f, "'{}' does not contain any requests", path.display())
+            }
+            Self::CookieJar(path, error) => {
+                write!(f, "error reading cookie jar '{}' - {error}", path.display())
+            }
             Self::FailFast => write!(f, "exiting early since --fail-fast is enabled"),
+            Self::HttpClient(error) => write!(f, "error building http client - {error}"),
+            Self::InvalidGlob(glob, error) => write!(f, "invalid glob '{glob}' - {error}"),
+            Self::InvalidProxy(url, error) => write!(f, "invalid proxy url '{url}' - {error}"),
             Self::InvalidVariableArgument(input) => write!(
                 f,
                 "'{input}' is not a valid variable argument - variable input should be '--var <KEY>=<VALUE>'"
@@ -30,10 +69,24 @@ impl core::fmt::Display for HittCliError {
             Self::IoRead(path, error) => {
                 write!(f, "error reading '{}' - {error:#?}", path.display())
             }
+            Self::IoWrite(path, error) => {
+                write!(f, "error writing '{}' - {error:#?}", path.display())
+            }
             Self::Join(error) => write!(f, "error joining handles - {error:#?}"),
             Self::Parse(path, error) => {
                 write!(f, "error parsing file '{}' - {error}", path.display())
             }
+            Self::ParseMarkdown(path, line, error) => {
+                write!(
+                    f,
+                    "error parsing file '{}:{line}' - {error}",
+                    path.display()
+                )
+            }
+            Self::PromptNotInteractive(name) => write!(
+                f,
+                "variable '{name}' must be prompted for, but the terminal is not interactive - pass it using '--var {name}=<VALUE>'"
+            ),
             Self::RecursiveNotEnabled => {
                 write!(f, "received directory path but --recursive is not enabled")
             }
@@ -41,12 +94,34 @@ impl core::fmt::Display for HittCliError {
             Self::Reqwest(method, uri, error) => write!(f, "{method} {uri} - {error}"),
             Self::SSEError(error) => write!(f, "sse error - {error}"),
             Self::SSEParseUrl(url) => write!(f, "'{url}' is not a valid url"),
+            Self::StreamParallel => write!(
+                f,
+                "--stream can not be used when --parallel runs more than one file at a time"
+            ),
+            Self::Tls(error) => write!(f, "error configuring tls - {error}"),
+            Self::UnsupportedProxyScheme(url) => write!(
+                f,
+                "unsupported proxy url '{url}' - expected a http, https or socks url"
+            ),
+            #[cfg(not(unix))]
+            Self::UnsupportedUnixSocket(path) => write!(
+                f,
+                "unable to connect to '{}' - unix sockets are not supported on this platform",
+                path.display()
+            ),
             Self::VariableArgumentKeyIndexing(variable) => {
                 write!(f, "unable to index key of --var '{variable}'")
             }
             Self::VariableArgumentValueIndexing(variable) => {
                 write!(f, "unable to index value of --var '{variable}'")
             }
+            Self::VariableFile(path, error) => {
+                write!(
+                    f,
+                    "error parsing variable file '{}' - {error}",
+                    path.display()
+                )
+            }
         }
     }
 }
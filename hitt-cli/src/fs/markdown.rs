@@ -0,0 +1,219 @@
+const MARKDOWN_EXTENSIONS: [&str; 2] = ["md", "markdown"];
+
+/// A fenced `http` code block in a Markdown file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct HttpCodeBlock {
+    /// Line number of the first line inside the block, starting at 1.
+    pub line: usize,
+    pub content: String,
+}
+
+#[inline]
+pub fn is_markdown_file(path: &std::path::Path) -> bool {
+    path.extension()
+        .and_then(std::ffi::OsStr::to_str)
+        .is_some_and(|ext| {
+            MARKDOWN_EXTENSIONS
+                .iter()
+                .any(|markdown_ext| ext.eq_ignore_ascii_case(markdown_ext))
+        })
+}
+
+#[cfg(test)]
+mod test_is_markdown_file {
+    use super::is_markdown_file;
+
+    #[test]
+    fn it_should_detect_markdown_files() {
+        for path in ["README.md", "docs/api.markdown", "docs/API.MD"] {
+            assert!(is_markdown_file(std::path::Path::new(path)));
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_other_files() {
+        for path in ["api.http", "md", "docs/md.txt"] {
+            assert!(!is_markdown_file(std::path::Path::new(path)));
+        }
+    }
+}
+
+/// Returns the fence character, the length of the fence and the info string of an opening fence.
+#[inline]
+fn parse_fence(line: &str) -> Option<(char, usize, &str)> {
+    let indent = line.len() - line.trim_start_matches(' ').len();
+
+    if indent > 3 {
+        return None;
+    }
+
+    let fence = &line[indent..];
+
+    let ch = fence.chars().next().filter(|ch| *ch == '`' || *ch == '~')?;
+
+    let len = fence.len() - fence.trim_start_matches(ch).len();
+
+    if len < 3 {
+        return None;
+    }
+
+    let info = fence[len..].trim();
+
+    // backtick fences cannot contain backticks in the info string
+    if ch == '`' && info.contains('`') {
+        return None;
+    }
+
+    Some((ch, len, info))
+}
+
+#[inline]
+fn is_closing_fence(line: &str, ch: char, len: usize) -> bool {
+    parse_fence(line).is_some_and(|(closing_ch, closing_len, info)| {
+        closing_ch == ch && closing_len >= len && info.is_empty()
+    })
+}
+
+/// Extracts the fenced code blocks using `http` as the language.
+#[inline]
+pub fn extract_http_blocks(content: &str) -> Vec<HttpCodeBlock> {
+    let mut blocks = Vec::new();
+
+    let mut current_block: Option<(char, usize, HttpCodeBlock)> = None;
+
+    let mut ignored_fence: Option<(char, usize)> = None;
+
+    for (index, line) in content.lines().enumerate() {
+        if let Some((ch, len)) = ignored_fence {
+            if is_closing_fence(line, ch, len) {
+                ignored_fence = None;
+            }
+
+            continue;
+        }
+
+        if let Some((ch, len, block)) = &mut current_block {
+            if is_closing_fence(line, *ch, *len) {
+                if let Some((_, _, finished_block)) = current_block.take() {
+                    blocks.push(finished_block);
+                }
+            } else {
+                block.content.push_str(line);
+                block.content.push('\n');
+            }
+
+            continue;
+        }
+
+        if let Some((ch, len, info)) = parse_fence(line) {
+            let language = info.split_whitespace().next().unwrap_or_default();
+
+            if language.eq_ignore_ascii_case("http") {
+                current_block = Some((
+                    ch,
+                    len,
+                    HttpCodeBlock {
+                        line: index + 2,
+                        content: String::new(),
+                    },
+                ));
+            } else {
+                ignored_fence = Some((ch, len));
+            }
+        }
+    }
+
+    // unclosed blocks run until the end of the document
+    if let Some((_, _, block)) = current_block {
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+#[cfg(test)]
+mod test_extract_http_blocks {
+    use super::{HttpCodeBlock, extract_http_blocks};
+
+    #[test]
+    fn it_should_extract_http_blocks() {
+        let input = "# API
+
+Fetch a user:
+
+```http
+GET https://mhouge.dk/users/1
+```
+
+Some code:
+
+```js
+console.log('```http');
+```
+
+~~~~ HTTP title=\"create\"
+POST https://mhouge.dk/users
+content-type: application/json
+
+{ \"name\": \"mads\" }
+~~~~
+";
+
+        assert_eq!(
+            extract_http_blocks(input),
+            [
+                HttpCodeBlock {
+                    line: 6,
+                    content: "GET https://mhouge.dk/users/1\n".to_owned(),
+                },
+                HttpCodeBlock {
+                    line: 16,
+                    content: "POST https://mhouge.dk/users\ncontent-type: application/json\n\n{ \"name\": \"mads\" }\n".to_owned(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_require_matching_closing_fence() {
+        let input = "````http
+GET https://mhouge.dk/
+```
+~~~~
+````";
+
+        assert_eq!(
+            extract_http_blocks(input),
+            [HttpCodeBlock {
+                line: 2,
+                content: "GET https://mhouge.dk/\n```\n~~~~\n".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn it_should_include_unclosed_blocks() {
+        let input = "```http\nGET https://mhouge.dk/";
+
+        assert_eq!(
+            extract_http_blocks(input),
+            [HttpCodeBlock {
+                line: 2,
+                content: "GET https://mhouge.dk/\n".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn it_should_ignore_other_blocks() {
+        for input in [
+            "```\nGET https://mhouge.dk/\n```",
+            "```json\nGET https://mhouge.dk/\n```",
+            "```https\nGET https://mhouge.dk/\n```",
+            "    ```http\n    GET https://mhouge.dk/\n    ```",
+            "GET https://mhouge.dk/",
+        ] {
+            assert!(extract_http_blocks(input).is_empty());
+        }
+    }
+}
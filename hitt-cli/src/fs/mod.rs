@@ -1,21 +1,126 @@
 use std::sync::Arc;
 
 use futures::future::TryJoinAll;
-use hitt_parser::HittRequest;
+use hitt_parser::{HittRequest, ParseOptions, prompt::PromptDeclaration, value::VariableValue};
+use markdown::{extract_http_blocks, is_markdown_file};
 
-use crate::error::HittCliError;
+use crate::{
+    error::HittCliError,
+    terminal::redact::{REDACTED, is_sensitive_header, redact_secrets},
+};
+
+mod markdown;
+
+/// Parses the fenced `http` blocks of a Markdown file.
+///
+/// Each block is parsed on its own, so variables declared in one block are not available in the
+/// other blocks.
+#[inline]
+fn parse_markdown(
+    path: &std::path::Path,
+    content: &str,
+    input_variables: &std::collections::HashMap<String, VariableValue>,
+    options: &ParseOptions,
+) -> Result<Vec<HittRequest>, HittCliError> {
+    let mut reqs = Vec::new();
+
+    for block in extract_http_blocks(content) {
+        reqs.extend(
+            hitt_parser::parse_requests_with_options(&block.content, input_variables, options)
+                .map_err(|e| HittCliError::ParseMarkdown(path.to_owned(), block.line, e))?,
+        );
+    }
+
+    Ok(reqs)
+}
+
+#[cfg(test)]
+mod test_parse_markdown {
+    use hitt_parser::ParseOptions;
+
+    use super::parse_markdown;
+    use crate::error::HittCliError;
+
+    #[test]
+    fn it_should_parse_http_blocks() {
+        let input = "# API
+
+```http
+GET https://mhouge.dk/users
+
+###
+
+DELETE https://mhouge.dk/users/1
+```
+
+```json
+{ \"name\": \"mads\" }
+```
+
+```http
+POST https://mhouge.dk/users
+```";
+
+        let reqs = parse_markdown(
+            std::path::Path::new("api.md"),
+            input,
+            &std::collections::HashMap::new(),
+            &ParseOptions::default(),
+        )
+        .expect("it to parse successfully");
+
+        let methods = reqs
+            .iter()
+            .map(|req| req.method.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(methods, ["GET", "DELETE", "POST"]);
+    }
+
+    #[test]
+    fn it_should_point_errors_to_the_block() {
+        let input = "# API
+
+```http
+GET https://mhouge.dk/
+```
+
+```http
+GET https://mhouge.dk/{{id}}
+```";
+
+        let error = parse_markdown(
+            std::path::Path::new("api.md"),
+            input,
+            &std::collections::HashMap::new(),
+            &ParseOptions::default(),
+        )
+        .expect_err("it to return an error");
+
+        assert_eq!(
+            "error parsing file 'api.md:8' - variable 'id' was used, but not set",
+            error.to_string()
+        );
+        assert!(matches!(error, HittCliError::ParseMarkdown(_, 8, _)));
+    }
+}
 
 #[inline]
 pub async fn parse_file(
     path: &std::path::Path,
-    input_variables: Arc<std::collections::HashMap<String, String>>,
+    input_variables: Arc<std::collections::HashMap<String, VariableValue>>,
+    options: &ParseOptions,
 ) -> Result<(std::path::PathBuf, Vec<HittRequest>), HittCliError> {
     let content = tokio::fs::read_to_string(&path)
         .await
         .map_err(|err| HittCliError::IoRead(path.to_owned(), err))?;
 
-    let reqs = hitt_parser::parse_requests(&content, &input_variables)
-        .map_err(|e| HittCliError::Parse(path.to_owned(), e))?;
+    let reqs = if is_markdown_file(path) {
+        parse_markdown(path, &content, &input_variables, options)?
+    } else {
+        hitt_parser::parse_requests_with_options(&content, &input_variables, options)
+            .map_err(|e| HittCliError::Parse(path.to_owned(), e))?
+    };
 
     Ok((path.to_owned(), reqs))
 }
@@ -23,7 +128,8 @@ pub async fn parse_file(
 #[inline]
 pub async fn parse_files(
     paths: Vec<std::path::PathBuf>,
-    input_variables: std::collections::HashMap<String, String>,
+    input_variables: std::collections::HashMap<String, VariableValue>,
+    options: &ParseOptions,
 ) -> Result<Vec<(std::path::PathBuf, Vec<HittRequest>)>, HittCliError> {
     let vars = Arc::new(input_variables);
 
@@ -31,8 +137,9 @@ pub async fn parse_files(
         .into_iter()
         .map(|path| {
             let var_clone = Arc::clone(&vars);
+            let options_clone = options.clone();
 
-            tokio::task::spawn(async move { parse_file(&path, var_clone).await })
+            tokio::task::spawn(async move { parse_file(&path, var_clone, &options_clone).await })
         })
         .collect::<TryJoinAll<_>>()
         .await
@@ -47,19 +154,264 @@ pub async fn parse_files(
     Ok(parsed_requests)
 }
 
+/// Reads a JSON file containing an object, where each field is a variable.
+#[inline]
+pub async fn read_variable_file(
+    path: &std::path::Path,
+) -> Result<std::collections::HashMap<String, VariableValue>, HittCliError> {
+    let content = tokio::fs::read_to_string(path)
+        .await
+        .map_err(|err| HittCliError::IoRead(path.to_owned(), err))?;
+
+    let fields = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&content)
+        .map_err(|err| HittCliError::VariableFile(path.to_owned(), err))?;
+
+    Ok(fields
+        .into_iter()
+        .map(|(name, value)| (name, VariableValue::from(value)))
+        .collect())
+}
+
+#[cfg(test)]
+mod test_read_variable_file {
+    use hitt_parser::value::VariableValue;
+
+    use super::read_variable_file;
+    use crate::error::HittCliError;
+
+    #[tokio::test]
+    async fn it_should_read_typed_variables() {
+        let f = tempfile::Builder::new()
+            .prefix("hitt-")
+            .suffix(".json")
+            .tempfile()
+            .expect("it to create a file");
+
+        std::fs::write(
+            f.path(),
+            r#"{ "host": "https://mhouge.dk", "user": { "id": 1 } }"#,
+        )
+        .expect("it to write successfully");
+
+        let vars = read_variable_file(f.path())
+            .await
+            .expect("it to return a map of variables");
+
+        assert_eq!(vars.len(), 2);
+
+        assert_eq!(
+            vars.get("host"),
+            Some(&VariableValue::from("https://mhouge.dk"))
+        );
+
+        assert_eq!(
+            vars.get("user").map(ToString::to_string).as_deref(),
+            Some("{\"id\":1}")
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_non_objects() {
+        let f = tempfile::Builder::new()
+            .prefix("hitt-")
+            .suffix(".json")
+            .tempfile()
+            .expect("it to create a file");
+
+        std::fs::write(f.path(), "[1, 2, 3]").expect("it to write successfully");
+
+        let err = read_variable_file(f.path())
+            .await
+            .expect_err("it to return an error");
+
+        assert!(matches!(err, HittCliError::VariableFile(path, _) if path == f.path()));
+    }
+}
+
+#[inline]
+pub async fn find_prompts(
+    paths: &[std::path::PathBuf],
+) -> Result<Vec<PromptDeclaration>, HittCliError> {
+    let mut prompts: Vec<PromptDeclaration> = Vec::new();
+
+    for path in paths {
+        let content = tokio::fs::read_to_string(path)
+            .await
+            .map_err(|err| HittCliError::IoRead(path.to_owned(), err))?;
+
+        let file_prompts = if is_markdown_file(path) {
+            extract_http_blocks(&content)
+                .iter()
+                .flat_map(|block| hitt_parser::parse_prompts(&block.content))
+                .collect()
+        } else {
+            hitt_parser::parse_prompts(&content)
+        };
+
+        for prompt in file_prompts {
+            if !prompts.iter().any(|existing| existing.name == prompt.name) {
+                prompts.push(prompt);
+            }
+        }
+    }
+
+    Ok(prompts)
+}
+
+#[cfg(test)]
+mod test_find_prompts {
+    use super::find_prompts;
+
+    #[tokio::test]
+    async fn it_should_return_unique_prompts_across_files() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to return a valid dir");
+
+        let file1 = dir.path().join("file1.http");
+        let file2 = dir.path().join("file2.http");
+
+        std::fs::write(
+            &file1,
+            "# @prompt otp Enter the 2FA code\nGET https://mhouge.dk/?otp={{otp}}",
+        )
+        .expect("it to write successfully");
+
+        std::fs::write(
+            &file2,
+            "# @prompt otp\n# @prompt password\nGET https://mhouge.dk/",
+        )
+        .expect("it to write successfully");
+
+        let prompts = find_prompts(&[file1, file2])
+            .await
+            .expect("it to return a list of prompts");
+
+        assert_eq!(prompts.len(), 2);
+
+        let otp = prompts.first().expect("it to exist");
+        assert_eq!(otp.name, "otp");
+        assert_eq!(otp.description.as_deref(), Some("Enter the 2FA code"));
+
+        let password = prompts.get(1).expect("it to exist");
+        assert_eq!(password.name, "password");
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DiscoveryOptions {
+    /// File extensions to include, without the leading dot.
+    pub extensions: Vec<String>,
+    /// Glob patterns a file must match, relative to the directory.
+    pub include: Vec<String>,
+    /// Glob patterns of files to skip, relative to the directory.
+    pub exclude: Vec<String>,
+    /// Include fenced `http` blocks of Markdown files.
+    pub markdown: bool,
+}
+
+impl Default for DiscoveryOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            extensions: vec!["http".to_owned()],
+            include: Vec::new(),
+            exclude: Vec::new(),
+            markdown: false,
+        }
+    }
+}
+
+impl DiscoveryOptions {
+    #[inline]
+    fn has_extension(&self, path: &std::path::Path) -> bool {
+        path.extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .is_some_and(|ext| {
+                self.extensions
+                    .iter()
+                    .any(|expected| expected.trim_start_matches('.') == ext)
+            })
+            || (self.markdown && is_markdown_file(path))
+    }
+
+    #[inline]
+    fn build_overrides(
+        &self,
+        path: &std::path::Path,
+    ) -> Result<ignore::overrides::Override, HittCliError> {
+        let mut builder = ignore::overrides::OverrideBuilder::new(path);
+
+        for glob in &self.include {
+            builder
+                .add(glob)
+                .map_err(|err| HittCliError::InvalidGlob(glob.clone(), err))?;
+        }
+
+        for glob in &self.exclude {
+            builder
+                .add(&format!("!{glob}"))
+                .map_err(|err| HittCliError::InvalidGlob(glob.clone(), err))?;
+        }
+
+        builder.build().map_err(|err| {
+            let globs = self
+                .include
+                .iter()
+                .chain(&self.exclude)
+                .map(String::as_str)
+                .collect::<Vec<_>>();
+
+            HittCliError::InvalidGlob(globs.join(", "), err)
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_has_extension {
+    use super::DiscoveryOptions;
+
+    #[test]
+    fn it_should_match_configured_extensions() {
+        let options = DiscoveryOptions {
+            extensions: vec!["http".to_owned(), ".rest".to_owned()],
+            ..DiscoveryOptions::default()
+        };
+
+        for path in ["file.http", "nested/file.rest"] {
+            assert!(options.has_extension(std::path::Path::new(path)));
+        }
+
+        for path in ["file.hitt", "file.md", "http", "file.http.bak"] {
+            assert!(!options.has_extension(std::path::Path::new(path)));
+        }
+    }
+}
+
+/// Finds the files matching the discovery options in the directory, sorted by path.
 #[inline]
-pub fn find_http_files(path: &std::path::Path) -> Vec<std::path::PathBuf> {
-    ignore::WalkBuilder::new(path)
+pub fn find_http_files(
+    path: &std::path::Path,
+    options: &DiscoveryOptions,
+) -> Result<Vec<std::path::PathBuf>, HittCliError> {
+    let overrides = options.build_overrides(path)?;
+
+    let mut files = ignore::WalkBuilder::new(path)
         .git_ignore(true)
         .require_git(false)
         .add_custom_ignore_filename(".hittignore")
+        .overrides(overrides)
         .build()
         .filter_map(|original_entry| {
             if let Ok(entry) = original_entry {
                 let entry_path = entry.path();
 
-                if let Some(ext) = entry_path.extension()
-                    && ext == "http"
+                if entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_file())
+                    && options.has_extension(entry_path)
                 {
                     return Some(entry_path.to_path_buf());
                 }
@@ -67,12 +419,17 @@ pub fn find_http_files(path: &std::path::Path) -> Vec<std::path::PathBuf> {
 
             None
         })
-        .collect()
+        .collect::<Vec<_>>();
+
+    files.sort();
+
+    Ok(files)
 }
 
 #[cfg(test)]
 mod test_find_http_files {
-    use super::find_http_files;
+    use super::{DiscoveryOptions, find_http_files};
+    use crate::error::HittCliError;
 
     #[test]
     fn it_should_return_a_list_of_files() {
@@ -91,7 +448,8 @@ mod test_find_http_files {
 
         std::fs::File::create(dir.path().join("nested/file2.http")).expect("it to create a file");
 
-        let result = find_http_files(dir.path());
+        let result = find_http_files(dir.path(), &DiscoveryOptions::default())
+            .expect("it to return a list of files");
 
         assert_eq!(2, result.len());
     }
@@ -124,8 +482,518 @@ mod test_find_http_files {
         std::fs::write(dir.path().join(".gitignore"), gitignore)
             .expect("it to write to .gitignore");
 
-        let result = find_http_files(dir.path());
+        let result = find_http_files(dir.path(), &DiscoveryOptions::default())
+            .expect("it to return a list of files");
 
         assert_eq!(1, result.len());
     }
+
+    #[test]
+    fn it_should_include_markdown_files_when_enabled() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to return a valid dir");
+
+        std::fs::File::create(dir.path().join("file.http")).expect("it to create a file");
+
+        std::fs::File::create(dir.path().join("README.md")).expect("it to create a file");
+
+        let options = DiscoveryOptions::default();
+
+        assert_eq!(
+            1,
+            find_http_files(dir.path(), &options)
+                .expect("it to return a list of files")
+                .len()
+        );
+
+        let markdown_options = DiscoveryOptions {
+            markdown: true,
+            ..DiscoveryOptions::default()
+        };
+
+        assert_eq!(
+            2,
+            find_http_files(dir.path(), &markdown_options)
+                .expect("it to return a list of files")
+                .len()
+        );
+    }
+
+    #[test]
+    fn it_should_filter_using_globs() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to return a valid dir");
+
+        std::fs::create_dir_all(dir.path().join("api/users")).expect("it to create dir");
+        std::fs::create_dir_all(dir.path().join("scratch")).expect("it to create dir");
+
+        for file in [
+            "api/users/b.rest",
+            "api/users/a.http",
+            "api/orders.hitt",
+            "api/orders.js",
+            "api/draft.http",
+            "scratch/test.http",
+        ] {
+            std::fs::File::create(dir.path().join(file)).expect("it to create a file");
+        }
+
+        let options = DiscoveryOptions {
+            extensions: vec!["http".to_owned(), "rest".to_owned(), "hitt".to_owned()],
+            include: vec!["api/**".to_owned()],
+            exclude: vec!["draft.*".to_owned()],
+            markdown: false,
+        };
+
+        let result = find_http_files(dir.path(), &options).expect("it to return a list of files");
+
+        assert_eq!(
+            result,
+            [
+                dir.path().join("api/orders.hitt"),
+                dir.path().join("api/users/a.http"),
+                dir.path().join("api/users/b.rest"),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_reject_invalid_globs() {
+        let options = DiscoveryOptions {
+            include: vec!["api/[".to_owned()],
+            ..DiscoveryOptions::default()
+        };
+
+        let error = find_http_files(std::path::Path::new("."), &options)
+            .expect_err("it to return an error");
+
+        assert!(matches!(error, HittCliError::InvalidGlob(glob, _) if glob == "api/["));
+    }
+}
+
+/// Replaces characters that are not safe in file names, returning `None` if nothing is left.
+#[inline]
+fn sanitize_file_name(name: &str) -> Option<String> {
+    let sanitized = name
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '_') {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    if sanitized.trim_matches('.').is_empty() {
+        None
+    } else {
+        Some(sanitized)
+    }
+}
+
+/// Uses the last segment of the url path as the file name, falling back to `response`.
+#[inline]
+fn body_file_name(url: &str) -> String {
+    let path = url
+        .parse::<http::Uri>()
+        .map(|uri| uri.path().to_owned())
+        .unwrap_or_default();
+
+    let segment = path
+        .rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or_default();
+
+    sanitize_file_name(segment).unwrap_or_else(|| "response".to_owned())
+}
+
+#[cfg(test)]
+mod test_body_file_name {
+    use super::body_file_name;
+
+    #[test]
+    fn it_should_use_last_path_segment() {
+        assert_eq!(
+            body_file_name("https://mhouge.dk/images/logo.png"),
+            "logo.png"
+        );
+        assert_eq!(
+            body_file_name("https://mhouge.dk/files/report/?page=1"),
+            "report"
+        );
+        assert_eq!(body_file_name("https://mhouge.dk/a%20b.bin"), "a_20b.bin");
+    }
+
+    #[test]
+    fn it_should_fallback_to_response() {
+        for url in [
+            "https://mhouge.dk/",
+            "https://mhouge.dk",
+            "https://mhouge.dk/..",
+        ] {
+            assert_eq!(body_file_name(url), "response");
+        }
+    }
+}
+
+/// Writes the raw response body to the directory, overwriting existing files.
+#[inline]
+pub fn save_response_body(
+    dir: &std::path::Path,
+    url: &str,
+    body: &[u8],
+) -> Result<std::path::PathBuf, HittCliError> {
+    std::fs::create_dir_all(dir).map_err(|err| HittCliError::IoWrite(dir.to_owned(), err))?;
+
+    let path = dir.join(body_file_name(url));
+
+    std::fs::write(&path, body).map_err(|err| HittCliError::IoWrite(path.clone(), err))?;
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test_save_response_body {
+    use super::save_response_body;
+
+    #[test]
+    fn it_should_write_raw_bytes() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to return a valid dir");
+
+        let output_dir = dir.path().join("nested");
+
+        let body = b"\x89PNG\r\n\x1a\n\0\xff";
+
+        let path = save_response_body(&output_dir, "https://mhouge.dk/logo.png", body)
+            .expect("it to write the body");
+
+        assert_eq!(path, output_dir.join("logo.png"));
+
+        assert_eq!(std::fs::read(path).expect("it to read the file"), body);
+    }
+}
+
+/// File extension matching the content type, falling back to `bin`.
+#[inline]
+fn content_type_extension(content_type: Option<&str>) -> String {
+    let essence = content_type
+        .and_then(|value| value.split(';').next())
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+
+    let subtype = essence.split_once('/').map_or("", |(_, subtype)| subtype);
+
+    // structured syntax suffixes, such as `application/problem+json`
+    let subtype = subtype
+        .rsplit_once('+')
+        .map_or(subtype, |(_, suffix)| suffix);
+
+    match subtype {
+        "plain" => "txt".to_owned(),
+        "javascript" | "ecmascript" => "js".to_owned(),
+        "jpeg" => "jpg".to_owned(),
+        "" | "octet-stream" => "bin".to_owned(),
+        _ if subtype.chars().all(|ch| ch.is_ascii_alphanumeric()) => subtype.to_owned(),
+        _ => "bin".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod test_content_type_extension {
+    use super::content_type_extension;
+
+    #[test]
+    fn it_should_map_content_types() {
+        for (content_type, extension) in [
+            (Some("application/json; charset=utf-8"), "json"),
+            (Some("application/problem+json"), "json"),
+            (Some("text/plain"), "txt"),
+            (Some("text/HTML"), "html"),
+            (Some("image/png"), "png"),
+            (Some("image/jpeg"), "jpg"),
+            (Some("application/x-protobuf"), "bin"),
+            (Some("application/octet-stream"), "bin"),
+            (Some("mads"), "bin"),
+            (None, "bin"),
+        ] {
+            assert_eq!(content_type_extension(content_type), extension);
+        }
+    }
+}
+
+/// Name of the file a response is written to when using `--output-dir`, without the extension.
+///
+/// The name of the request is used if it has one, otherwise the name of the `.http` file and the
+/// position of the request in the file.
+#[inline]
+pub fn output_file_stem(
+    request_name: Option<&str>,
+    http_file: &std::path::Path,
+    request_index: usize,
+) -> String {
+    request_name
+        .and_then(sanitize_file_name)
+        .unwrap_or_else(|| {
+            let stem = http_file
+                .file_stem()
+                .map(|stem| stem.to_string_lossy())
+                .and_then(|stem| sanitize_file_name(&stem))
+                .unwrap_or_else(|| "response".to_owned());
+
+            format!("{stem}-{}", request_index + 1)
+        })
+}
+
+#[cfg(test)]
+mod test_output_file_stem {
+    use super::output_file_stem;
+
+    #[test]
+    fn it_should_use_request_name() {
+        assert_eq!(
+            output_file_stem(Some("get user"), std::path::Path::new("api/users.http"), 3),
+            "get_user"
+        );
+    }
+
+    #[test]
+    fn it_should_fallback_to_file_name() {
+        assert_eq!(
+            output_file_stem(None, std::path::Path::new("api/users.http"), 0),
+            "users-1"
+        );
+
+        assert_eq!(
+            output_file_stem(Some(".."), std::path::Path::new("users.http"), 1),
+            "users-2"
+        );
+    }
+}
+
+/// Returns the stem, adding a number if it is already used by another request of the run.
+///
+/// Requests with the same name, or in files with the same name, would otherwise write to the same
+/// file.
+#[inline]
+pub fn unique_file_stem(
+    used_stems: &mut std::collections::HashSet<String>,
+    stem: String,
+) -> String {
+    let mut unique_stem = stem.clone();
+
+    let mut number = 1;
+
+    while !used_stems.insert(unique_stem.clone()) {
+        number += 1;
+
+        unique_stem = format!("{stem}-{number}");
+    }
+
+    unique_stem
+}
+
+#[cfg(test)]
+mod test_unique_file_stem {
+    use super::unique_file_stem;
+
+    #[test]
+    fn it_should_number_duplicate_stems() {
+        let mut used_stems = std::collections::HashSet::new();
+
+        assert_eq!(
+            vec![
+                "users-1",
+                "users-1-2",
+                "get-user",
+                "users-1-3",
+                "get-user-2"
+            ],
+            ["users-1", "users-1", "get-user", "users-1", "get-user"]
+                .into_iter()
+                .map(|stem| unique_file_stem(&mut used_stems, stem.to_owned()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_should_skip_numbered_stems_already_used() {
+        let mut used_stems = std::collections::HashSet::from(["users-1-2".to_owned()]);
+
+        unique_file_stem(&mut used_stems, "users-1".to_owned());
+
+        assert_eq!(
+            "users-1-3",
+            unique_file_stem(&mut used_stems, "users-1".to_owned())
+        );
+    }
+}
+
+/// Name of the file a response is written to when using `--output-dir`, with the extension based
+/// on the `content-type` header.
+#[inline]
+pub fn output_file_name(stem: &str, content_type: Option<&str>) -> String {
+    format!("{stem}.{}", content_type_extension(content_type))
+}
+
+#[cfg(test)]
+mod test_output_file_name {
+    use super::output_file_name;
+
+    #[test]
+    fn it_should_add_extension() {
+        assert_eq!(
+            output_file_name("get_user", Some("application/json")),
+            "get_user.json"
+        );
+
+        assert_eq!(output_file_name("users-2", None), "users-2.bin");
+    }
+}
+
+/// Writes the raw response body to the path, and the status line and headers to `<path>.headers`
+/// if `include_headers` is set.
+///
+/// Sensitive headers and secrets are redacted from the headers file.
+#[inline]
+pub fn write_response(
+    path: &std::path::Path,
+    response: &hitt_request::HittResponse,
+    include_headers: bool,
+    secrets: &[String],
+) -> Result<(), HittCliError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| HittCliError::IoWrite(parent.to_owned(), err))?;
+    }
+
+    std::fs::write(path, &response.raw_body)
+        .map_err(|err| HittCliError::IoWrite(path.to_owned(), err))?;
+
+    if include_headers {
+        let mut headers = format!("{:?} {}\n", response.http_version, response.status_code);
+
+        for (key, value) in &response.headers {
+            let value_str = if is_sensitive_header(key) {
+                REDACTED.to_owned()
+            } else {
+                redact_secrets(&String::from_utf8_lossy(value.as_bytes()), secrets).into_owned()
+            };
+
+            headers.push_str(&format!("{key}: {value_str}\n"));
+        }
+
+        let mut headers_path = path.as_os_str().to_owned();
+        headers_path.push(".headers");
+
+        let headers_path = std::path::PathBuf::from(headers_path);
+
+        std::fs::write(&headers_path, headers)
+            .map_err(|err| HittCliError::IoWrite(headers_path.clone(), err))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_write_response {
+    use hitt_request::HittResponse;
+    use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+
+    use super::write_response;
+
+    #[test]
+    fn it_should_write_body_and_headers() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to return a valid dir");
+
+        let response = HittResponse {
+            url: "https://mhouge.dk/".to_owned(),
+            method: "GET".to_owned(),
+            status_code: StatusCode::OK,
+            duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
+            headers: HeaderMap::from_iter([
+                (
+                    HeaderName::from_static("content-type"),
+                    HeaderValue::from_static("application/json"),
+                ),
+                (
+                    HeaderName::from_static("set-cookie"),
+                    HeaderValue::from_static("session=abc"),
+                ),
+                (
+                    HeaderName::from_static("x-token"),
+                    HeaderValue::from_static("abc123"),
+                ),
+            ]),
+            http_version: http::Version::HTTP_11,
+            body: "{\"key\": \"value\"}".to_owned(),
+            raw_body: b"{\"key\": \"value\"}".to_vec(),
+            truncated: false,
+        };
+
+        let path = dir.path().join("out/user.json");
+
+        write_response(&path, &response, true, &["abc123".to_owned()])
+            .expect("it to write the response");
+
+        assert_eq!(
+            std::fs::read(&path).expect("it to read the body"),
+            response.raw_body
+        );
+
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("out/user.json.headers"))
+                .expect("it to read the headers"),
+            "HTTP/1.1 200 OK\ncontent-type: application/json\nset-cookie: <redacted>\nx-token: <redacted>\n"
+        );
+    }
+
+    #[test]
+    fn it_should_only_write_headers_if_enabled() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to return a valid dir");
+
+        let response = HittResponse {
+            url: "https://mhouge.dk/".to_owned(),
+            method: "GET".to_owned(),
+            status_code: StatusCode::OK,
+            duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
+            headers: HeaderMap::new(),
+            http_version: http::Version::HTTP_11,
+            body: "mads was here".to_owned(),
+            raw_body: b"mads was here".to_vec(),
+            truncated: false,
+        };
+
+        let path = dir.path().join("out.txt");
+
+        write_response(&path, &response, false, &[]).expect("it to write the response");
+
+        assert!(std::fs::exists(&path).expect("it to check the path"));
+        assert!(
+            !std::fs::exists(dir.path().join("out.txt.headers")).expect("it to check the path")
+        );
+    }
 }
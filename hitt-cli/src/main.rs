@@ -6,6 +6,7 @@ use crossterm::{
     style::{Print, Stylize},
 };
 
+mod bench;
 mod commands;
 mod config;
 mod error;
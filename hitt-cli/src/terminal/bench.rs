@@ -0,0 +1,233 @@
+use crossterm::{
+    queue,
+    style::{Print, Stylize},
+};
+
+use crate::bench::{BenchReport, build_histogram, percentile};
+
+const HISTOGRAM_BUCKETS: usize = 10;
+
+const HISTOGRAM_WIDTH: usize = 40;
+
+#[inline]
+pub fn print_bench_start<W: std::io::Write + Send>(
+    term: &mut W,
+    method: &str,
+    url: &str,
+    description: &str,
+) -> std::io::Result<()> {
+    queue!(
+        term,
+        Print(format!("hitt: benchmarking {method} {url} - {description}\n").cyan())
+    )?;
+
+    term.flush()
+}
+
+#[inline]
+fn format_latency(duration: core::time::Duration) -> String {
+    if duration < core::time::Duration::from_secs(1) {
+        format!("{:.2}ms", duration.as_secs_f64() * 1000.0)
+    } else {
+        format!("{:.2}s", duration.as_secs_f64())
+    }
+}
+
+#[cfg(test)]
+mod test_format_latency {
+    use core::time::Duration;
+
+    use super::format_latency;
+
+    #[test]
+    fn it_should_format_latencies() {
+        assert_eq!("0.25ms", format_latency(Duration::from_micros(250)));
+        assert_eq!("123.00ms", format_latency(Duration::from_millis(123)));
+        assert_eq!("1.50s", format_latency(Duration::from_millis(1500)));
+    }
+}
+
+#[inline]
+pub fn print_bench_report<W: std::io::Write + Send>(
+    term: &mut W,
+    report: &BenchReport,
+) -> std::io::Result<()> {
+    queue!(
+        term,
+        Print(format!(
+            "\nrequests  {} in {}, {:.1} req/s\n",
+            report.request_count(),
+            format_latency(report.elapsed),
+            report.throughput()
+        ))
+    )?;
+
+    if !report.status_codes.is_empty() {
+        queue!(term, Print("status    "))?;
+
+        for (index, (status_code, count)) in report.status_codes.iter().enumerate() {
+            if index > 0 {
+                queue!(term, Print(", "))?;
+            }
+
+            let text = format!("{status_code} x {count}");
+
+            queue!(
+                term,
+                Print(if *status_code < 400 {
+                    text.green()
+                } else {
+                    text.red()
+                })
+            )?;
+        }
+
+        queue!(term, Print('\n'))?;
+    }
+
+    if !report.errors.is_empty() {
+        queue!(term, Print("errors    "))?;
+
+        for (index, (error, count)) in report.errors.iter().enumerate() {
+            if index > 0 {
+                queue!(term, Print(", "))?;
+            }
+
+            queue!(term, Print(format!("{error} x {count}").red()))?;
+        }
+
+        queue!(term, Print('\n'))?;
+    }
+
+    let percentiles = [("p50", 50.0), ("p90", 90.0), ("p99", 99.0), ("max", 100.0)]
+        .iter()
+        .filter_map(|(name, value)| {
+            percentile(&report.latencies, *value)
+                .map(|latency| format!("{name} {}", format_latency(latency)))
+        })
+        .collect::<Vec<_>>();
+
+    if percentiles.is_empty() {
+        return Ok(());
+    }
+
+    queue!(
+        term,
+        Print(format!("latency   {}\n\n", percentiles.join(", ")))
+    )?;
+
+    let buckets = build_histogram(&report.latencies, HISTOGRAM_BUCKETS);
+
+    let max_count = buckets
+        .iter()
+        .map(|bucket| bucket.count)
+        .max()
+        .unwrap_or_default();
+
+    let ranges = buckets
+        .iter()
+        .map(|bucket| {
+            (
+                format_latency(bucket.start),
+                format_latency(bucket.end),
+                bucket.count,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let start_width = ranges
+        .iter()
+        .map(|(start, _, _)| start.len())
+        .max()
+        .unwrap_or_default();
+
+    let end_width = ranges
+        .iter()
+        .map(|(_, end, _)| end.len())
+        .max()
+        .unwrap_or_default();
+
+    for (start, end, count) in ranges {
+        let bar_width = (count * HISTOGRAM_WIDTH).div_ceil(max_count.max(1));
+
+        queue!(
+            term,
+            Print(format!("{start:>start_width$} - {end:>end_width$} ")),
+            Print("█".repeat(bar_width).dark_grey()),
+            Print(format!(" {count}\n"))
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_print_bench_report {
+    use core::time::Duration;
+
+    use super::print_bench_report;
+    use crate::bench::{BenchError, BenchOutcome, build_report};
+
+    #[test]
+    fn it_should_print_statistics() {
+        let report = build_report(
+            vec![
+                (
+                    Duration::from_millis(10),
+                    BenchOutcome::Response(reqwest::StatusCode::OK),
+                ),
+                (
+                    Duration::from_millis(20),
+                    BenchOutcome::Response(reqwest::StatusCode::OK),
+                ),
+                (
+                    Duration::from_millis(110),
+                    BenchOutcome::Response(reqwest::StatusCode::SERVICE_UNAVAILABLE),
+                ),
+                (
+                    Duration::from_secs(1),
+                    BenchOutcome::Error(BenchError::Connection),
+                ),
+            ],
+            Duration::from_secs(2),
+        );
+
+        let mut term = Vec::new();
+
+        print_bench_report(&mut term, &report).expect("it to print the report");
+
+        let output = String::from_utf8_lossy(&term);
+
+        for expected in [
+            "requests  4 in 2.00s, 2.0 req/s\n",
+            "status    \u{1b}[38;5;10m200 x 2\u{1b}[39m, \u{1b}[38;5;9m503 x 1\u{1b}[39m\n",
+            "errors    \u{1b}[38;5;9mconnection error x 1\u{1b}[39m\n",
+            "latency   p50 20.00ms, p90 110.00ms, p99 110.00ms, max 110.00ms\n",
+            " 10.00ms -  20.00ms ",
+            "100.00ms - 110.00ms ",
+        ] {
+            assert!(output.contains(expected), "{expected:?} in {output:?}");
+        }
+    }
+
+    #[test]
+    fn it_should_skip_latency_without_responses() {
+        let report = build_report(
+            vec![(
+                Duration::from_secs(1),
+                BenchOutcome::Error(BenchError::Timeout),
+            )],
+            Duration::from_secs(1),
+        );
+
+        let mut term = Vec::new();
+
+        print_bench_report(&mut term, &report).expect("it to print the report");
+
+        let output = String::from_utf8_lossy(&term);
+
+        assert!(output.contains("errors    "));
+        assert!(!output.contains("status    "));
+        assert!(!output.contains("latency   "));
+    }
+}
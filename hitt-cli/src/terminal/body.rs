@@ -4,6 +4,8 @@ use crossterm::{
 };
 use hitt_formatter::ContentType;
 
+use crate::terminal::redact::redact_secrets;
+
 #[inline]
 fn __print_body<W: std::io::Write + Send>(term: &mut W, body: &str) -> std::io::Result<()> {
     queue!(term, Print('\n'), Print(body.dark_yellow()), Print("\n\n"))
@@ -123,3 +125,557 @@ mod test_print_body {
         term.clear();
     }
 }
+
+const TEXT_MIME_SUBTYPES: [&str; 7] = [
+    "json",
+    "xml",
+    "javascript",
+    "ecmascript",
+    "x-www-form-urlencoded",
+    "yaml",
+    "graphql",
+];
+
+const HEX_DUMP_MAX_BYTES: usize = 64;
+
+#[inline]
+fn is_text_content_type(content_type: &str) -> bool {
+    let essence = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase();
+
+    let Some((kind, subtype)) = essence.split_once('/') else {
+        return false;
+    };
+
+    kind == "text"
+        || TEXT_MIME_SUBTYPES
+            .iter()
+            .any(|text_subtype| subtype.contains(text_subtype))
+}
+
+/// Whether the body should be summarized instead of printed.
+///
+/// Bodies with a textual content type are never treated as binary, otherwise the body is binary
+/// if it is not valid UTF-8 or contains control characters.
+#[inline]
+pub fn is_binary_body(content_type: Option<&str>, body: &[u8]) -> bool {
+    if content_type.is_some_and(is_text_content_type) {
+        return false;
+    }
+
+    core::str::from_utf8(body).map_or(true, |text| {
+        text.chars()
+            .any(|ch| ch.is_control() && !matches!(ch, '\t' | '\n' | '\r' | '\x0c' | '\x1b'))
+    })
+}
+
+#[cfg(test)]
+mod test_is_binary_body {
+    use super::is_binary_body;
+
+    #[test]
+    fn it_should_detect_binary_bodies() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+        assert!(is_binary_body(Some("image/png"), png));
+        assert!(is_binary_body(None, png));
+        assert!(is_binary_body(
+            Some("application/x-protobuf"),
+            b"\x08\x96\x01"
+        ));
+        assert!(is_binary_body(Some("application/octet-stream"), b"mads\0"));
+    }
+
+    #[test]
+    fn it_should_allow_text_bodies() {
+        assert!(!is_binary_body(None, b"mads was here\n"));
+        assert!(!is_binary_body(None, "æøå".as_bytes()));
+        assert!(!is_binary_body(
+            Some("application/json; charset=utf-8"),
+            b"{\"key\": \"value\"}"
+        ));
+        assert!(!is_binary_body(Some("text/plain"), b"\xff\xfe"));
+        assert!(!is_binary_body(Some("application/problem+json"), b"{}"));
+    }
+}
+
+#[inline]
+#[allow(clippy::cast_precision_loss)]
+pub fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+
+    for next_unit in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+
+        size /= 1024.0;
+        unit = next_unit;
+    }
+
+    format!("{size:.1} {unit}")
+}
+
+#[cfg(test)]
+mod test_format_size {
+    use super::format_size;
+
+    #[test]
+    fn it_should_format_sizes() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1024), "1.0 KiB");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+    }
+}
+
+/// Formats the bytes as `offset  hex bytes  |ascii|` lines of 16 bytes.
+#[inline]
+fn hex_dump(bytes: &[u8]) -> String {
+    let mut output = String::new();
+
+    for (index, chunk) in bytes.chunks(16).enumerate() {
+        let hex = (0..16)
+            .map(|i| {
+                let byte = chunk
+                    .get(i)
+                    .map_or_else(|| "  ".to_owned(), |b| format!("{b:02x}"));
+
+                if i == 8 { format!(" {byte}") } else { byte }
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let ascii = chunk
+            .iter()
+            .map(|b| {
+                if b.is_ascii_graphic() || *b == b' ' {
+                    char::from(*b)
+                } else {
+                    '.'
+                }
+            })
+            .collect::<String>();
+
+        output.push_str(&format!("{:08x}  {hex}  |{ascii}|\n", index * 16));
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test_hex_dump {
+    use super::hex_dump;
+
+    #[test]
+    fn it_should_dump_bytes() {
+        assert_eq!(
+            hex_dump(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\x01"),
+            "00000000  89 50 4e 47 0d 0a 1a 0a  00 00 00 0d 49 48 44 52  |.PNG........IHDR|\n00000010  00 00 01                                          |...|\n"
+        );
+    }
+}
+
+/// Prints the size, content type and the first bytes of a binary body.
+#[inline]
+pub fn print_binary_body<W: std::io::Write + Send>(
+    term: &mut W,
+    body: &[u8],
+    content_type: Option<&str>,
+    saved_path: Option<&std::path::Path>,
+) -> std::io::Result<()> {
+    let mut summary = format!(
+        "binary body - {} - {}",
+        format_size(body.len()),
+        content_type.unwrap_or("unknown content type")
+    );
+
+    if let Some(path) = saved_path {
+        summary.push_str(&format!(" - saved to '{}'", path.display()));
+    }
+
+    let dump = hex_dump(&body[..body.len().min(HEX_DUMP_MAX_BYTES)]);
+
+    queue!(
+        term,
+        Print('\n'),
+        Print(summary.dark_grey()),
+        Print('\n'),
+        Print(dump.dark_yellow()),
+        Print('\n')
+    )
+}
+
+#[cfg(test)]
+mod test_print_binary_body {
+    use std::io::Write;
+
+    use super::print_binary_body;
+
+    #[test]
+    fn it_should_print_summary() {
+        let mut term = Vec::new();
+
+        let body = [0_u8; 100];
+
+        print_binary_body(
+            &mut term,
+            &body,
+            Some("image/png"),
+            Some(std::path::Path::new("out/image.png")),
+        )
+        .expect("it not to return an error");
+
+        term.flush().expect("it to flush");
+
+        let output = String::from_utf8_lossy(&term);
+
+        assert!(output.contains("binary body - 100 B - image/png - saved to 'out/image.png'"));
+
+        // only the first 64 bytes are dumped
+        assert!(output.contains("00000030  00"));
+        assert!(!output.contains("00000040"));
+    }
+}
+
+/// Longest partial line held back while streaming, before it is printed without formatting.
+const STREAM_LINE_MAX_BYTES: usize = 64 * 1024;
+
+/// Prints a body while it is received.
+///
+/// The body is printed a line at a time, so secrets can be redacted. Lines of JSON bodies that are
+/// valid JSON on their own, such as newline delimited JSON, are pretty printed.
+pub struct StreamingBodyPrinter<'a> {
+    content_type_header: Option<String>,
+    is_json: bool,
+    secrets: &'a [String],
+    pending: Vec<u8>,
+    /// Decided by the first chunk, binary bodies are not printed while streaming.
+    is_binary: Option<bool>,
+}
+
+impl<'a> StreamingBodyPrinter<'a> {
+    #[inline]
+    pub fn new(
+        content_type_header: Option<&str>,
+        disable_pretty_printing: bool,
+        secrets: &'a [String],
+    ) -> Self {
+        let is_json = !disable_pretty_printing
+            && content_type_header.map(ContentType::from) == Some(ContentType::Json);
+
+        Self {
+            content_type_header: content_type_header.map(str::to_owned),
+            is_json,
+            secrets,
+            pending: Vec::new(),
+            is_binary: None,
+        }
+    }
+
+    /// Whether the body was detected as binary, and should be summarized once it is read.
+    #[inline]
+    pub fn is_binary(&self) -> bool {
+        self.is_binary == Some(true)
+    }
+
+    #[inline]
+    fn print_line<W: std::io::Write + Send>(
+        &self,
+        term: &mut W,
+        line: &[u8],
+        is_complete: bool,
+    ) -> std::io::Result<()> {
+        let text = String::from_utf8_lossy(line);
+        let text = redact_secrets(&text, self.secrets);
+
+        let formatted = if is_complete
+            && self.is_json
+            && serde_json::from_str::<serde_json::Value>(&text).is_ok()
+        {
+            hitt_formatter::format(&text, ContentType::Json)
+        } else {
+            None
+        };
+
+        queue!(
+            term,
+            Print(formatted.as_deref().unwrap_or(&text).dark_yellow())
+        )?;
+
+        if is_complete {
+            queue!(term, Print('\n'))?;
+        }
+
+        Ok(())
+    }
+
+    /// Length of the start of the pending line that can be printed, without splitting a secret
+    /// or a character.
+    #[inline]
+    fn printable_len(&self) -> usize {
+        let longest_secret = self
+            .secrets
+            .iter()
+            .map(String::len)
+            .max()
+            .unwrap_or_default();
+
+        let search_start = self
+            .pending
+            .len()
+            .saturating_sub(longest_secret.saturating_sub(1));
+
+        let mut len = (search_start..self.pending.len())
+            .find(|index| {
+                let tail = &self.pending[*index..];
+
+                self.secrets
+                    .iter()
+                    .any(|secret| !secret.is_empty() && secret.as_bytes().starts_with(tail))
+            })
+            .unwrap_or(self.pending.len());
+
+        // utf-8 continuation bytes start with 0b10
+        while len > 0 && len < self.pending.len() && (self.pending[len] & 0xC0) == 0x80 {
+            len -= 1;
+        }
+
+        len
+    }
+
+    #[inline]
+    pub fn write_chunk<W: std::io::Write + Send>(
+        &mut self,
+        term: &mut W,
+        chunk: &[u8],
+    ) -> std::io::Result<()> {
+        if self.is_binary.is_none() {
+            // a chunk can end in the middle of a character
+            let sample = match core::str::from_utf8(chunk) {
+                Err(error) if error.error_len().is_none() => &chunk[..error.valid_up_to()],
+                _ => chunk,
+            };
+
+            let is_binary = is_binary_body(self.content_type_header.as_deref(), sample);
+
+            self.is_binary = Some(is_binary);
+
+            if !is_binary {
+                queue!(term, Print('\n'))?;
+            }
+        }
+
+        if self.is_binary() {
+            return Ok(());
+        }
+
+        self.pending.extend_from_slice(chunk);
+
+        while let Some(index) = self.pending.iter().position(|byte| *byte == b'\n') {
+            let line = self.pending.drain(..=index).collect::<Vec<_>>();
+
+            self.print_line(term, &line[..index], true)?;
+        }
+
+        if self.pending.len() > STREAM_LINE_MAX_BYTES {
+            let len = self.printable_len();
+
+            let partial = self.pending.drain(..len).collect::<Vec<_>>();
+
+            self.print_line(term, &partial, false)?;
+        }
+
+        term.flush()
+    }
+
+    #[inline]
+    pub fn finish<W: std::io::Write + Send>(self, term: &mut W) -> std::io::Result<()> {
+        if self.is_binary != Some(false) {
+            return Ok(());
+        }
+
+        if !self.pending.is_empty() {
+            self.print_line(term, &self.pending, true)?;
+        }
+
+        queue!(term, Print('\n'))?;
+
+        term.flush()
+    }
+}
+
+#[cfg(test)]
+mod test_streaming_body_printer {
+    use super::StreamingBodyPrinter;
+
+    fn print_chunks(
+        content_type: Option<&str>,
+        disable_pretty_printing: bool,
+        secrets: &[String],
+        chunks: &[&[u8]],
+    ) -> (String, bool) {
+        let mut term = Vec::new();
+
+        let mut printer = StreamingBodyPrinter::new(content_type, disable_pretty_printing, secrets);
+
+        for chunk in chunks {
+            printer
+                .write_chunk(&mut term, chunk)
+                .expect("it to print the chunk");
+        }
+
+        let is_binary = printer.is_binary();
+
+        printer.finish(&mut term).expect("it to finish");
+
+        (
+            String::from_utf8_lossy(&term)
+                .replace("\x1B[38;5;3m", "")
+                .replace("\x1B[39m", ""),
+            is_binary,
+        )
+    }
+
+    #[test]
+    fn it_should_print_lines_as_they_arrive() {
+        let mut term = Vec::new();
+
+        let mut printer = StreamingBodyPrinter::new(Some("text/plain"), false, &[]);
+
+        printer
+            .write_chunk(&mut term, b"mads was")
+            .expect("it to print the chunk");
+
+        assert_eq!("\n", String::from_utf8_lossy(&term));
+
+        printer
+            .write_chunk(&mut term, b" here\nand ")
+            .expect("it to print the chunk");
+
+        assert_eq!(
+            "\n\x1B[38;5;3mmads was here\x1B[39m\n",
+            String::from_utf8_lossy(&term)
+        );
+
+        assert_eq!(
+            ("\nmads was here\nand there\n\n".to_owned(), false),
+            print_chunks(
+                Some("text/plain"),
+                false,
+                &[],
+                &[b"mads was", b" here\nand ", b"there"]
+            )
+        );
+    }
+
+    #[test]
+    fn it_should_format_json_lines() {
+        assert_eq!(
+            "\n{\n  \"id\": 1\n}\n{\n  \"id\": 2\n}\n{\"id\": \n\n",
+            print_chunks(
+                Some("application/json"),
+                false,
+                &[],
+                &[b"{\"id\":1}\n{\"id\"", b":2}\n{\"id\": "]
+            )
+            .0
+        );
+
+        assert_eq!(
+            "\n{\"id\":1}\n\n",
+            print_chunks(Some("application/json"), true, &[], &[b"{\"id\":1}"]).0
+        );
+    }
+
+    #[test]
+    fn it_should_redact_secrets_split_across_chunks() {
+        let secrets = ["hunter2".to_owned()];
+
+        assert_eq!(
+            "\nthe password is <redacted>\n\n",
+            print_chunks(
+                Some("text/plain"),
+                false,
+                &secrets,
+                &[b"the password is hun", b"ter2"]
+            )
+            .0
+        );
+
+        let long_line = [b'a'; super::STREAM_LINE_MAX_BYTES];
+
+        let (output, _) = print_chunks(
+            Some("text/plain"),
+            false,
+            &secrets,
+            &[&long_line, b"hunt", b"er2"],
+        );
+
+        assert!(output.ends_with("a<redacted>\n\n"));
+        assert!(!output.contains("hunt"));
+    }
+
+    #[test]
+    fn it_should_skip_binary_bodies() {
+        assert_eq!(
+            (String::new(), true),
+            print_chunks(Some("image/png"), false, &[], &[b"\x89PNG\r\n\x1a\n\0\0"])
+        );
+    }
+
+    #[test]
+    fn it_should_allow_characters_split_across_chunks() {
+        let (output, is_binary) = print_chunks(None, false, &[], &[b"\xc3", b"\xa6\xc3\xb8"]);
+
+        assert!(!is_binary);
+        assert_eq!("\næø\n\n", output);
+    }
+}
+
+/// Prints a warning that the body was longer than `--max-body-size`.
+#[inline]
+pub fn print_truncated_body<W: std::io::Write + Send>(
+    term: &mut W,
+    max_body_size: usize,
+) -> std::io::Result<()> {
+    queue!(
+        term,
+        Print(
+            format!(
+                "hitt: warning - stopped reading the body after {} (--max-body-size)\n",
+                format_size(max_body_size)
+            )
+            .yellow()
+        )
+    )
+}
+
+#[cfg(test)]
+mod test_print_truncated_body {
+    use super::print_truncated_body;
+
+    #[test]
+    fn it_should_print_warning() {
+        let mut term = Vec::new();
+
+        print_truncated_body(&mut term, 10 * 1024 * 1024).expect("it not to return an error");
+
+        assert_eq!(
+            "\x1B[38;5;11mhitt: warning - stopped reading the body after 10.0 MiB (--max-body-size)\n\x1B[39m",
+            String::from_utf8_lossy(&term)
+        );
+    }
+}
@@ -3,13 +3,27 @@ use crossterm::{
     style::{Print, Stylize},
 };
 
+use super::redact::{REDACTED, is_sensitive_header, redact_secrets};
+
+/// Prints the headers, except `set-cookie` headers which are printed by [`print_cookies`].
 #[inline]
 pub fn print_headers<W: std::io::Write + Send>(
     term: &mut W,
     headers: &reqwest::header::HeaderMap,
+    secrets: &[String],
 ) -> std::io::Result<()> {
     for (key, value) in headers {
-        let value_str = String::from_utf8_lossy(value.as_ref());
+        if key == reqwest::header::SET_COOKIE {
+            continue;
+        }
+
+        let raw_value = String::from_utf8_lossy(value.as_ref());
+
+        let value_str = if is_sensitive_header(key) {
+            std::borrow::Cow::Borrowed(REDACTED)
+        } else {
+            redact_secrets(&raw_value, secrets)
+        };
 
         let line = format!("{}: {value_str}\n", key.to_string().dark_yellow());
 
@@ -38,7 +52,7 @@ mod test_print_headers {
 
         headers.insert(HeaderName::from_static(n), HeaderValue::from_static(v));
 
-        print_headers(&mut term, &headers).expect("it to not error");
+        print_headers(&mut term, &headers, &[]).expect("it to not error");
 
         term.flush().expect("it to flush");
 
@@ -49,4 +63,150 @@ mod test_print_headers {
 
         term.clear();
     }
+
+    #[test]
+    fn it_should_redact_sensitive_headers() {
+        let mut term = Vec::new();
+
+        let mut headers = HeaderMap::new();
+
+        headers.insert(
+            HeaderName::from_static("authorization"),
+            HeaderValue::from_static("Bearer abc123"),
+        );
+
+        print_headers(&mut term, &headers, &[]).expect("it to not error");
+
+        term.flush().expect("it to flush");
+
+        assert_eq!(
+            "\x1B[38;5;3mauthorization\x1B[39m: <redacted>\n",
+            String::from_utf8_lossy(&term)
+        );
+    }
+
+    #[test]
+    fn it_should_skip_set_cookie_headers() {
+        let mut term = Vec::new();
+
+        let mut headers = HeaderMap::new();
+
+        headers.insert(
+            HeaderName::from_static("set-cookie"),
+            HeaderValue::from_static("session=abc123"),
+        );
+
+        print_headers(&mut term, &headers, &[]).expect("it to not error");
+
+        assert!(term.is_empty());
+    }
+
+    #[test]
+    fn it_should_redact_secret_values() {
+        let mut term = Vec::new();
+
+        let mut headers = HeaderMap::new();
+
+        headers.insert(
+            HeaderName::from_static("x-echo"),
+            HeaderValue::from_static("token abc123"),
+        );
+
+        print_headers(&mut term, &headers, &["abc123".to_owned()]).expect("it to not error");
+
+        term.flush().expect("it to flush");
+
+        assert_eq!(
+            "\x1B[38;5;3mx-echo\x1B[39m: token <redacted>\n",
+            String::from_utf8_lossy(&term)
+        );
+    }
+}
+
+/// Prints the cookies set using `set-cookie` headers, with their values redacted.
+#[inline]
+pub fn print_cookies<W: std::io::Write + Send>(
+    term: &mut W,
+    headers: &reqwest::header::HeaderMap,
+    secrets: &[String],
+) -> std::io::Result<()> {
+    for value in headers.get_all(reqwest::header::SET_COOKIE) {
+        let raw_value = String::from_utf8_lossy(value.as_ref());
+
+        let (cookie, attributes) = raw_value
+            .split_once(';')
+            .unwrap_or((raw_value.as_ref(), ""));
+
+        let (name, value) = cookie.split_once('=').unwrap_or((cookie, ""));
+
+        let line = format!(
+            "{} {}={}{}\n",
+            "cookie".dark_cyan(),
+            redact_secrets(name.trim(), secrets),
+            if value.trim().is_empty() {
+                ""
+            } else {
+                REDACTED
+            },
+            redact_secrets(attributes, secrets)
+                .split(';')
+                .map(str::trim)
+                .filter(|attribute| !attribute.is_empty())
+                .fold(String::new(), |output, attribute| format!(
+                    "{output}; {attribute}"
+                ))
+        );
+
+        queue!(term, Print(line))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_print_cookies {
+    use reqwest::header::{HeaderMap, HeaderValue, SET_COOKIE};
+
+    use super::print_cookies;
+
+    #[test]
+    fn it_should_print_cookies_without_values() {
+        let mut term = Vec::new();
+
+        let mut headers = HeaderMap::new();
+
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("session=abc123; Path=/;HttpOnly"),
+        );
+        headers.append(SET_COOKIE, HeaderValue::from_static("theme=; Max-Age=0"));
+        headers.insert("x-echo", HeaderValue::from_static("session=abc123"));
+
+        print_cookies(&mut term, &headers, &[]).expect("it to not error");
+
+        assert_eq!(
+            "\x1B[38;5;6mcookie\x1B[39m session=<redacted>; Path=/; HttpOnly\n\
+             \x1B[38;5;6mcookie\x1B[39m theme=; Max-Age=0\n",
+            String::from_utf8_lossy(&term)
+        );
+    }
+
+    #[test]
+    fn it_should_redact_secret_attributes() {
+        let mut term = Vec::new();
+
+        let mut headers = HeaderMap::new();
+
+        headers.append(
+            SET_COOKIE,
+            HeaderValue::from_static("session=abc; Domain=tenant-42.mhouge.dk"),
+        );
+
+        print_cookies(&mut term, &headers, &["tenant-42".to_owned()]).expect("it to not error");
+
+        assert_eq!(
+            "\x1B[38;5;6mcookie\x1B[39m session=<redacted>; Domain=<redacted>.mhouge.dk\n",
+            String::from_utf8_lossy(&term)
+        );
+    }
 }
@@ -1,19 +1,26 @@
-use body::print_body;
+use body::{
+    StreamingBodyPrinter, is_binary_body, print_binary_body, print_body, print_truncated_body,
+};
 use crossterm::{
     queue,
     style::{Print, Stylize},
 };
-use headers::print_headers;
+use headers::{print_cookies, print_headers};
 use hitt_formatter::ContentType;
+use hitt_parser::warning::RequestParseWarning;
 use hitt_request::HittResponse;
-use status::print_status;
+use redact::redact_secrets;
+use status::{print_redirect, print_status, print_timings};
 
-use crate::{config::RunCommandArguments, error::HittCliError};
+use crate::{config::RunCommandArguments, error::HittCliError, fs::save_response_body};
 
+pub mod bench;
 pub mod body;
 mod headers;
+pub mod prompt;
+pub mod redact;
 pub mod sse;
-mod status;
+pub mod status;
 
 #[inline]
 pub fn print_running_file<W: std::io::Write + Send>(
@@ -27,32 +34,152 @@ pub fn print_running_file<W: std::io::Write + Send>(
 }
 
 #[inline]
-pub fn handle_response<W: std::io::Write + Send>(
+pub fn print_saved_response<W: std::io::Write + Send>(
+    term: &mut W,
+    path: &std::path::Path,
+) -> std::io::Result<()> {
+    queue!(
+        term,
+        Print(format!("hitt: saved response to '{}'\n", path.display()).dark_grey())
+    )
+}
+
+#[inline]
+pub fn print_unsaved_response<W: std::io::Write + Send>(
+    term: &mut W,
+    path: &std::path::Path,
+    status_code: u16,
+) -> std::io::Result<()> {
+    queue!(
+        term,
+        Print(
+            format!(
+                "hitt: response not saved to '{}' - status code {status_code} is an error\n",
+                path.display()
+            )
+            .yellow()
+        )
+    )
+}
+
+#[inline]
+pub fn print_parse_warning<W: std::io::Write + Send>(
+    term: &mut W,
+    warning: &RequestParseWarning,
+) -> std::io::Result<()> {
+    queue!(term, Print(format!("hitt: warning - {warning}\n").yellow()))
+}
+
+#[cfg(test)]
+mod test_print_parse_warning {
+    use std::io::Write;
+
+    use hitt_parser::warning::RequestParseWarning;
+
+    use super::print_parse_warning;
+
+    #[test]
+    fn it_should_print_warning() {
+        let mut term = Vec::new();
+
+        print_parse_warning(
+            &mut term,
+            &RequestParseWarning::VariableShadowed("path".to_owned()),
+        )
+        .expect("it not to return an error");
+
+        term.flush().expect("it to flush");
+
+        assert_eq!(
+            "\x1B[38;5;11mhitt: warning - request variable 'path' shadows a variable declared at the top of the file\n\x1B[39m",
+            String::from_utf8_lossy(&term)
+        );
+    }
+}
+
+/// Prints the followed redirects, the status line and the headers of the response.
+#[inline]
+pub fn print_response_head<W: std::io::Write + Send>(
     term: &mut W,
     response: &HittResponse,
     args: &RunCommandArguments,
+    secrets: &[String],
 ) -> Result<(), HittCliError> {
+    for hop in &response.redirects {
+        print_redirect(term, hop, secrets)?;
+
+        if !args.hide_headers {
+            print_cookies(term, &hop.headers, secrets)?;
+        }
+    }
+
     print_status(
         term,
         response.http_version,
         &response.method,
-        &response.url,
+        &redact_secrets(&response.url, secrets),
         response.status_code.as_u16(),
         &response.duration,
     )?;
 
+    // the download time of streamed bodies is only known once the body is printed
+    if args.timings && !args.stream {
+        print_timings(term, &response.timings)?;
+    }
+
     if !args.hide_headers {
-        print_headers(term, &response.headers)?;
+        print_headers(term, &response.headers, secrets)?;
+        print_cookies(term, &response.headers, secrets)?;
+    }
+
+    Ok(())
+}
+
+#[inline]
+pub fn content_type_header(response: &HittResponse) -> Option<&str> {
+    response
+        .headers
+        .get("content-type")
+        .map(|value| value.to_str().unwrap_or_default())
+}
+
+/// Writes the body to `--save-binary`, and prints a summary of it unless the body is hidden.
+#[inline]
+fn print_binary_response<W: std::io::Write + Send>(
+    term: &mut W,
+    response: &HittResponse,
+    args: &RunCommandArguments,
+) -> Result<(), HittCliError> {
+    let saved_path = match &args.save_binary {
+        Some(dir) => Some(save_response_body(dir, &response.url, &response.raw_body)?),
+        None => None,
+    };
+
+    if !args.hide_body {
+        print_binary_body(
+            term,
+            &response.raw_body,
+            content_type_header(response),
+            saved_path.as_deref(),
+        )?;
     }
 
-    if !args.hide_body && !response.body.is_empty() {
-        let content_type = response
-            .headers
-            .get("content-type")
-            .map(|value| ContentType::from(value.to_str().unwrap_or_default()))
-            .unwrap_or_default();
+    Ok(())
+}
+
+/// Prints what is left after the body has been read, and checks `--fail-fast`.
+#[inline]
+fn finish_response<W: std::io::Write + Send>(
+    term: &mut W,
+    response: &HittResponse,
+    args: &RunCommandArguments,
+) -> Result<(), HittCliError> {
+    if response.truncated {
+        print_truncated_body(term, response.raw_body.len())?;
+    }
 
-        print_body(term, &response.body, content_type, args.disable_formatting)?;
+    if args.timings && args.stream {
+        print_timings(term, &response.timings)?;
     }
 
     if args.fail_fast
@@ -64,6 +191,136 @@ pub fn handle_response<W: std::io::Write + Send>(
     Ok(())
 }
 
+/// Finishes a response whose body was printed while it was received.
+///
+/// Without a printer, because the body is hidden, the body is only checked for being binary.
+#[inline]
+pub fn finish_streamed_response<W: std::io::Write + Send>(
+    term: &mut W,
+    response: &HittResponse,
+    args: &RunCommandArguments,
+    printer: Option<StreamingBodyPrinter<'_>>,
+) -> Result<(), HittCliError> {
+    let is_binary = match printer {
+        Some(printer) => {
+            let is_binary = printer.is_binary();
+
+            printer.finish(term)?;
+
+            is_binary
+        }
+        None => is_binary_body(content_type_header(response), &response.raw_body),
+    };
+
+    if is_binary {
+        print_binary_response(term, response, args)?;
+    }
+
+    finish_response(term, response, args)
+}
+
+#[cfg(test)]
+mod test_finish_streamed_response {
+    use std::io::Write;
+
+    use hitt_request::HittResponse;
+    use http::{HeaderMap, HeaderName, HeaderValue, StatusCode};
+
+    use crate::{
+        config::RunCommandArguments,
+        terminal::{body::StreamingBodyPrinter, finish_streamed_response},
+    };
+
+    fn build_response(content_type: &'static str, raw_body: &[u8]) -> HittResponse {
+        HittResponse {
+            url: "https://mhouge.dk/".to_owned(),
+            method: "GET".to_owned(),
+            status_code: StatusCode::OK,
+            duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
+            headers: HeaderMap::from_iter([(
+                HeaderName::from_static("content-type"),
+                HeaderValue::from_static(content_type),
+            )]),
+            http_version: http::Version::HTTP_11,
+            body: String::from_utf8_lossy(raw_body).into_owned(),
+            raw_body: raw_body.to_vec(),
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn it_should_finish_printed_bodies() {
+        let args = RunCommandArguments {
+            hide_headers: true,
+            stream: true,
+            ..crate::config::parse_run_arguments(&["requests.http"])
+        };
+
+        for (content_type, raw_body, expected) in [
+            (
+                "text/plain",
+                b"mads was here".as_slice(),
+                "\n\x1B[38;5;3mmads was here\x1B[39m\n\n",
+            ),
+            (
+                "image/png",
+                b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".as_slice(),
+                "\n\x1B[38;5;8mbinary body - 16 B - image/png\x1B[39m\n",
+            ),
+        ] {
+            let response = build_response(content_type, raw_body);
+
+            let mut term = Vec::new();
+
+            let mut printer = StreamingBodyPrinter::new(Some(content_type), false, &[]);
+
+            printer
+                .write_chunk(&mut term, raw_body)
+                .expect("it to print the chunk");
+
+            finish_streamed_response(&mut term, &response, &args, Some(printer))
+                .expect("it to be ok");
+
+            term.flush().expect("it to flush");
+
+            assert!(String::from_utf8_lossy(&term).starts_with(expected));
+        }
+    }
+}
+
+#[inline]
+pub fn handle_response<W: std::io::Write + Send>(
+    term: &mut W,
+    response: &HittResponse,
+    args: &RunCommandArguments,
+    secrets: &[String],
+) -> Result<(), HittCliError> {
+    print_response_head(term, response, args, secrets)?;
+
+    let content_type_header = content_type_header(response);
+
+    if !response.raw_body.is_empty() {
+        if is_binary_body(content_type_header, &response.raw_body) {
+            print_binary_response(term, response, args)?;
+        } else if !args.hide_body {
+            let content_type = content_type_header
+                .map(ContentType::from)
+                .unwrap_or_default();
+
+            print_body(
+                term,
+                &redact_secrets(&response.body, secrets),
+                content_type,
+                args.disable_formatting,
+            )?;
+        }
+    }
+
+    finish_response(term, response, args)
+}
+
 #[cfg(test)]
 mod test_handle_response {
     use std::io::Write;
@@ -83,6 +340,8 @@ mod test_handle_response {
             method: "GET".to_owned(),
             status_code: StatusCode::OK,
             duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
             headers: HeaderMap::from_iter([
                 (HeaderName::from_static(n), HeaderValue::from_static(v)),
                 (HeaderName::from_static(n), HeaderValue::from_static(n)),
@@ -91,24 +350,18 @@ mod test_handle_response {
             ]),
             http_version: http::Version::HTTP_11,
             body: "mads was here".to_owned(),
+            raw_body: b"mads was here".to_vec(),
+            truncated: false,
         };
 
         let args = RunCommandArguments {
             disable_formatting: true,
-            //
-            paths: vec![std::path::PathBuf::new()],
-            timeout: None,
-            var: None,
-            recursive: false,
-            fail_fast: false,
-            hide_body: false,
-            hide_headers: false,
-            vim: false,
+            ..crate::config::parse_run_arguments(&["requests.http"])
         };
 
         let mut term = Vec::new();
 
-        handle_response(&mut term, &response, &args).expect("it to be ok");
+        handle_response(&mut term, &response, &args, &[]).expect("it to be ok");
 
         let status = format!(
             "\x1b[38;5;10m\x1B[1m{:?} {} {} {} {}ms\n\x1B[0m",
@@ -142,29 +395,23 @@ mod test_handle_response {
             method: "GET".to_owned(),
             status_code: StatusCode::OK,
             duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
             headers: HeaderMap::from_iter([(
                 HeaderName::from_static(n),
                 HeaderValue::from_static(v),
             )]),
             http_version: http::Version::HTTP_11,
             body: "{\"key\": \"value\"}".to_owned(),
+            raw_body: b"{\"key\": \"value\"}".to_vec(),
+            truncated: false,
         };
 
-        let args = RunCommandArguments {
-            disable_formatting: false,
-            paths: vec![std::path::PathBuf::new()],
-            timeout: None,
-            var: None,
-            recursive: false,
-            fail_fast: false,
-            hide_body: false,
-            hide_headers: false,
-            vim: false,
-        };
+        let args = crate::config::parse_run_arguments(&["requests.http"]);
 
         let mut term = Vec::new();
 
-        handle_response(&mut term, &response, &args).expect("it to be ok");
+        handle_response(&mut term, &response, &args, &[]).expect("it to be ok");
 
         let status = format!(
             "\x1b[38;5;10m\x1B[1m{:?} {} {} {} {}ms\n\x1B[0m",
@@ -193,30 +440,27 @@ mod test_handle_response {
             method: "GET".to_owned(),
             status_code: StatusCode::OK,
             duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
             headers: HeaderMap::from_iter([(
                 HeaderName::from_static("mads"),
                 HeaderValue::from_static("hougesen"),
             )]),
             http_version: http::Version::HTTP_11,
             body: "mads was here".to_owned(),
+            raw_body: b"mads was here".to_vec(),
+            truncated: false,
         };
 
         let args = RunCommandArguments {
             hide_headers: true,
             disable_formatting: true,
-            //
-            paths: vec![std::path::PathBuf::new()],
-            timeout: None,
-            var: None,
-            recursive: false,
-            fail_fast: false,
-            hide_body: false,
-            vim: false,
+            ..crate::config::parse_run_arguments(&["requests.http"])
         };
 
         let mut term = Vec::new();
 
-        handle_response(&mut term, &response, &args).expect("it to be ok");
+        handle_response(&mut term, &response, &args, &[]).expect("it to be ok");
 
         let expected_response = format!(
             "\x1b[38;5;10m\x1B[1m{:?} {} {} {} {}ms\n\x1B[0m\n\x1B[38;5;3m{}\x1B[39m\n\n",
@@ -233,6 +477,70 @@ mod test_handle_response {
         assert_eq!(expected_response, String::from_utf8_lossy(&term));
     }
 
+    #[test]
+    fn it_should_only_print_cookies_with_headers() {
+        let cookie_headers = HeaderMap::from_iter([(
+            HeaderName::from_static("set-cookie"),
+            HeaderValue::from_static("session=abc123; Path=/"),
+        )]);
+
+        let response = HittResponse {
+            url: "https://mhouge.dk/".to_owned(),
+            method: "GET".to_owned(),
+            status_code: StatusCode::OK,
+            duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: vec![hitt_request::redirect::RedirectHop {
+                method: "GET".to_owned(),
+                url: "https://mhouge.dk/login".to_owned(),
+                status_code: StatusCode::FOUND,
+                headers: cookie_headers.clone(),
+                location: "https://mhouge.dk/".to_owned(),
+                duration: core::time::Duration::from_millis(12),
+            }],
+            headers: cookie_headers,
+            http_version: http::Version::HTTP_11,
+            body: String::new(),
+            raw_body: Vec::new(),
+            truncated: false,
+        };
+
+        let mut term = Vec::new();
+
+        handle_response(
+            &mut term,
+            &response,
+            &crate::config::parse_run_arguments(&["requests.http"]),
+            &[],
+        )
+        .expect("it to be ok");
+
+        term.flush().expect("it to flush");
+
+        let output = String::from_utf8_lossy(&term);
+
+        assert_eq!(
+            2,
+            output
+                .matches("\x1B[38;5;6mcookie\x1B[39m session=<redacted>; Path=/\n")
+                .count()
+        );
+        assert!(!output.contains("set-cookie"));
+
+        let args = RunCommandArguments {
+            hide_headers: true,
+            ..crate::config::parse_run_arguments(&["requests.http"])
+        };
+
+        let mut term = Vec::new();
+
+        handle_response(&mut term, &response, &args, &[]).expect("it to be ok");
+
+        term.flush().expect("it to flush");
+
+        assert!(!String::from_utf8_lossy(&term).contains("cookie"));
+    }
+
     #[test]
     fn it_should_not_print_body_if_empty() {
         let n = "mads";
@@ -243,6 +551,8 @@ mod test_handle_response {
             method: "GET".to_owned(),
             status_code: StatusCode::OK,
             duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
             headers: HeaderMap::from_iter([
                 (HeaderName::from_static(n), HeaderValue::from_static(v)),
                 (HeaderName::from_static(n), HeaderValue::from_static(n)),
@@ -251,24 +561,19 @@ mod test_handle_response {
             ]),
             http_version: http::Version::HTTP_11,
             body: String::new(),
+            raw_body: Vec::new(),
+            truncated: false,
         };
 
         let args = RunCommandArguments {
             disable_formatting: true,
             hide_body: true,
-            //
-            paths: vec![std::path::PathBuf::new()],
-            timeout: None,
-            var: None,
-            recursive: false,
-            fail_fast: false,
-            hide_headers: false,
-            vim: false,
+            ..crate::config::parse_run_arguments(&["requests.http"])
         };
 
         let mut term = Vec::new();
 
-        handle_response(&mut term, &response, &args).expect("it to be ok");
+        handle_response(&mut term, &response, &args, &[]).expect("it to be ok");
 
         let status = format!(
             "\x1b[38;5;10m\x1B[1m{:?} {} {} {} {}ms\n\x1B[0m",
@@ -300,6 +605,8 @@ mod test_handle_response {
             method: "GET".to_owned(),
             status_code: StatusCode::OK,
             duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
             headers: HeaderMap::from_iter([
                 (HeaderName::from_static(n), HeaderValue::from_static(v)),
                 (HeaderName::from_static(n), HeaderValue::from_static(n)),
@@ -308,24 +615,19 @@ mod test_handle_response {
             ]),
             http_version: http::Version::HTTP_11,
             body: "mads was here".to_owned(),
+            raw_body: b"mads was here".to_vec(),
+            truncated: false,
         };
 
         let args = RunCommandArguments {
             disable_formatting: true,
             hide_body: true,
-            //
-            paths: vec![std::path::PathBuf::new()],
-            timeout: None,
-            var: None,
-            recursive: false,
-            fail_fast: false,
-            hide_headers: false,
-            vim: false,
+            ..crate::config::parse_run_arguments(&["requests.http"])
         };
 
         let mut term = Vec::new();
 
-        handle_response(&mut term, &response, &args).expect("it to be ok");
+        handle_response(&mut term, &response, &args, &[]).expect("it to be ok");
 
         let status = format!(
             "\x1b[38;5;10m\x1B[1m{:?} {} {} {} {}ms\n\x1B[0m",
@@ -357,30 +659,27 @@ mod test_handle_response {
             method: "GET".to_owned(),
             status_code: StatusCode::SERVICE_UNAVAILABLE,
             duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
             headers: HeaderMap::from_iter([(
                 HeaderName::from_static(n),
                 HeaderValue::from_static(v),
             )]),
             http_version: http::Version::HTTP_11,
             body: "mads was here".to_owned(),
+            raw_body: b"mads was here".to_vec(),
+            truncated: false,
         };
 
         let args = RunCommandArguments {
             disable_formatting: true,
             fail_fast: true,
-            //
-            paths: vec![std::path::PathBuf::new()],
-            timeout: None,
-            var: None,
-            recursive: false,
-            hide_headers: false,
-            hide_body: false,
-            vim: false,
+            ..crate::config::parse_run_arguments(&["requests.http"])
         };
 
         let mut term = Vec::new();
 
-        let error = handle_response(&mut term, &response, &args).expect_err("it to fail fast");
+        let error = handle_response(&mut term, &response, &args, &[]).expect_err("it to fail fast");
 
         assert_eq!(
             "exiting early since --fail-fast is enabled",
@@ -407,4 +706,179 @@ mod test_handle_response {
 
         assert_eq!(expected_response, String::from_utf8_lossy(&term));
     }
+
+    #[test]
+    fn it_should_redact_secrets() {
+        let response = HittResponse {
+            url: "https://mhouge.dk/?token=abc123".to_owned(),
+            method: "GET".to_owned(),
+            status_code: StatusCode::OK,
+            duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
+            headers: HeaderMap::from_iter([(
+                HeaderName::from_static("authorization"),
+                HeaderValue::from_static("Bearer abc123"),
+            )]),
+            http_version: http::Version::HTTP_11,
+            body: "the token is abc123".to_owned(),
+            raw_body: b"the token is abc123".to_vec(),
+            truncated: false,
+        };
+
+        let args = RunCommandArguments {
+            disable_formatting: true,
+            ..crate::config::parse_run_arguments(&["requests.http"])
+        };
+
+        let mut term = Vec::new();
+
+        handle_response(&mut term, &response, &args, &["abc123".to_owned()]).expect("it to be ok");
+
+        let status = format!(
+            "\x1b[38;5;10m\x1B[1m{:?} {} https://mhouge.dk/?token=<redacted> {} {}ms\n\x1B[0m",
+            response.http_version,
+            response.method,
+            response.status_code.as_u16(),
+            response.duration.as_millis(),
+        );
+
+        let headers = "\x1B[38;5;3mauthorization\x1B[39m: <redacted>\n";
+
+        let body = "\n\x1B[38;5;3mthe token is <redacted>\x1B[39m\n\n";
+
+        let expected_response = format!("{status}{headers}{body}");
+
+        term.flush().expect("it to flush");
+
+        assert_eq!(expected_response, String::from_utf8_lossy(&term));
+    }
+
+    #[test]
+    fn it_should_summarize_and_save_binary_bodies() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to return a valid dir");
+
+        let raw_body = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+
+        let response = HittResponse {
+            url: "https://mhouge.dk/logo.png".to_owned(),
+            method: "GET".to_owned(),
+            status_code: StatusCode::OK,
+            duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
+            headers: HeaderMap::from_iter([(
+                HeaderName::from_static("content-type"),
+                HeaderValue::from_static("image/png"),
+            )]),
+            http_version: http::Version::HTTP_11,
+            body: String::from_utf8_lossy(&raw_body).into_owned(),
+            raw_body: raw_body.clone(),
+            truncated: false,
+        };
+
+        let args = RunCommandArguments {
+            hide_headers: true,
+            save_binary: Some(dir.path().to_path_buf()),
+            ..crate::config::parse_run_arguments(&["requests.http"])
+        };
+
+        let mut term = Vec::new();
+
+        handle_response(&mut term, &response, &args, &[]).expect("it to be ok");
+
+        term.flush().expect("it to flush");
+
+        let output = String::from_utf8_lossy(&term);
+
+        let saved_path = dir.path().join("logo.png");
+
+        assert!(output.contains(&format!(
+            "binary body - 16 B - image/png - saved to '{}'",
+            saved_path.display()
+        )));
+        assert!(output.contains("|.PNG........IHDR|"));
+
+        assert_eq!(
+            std::fs::read(saved_path).expect("it to read the file"),
+            raw_body
+        );
+    }
+
+    #[test]
+    fn it_should_print_timings_if_enabled() {
+        let response = HittResponse {
+            url: "https://mhouge.dk/".to_owned(),
+            method: "GET".to_owned(),
+            status_code: StatusCode::OK,
+            duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings {
+                redirects: None,
+                dns: Some(core::time::Duration::from_millis(1)),
+                tcp_connect: Some(core::time::Duration::from_millis(2)),
+                tls_handshake: Some(core::time::Duration::from_millis(3)),
+                ttfb: core::time::Duration::from_millis(117),
+                download: core::time::Duration::from_millis(4),
+            },
+            redirects: Vec::new(),
+            headers: HeaderMap::new(),
+            http_version: http::Version::HTTP_11,
+            body: String::new(),
+            raw_body: Vec::new(),
+            truncated: false,
+        };
+
+        let args = RunCommandArguments {
+            timings: true,
+            ..crate::config::parse_run_arguments(&["requests.http"])
+        };
+
+        let mut term = Vec::new();
+
+        handle_response(&mut term, &response, &args, &[]).expect("it to be ok");
+
+        term.flush().expect("it to flush");
+
+        assert_eq!(
+            "\x1b[38;5;10m\x1B[1mHTTP/1.1 GET https://mhouge.dk/ 200 123ms\n\x1B[0m\x1B[38;5;8mdns 1ms, tcp 2ms, tls 3ms, ttfb 117ms, download 4ms\n\x1B[39m",
+            String::from_utf8_lossy(&term)
+        );
+    }
+
+    #[test]
+    fn it_should_warn_about_truncated_bodies() {
+        let response = HittResponse {
+            url: "https://mhouge.dk/".to_owned(),
+            method: "GET".to_owned(),
+            status_code: StatusCode::OK,
+            duration: core::time::Duration::from_millis(123),
+            timings: hitt_request::timing::Timings::default(),
+            redirects: Vec::new(),
+            headers: HeaderMap::new(),
+            http_version: http::Version::HTTP_11,
+            body: "mads".to_owned(),
+            raw_body: b"mads".to_vec(),
+            truncated: true,
+        };
+
+        let args = RunCommandArguments {
+            hide_headers: true,
+            max_body_size: Some(4),
+            ..crate::config::parse_run_arguments(&["requests.http"])
+        };
+
+        let mut term = Vec::new();
+
+        handle_response(&mut term, &response, &args, &[]).expect("it to be ok");
+
+        term.flush().expect("it to flush");
+
+        assert!(String::from_utf8_lossy(&term).ends_with(
+            "\n\x1B[38;5;3mmads\x1B[39m\n\n\x1B[38;5;11mhitt: warning - stopped reading the body after 4 B (--max-body-size)\n\x1B[39m"
+        ));
+    }
 }
@@ -0,0 +1,109 @@
+use crossterm::{
+    event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Print, Stylize},
+};
+use hitt_parser::prompt::PromptDeclaration;
+
+#[inline]
+fn print_prompt<W: std::io::Write + Send>(
+    term: &mut W,
+    prompt: &PromptDeclaration,
+) -> std::io::Result<()> {
+    let message = prompt.description.as_deref().unwrap_or(&prompt.name);
+
+    queue!(term, Print(format!("{message}: ").cyan()))?;
+
+    term.flush()
+}
+
+#[inline]
+fn read_masked_line() -> std::io::Result<String> {
+    let mut value = String::new();
+
+    loop {
+        if let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = crossterm::event::read()?
+        {
+            match code {
+                KeyCode::Enter => return Ok(value),
+                KeyCode::Backspace => {
+                    value.pop();
+                }
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Err(std::io::Error::from(std::io::ErrorKind::Interrupted));
+                }
+                KeyCode::Char(ch) => value.push(ch),
+                _ => {}
+            }
+        }
+    }
+}
+
+#[inline]
+pub fn prompt_variable<W: std::io::Write + Send>(
+    term: &mut W,
+    prompt: &PromptDeclaration,
+) -> std::io::Result<String> {
+    print_prompt(term, prompt)?;
+
+    if prompt.is_masked() {
+        crossterm::terminal::enable_raw_mode()?;
+
+        let value = read_masked_line();
+
+        crossterm::terminal::disable_raw_mode()?;
+
+        queue!(term, Print('\n'))?;
+
+        value
+    } else {
+        let mut value = String::new();
+
+        std::io::stdin().read_line(&mut value)?;
+
+        Ok(value.trim_end_matches(['\r', '\n']).to_owned())
+    }
+}
+
+#[cfg(test)]
+mod test_print_prompt {
+    use hitt_parser::prompt::PromptDeclaration;
+
+    use super::print_prompt;
+
+    #[test]
+    fn it_should_print_description() {
+        let mut term = Vec::new();
+
+        let prompt = PromptDeclaration {
+            name: "otp".to_owned(),
+            description: Some("Enter the 2FA code".to_owned()),
+        };
+
+        print_prompt(&mut term, &prompt).expect("it not to return an error");
+
+        assert_eq!(
+            "\x1B[38;5;14mEnter the 2FA code: \x1B[39m",
+            String::from_utf8_lossy(&term)
+        );
+    }
+
+    #[test]
+    fn it_should_fallback_to_name() {
+        let mut term = Vec::new();
+
+        let prompt = PromptDeclaration {
+            name: "otp".to_owned(),
+            description: None,
+        };
+
+        print_prompt(&mut term, &prompt).expect("it not to return an error");
+
+        assert_eq!("\x1B[38;5;14motp: \x1B[39m", String::from_utf8_lossy(&term));
+    }
+}
@@ -0,0 +1,91 @@
+pub const REDACTED: &str = "<redacted>";
+
+const SENSITIVE_HEADERS: [http::HeaderName; 4] = [
+    http::header::AUTHORIZATION,
+    http::header::COOKIE,
+    http::header::PROXY_AUTHORIZATION,
+    http::header::SET_COOKIE,
+];
+
+#[inline]
+pub fn is_sensitive_header(name: &http::HeaderName) -> bool {
+    SENSITIVE_HEADERS.contains(name)
+}
+
+#[cfg(test)]
+mod test_is_sensitive_header {
+    use super::is_sensitive_header;
+
+    #[test]
+    fn it_should_detect_sensitive_headers() {
+        for name in [
+            "authorization",
+            "cookie",
+            "proxy-authorization",
+            "set-cookie",
+        ] {
+            assert!(is_sensitive_header(&http::HeaderName::from_static(name)));
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_other_headers() {
+        for name in ["content-type", "x-authorization-hint", "mads"] {
+            assert!(!is_sensitive_header(&http::HeaderName::from_static(name)));
+        }
+    }
+}
+
+/// Replaces every occurrence of the secret values in the input.
+#[inline]
+pub fn redact_secrets<'a>(input: &'a str, secrets: &[String]) -> std::borrow::Cow<'a, str> {
+    let mut sorted_secrets = secrets
+        .iter()
+        .filter(|secret| !secret.is_empty())
+        .collect::<Vec<_>>();
+
+    // longer secrets first, so a secret containing another secret is fully redacted
+    sorted_secrets.sort_by_key(|secret| core::cmp::Reverse(secret.len()));
+
+    let mut output = std::borrow::Cow::Borrowed(input);
+
+    for secret in sorted_secrets {
+        if output.contains(secret.as_str()) {
+            output = std::borrow::Cow::Owned(output.replace(secret.as_str(), REDACTED));
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test_redact_secrets {
+    use super::redact_secrets;
+
+    #[test]
+    fn it_should_redact_secrets() {
+        let secrets = vec!["abc123".to_owned()];
+
+        assert_eq!(
+            "https://mhouge.dk/?token=<redacted>&other=<redacted>",
+            redact_secrets("https://mhouge.dk/?token=abc123&other=abc123", &secrets)
+        );
+    }
+
+    #[test]
+    fn it_should_prefer_longest_secret() {
+        let secrets = vec!["abc".to_owned(), "abc123".to_owned()];
+
+        assert_eq!(
+            "<redacted> <redacted>",
+            redact_secrets("abc123 abc", &secrets)
+        );
+    }
+
+    #[test]
+    fn it_should_ignore_empty_secrets() {
+        let secrets = vec![String::new()];
+
+        assert_eq!("mads was here", redact_secrets("mads was here", &secrets));
+    }
+}
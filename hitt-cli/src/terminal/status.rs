@@ -3,6 +3,8 @@ use crossterm::{
     style::{Print, Stylize},
 };
 
+use crate::terminal::redact::redact_secrets;
+
 #[inline]
 pub fn print_status<W: std::io::Write + Send>(
     term: &mut W,
@@ -88,3 +90,202 @@ mod test_print_status {
         }
     }
 }
+
+#[inline]
+fn format_timings(timings: &hitt_request::timing::Timings) -> String {
+    [
+        ("redirects", timings.redirects),
+        ("dns", timings.dns),
+        ("tcp", timings.tcp_connect),
+        ("tls", timings.tls_handshake),
+        ("ttfb", Some(timings.ttfb)),
+        ("download", Some(timings.download)),
+    ]
+    .into_iter()
+    .filter_map(|(phase, duration)| duration.map(|dur| format!("{phase} {}ms", dur.as_millis())))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+#[cfg(test)]
+mod test_format_timings {
+    use core::time::Duration;
+
+    use hitt_request::timing::Timings;
+
+    use super::format_timings;
+
+    #[test]
+    fn it_should_format_all_phases() {
+        let timings = Timings {
+            redirects: Some(Duration::from_millis(6)),
+            dns: Some(Duration::from_millis(1)),
+            tcp_connect: Some(Duration::from_millis(2)),
+            tls_handshake: Some(Duration::from_millis(3)),
+            ttfb: Duration::from_millis(4),
+            download: Duration::from_millis(5),
+        };
+
+        assert_eq!(
+            "redirects 6ms, dns 1ms, tcp 2ms, tls 3ms, ttfb 4ms, download 5ms",
+            format_timings(&timings)
+        );
+    }
+
+    #[test]
+    fn it_should_skip_missing_phases() {
+        let timings = Timings {
+            redirects: None,
+            dns: None,
+            tcp_connect: Some(Duration::from_millis(2)),
+            tls_handshake: None,
+            ttfb: Duration::from_millis(4),
+            download: Duration::from_millis(5),
+        };
+
+        assert_eq!("tcp 2ms, ttfb 4ms, download 5ms", format_timings(&timings));
+    }
+}
+
+#[inline]
+pub fn print_timings<W: std::io::Write + Send>(
+    term: &mut W,
+    timings: &hitt_request::timing::Timings,
+) -> std::io::Result<()> {
+    queue!(
+        term,
+        Print(format!("{}\n", format_timings(timings)).dark_grey())
+    )
+}
+
+#[cfg(test)]
+mod test_print_timings {
+    use core::time::Duration;
+
+    use hitt_request::timing::Timings;
+
+    use super::print_timings;
+
+    #[test]
+    fn it_should_print_timings() {
+        let mut term = Vec::new();
+
+        let timings = Timings {
+            redirects: None,
+            dns: None,
+            tcp_connect: None,
+            tls_handshake: None,
+            ttfb: Duration::from_millis(4),
+            download: Duration::from_millis(5),
+        };
+
+        print_timings(&mut term, &timings).expect("it not to return an error");
+
+        assert_eq!(
+            "\x1B[38;5;8mttfb 4ms, download 5ms\n\x1B[39m",
+            String::from_utf8_lossy(&term)
+        );
+    }
+}
+
+#[inline]
+pub fn print_redirect<W: std::io::Write + Send>(
+    term: &mut W,
+    hop: &hitt_request::redirect::RedirectHop,
+    secrets: &[String],
+) -> std::io::Result<()> {
+    queue!(
+        term,
+        Print(
+            format!(
+                "{} {} {} {}ms -> {}\n",
+                hop.method,
+                redact_secrets(&hop.url, secrets),
+                hop.status_code.as_u16(),
+                hop.duration.as_millis(),
+                redact_secrets(&hop.location, secrets),
+            )
+            .dark_grey()
+        )
+    )
+}
+
+#[cfg(test)]
+mod test_print_redirect {
+    use hitt_request::redirect::RedirectHop;
+
+    use super::print_redirect;
+
+    #[test]
+    fn it_should_print_redirect() {
+        let mut term = Vec::new();
+
+        let hop = RedirectHop {
+            method: "GET".to_owned(),
+            url: "https://mhouge.dk/?token=abc".to_owned(),
+            status_code: http::StatusCode::FOUND,
+            headers: http::HeaderMap::new(),
+            location: "https://mhouge.dk/login?token=abc".to_owned(),
+            duration: core::time::Duration::from_millis(12),
+        };
+
+        print_redirect(&mut term, &hop, &["abc".to_owned()]).expect("it not to return an error");
+
+        assert_eq!(
+            "\x1B[38;5;8mGET https://mhouge.dk/?token=<redacted> 302 12ms -> https://mhouge.dk/login?token=<redacted>\n\x1B[39m",
+            String::from_utf8_lossy(&term)
+        );
+    }
+}
+
+#[inline]
+pub fn print_retry<W: std::io::Write + Send>(
+    term: &mut W,
+    attempt: &hitt_request::retry::RetryAttempt,
+    secrets: &[String],
+) -> std::io::Result<()> {
+    queue!(
+        term,
+        Print(
+            format!(
+                "{} {} {} - retry {} in {}ms\n",
+                attempt.method,
+                redact_secrets(&attempt.url, secrets),
+                attempt.reason,
+                attempt.attempt,
+                attempt.delay.as_millis(),
+            )
+            .dark_grey()
+        )
+    )?;
+
+    // the retry is printed before waiting for the next attempt
+    term.flush()
+}
+
+#[cfg(test)]
+mod test_print_retry {
+    use hitt_request::retry::{RetryAttempt, RetryReason};
+
+    use super::print_retry;
+
+    #[test]
+    fn it_should_print_retry() {
+        let mut term = Vec::new();
+
+        let attempt = RetryAttempt {
+            attempt: 2,
+            method: "GET".to_owned(),
+            url: "https://mhouge.dk/?token=abc".to_owned(),
+            reason: RetryReason::StatusCode(http::StatusCode::SERVICE_UNAVAILABLE),
+            delay: core::time::Duration::from_millis(1500),
+        };
+
+        print_retry(&mut term, &attempt, &["abc".to_owned()]).expect("it not to return an error");
+
+        assert_eq!(
+            "\x1B[38;5;8mGET https://mhouge.dk/?token=<redacted> 503 - retry 2 in 1500ms\n\x1B[39m",
+            String::from_utf8_lossy(&term)
+        );
+    }
+}
@@ -257,4 +257,232 @@ GET https://mhouge.dk/
         // Needed so the file isn't dropped
         assert!(std::fs::exists(file.path()).unwrap());
     }
+
+    #[test]
+    fn it_should_reject_prompts_when_not_interactive() {
+        let input = "
+# @prompt otp Enter the 2FA code
+GET https://mhouge.dk/?otp={{otp}}";
+
+        let dir = tempfile::TempDir::with_prefix("hitt-").unwrap();
+
+        let file = setup_test_input(dir.path(), input);
+
+        run_command(Some(dir.path()))
+            .arg(file.path())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "hitt: variable 'otp' must be prompted for, but the terminal is not interactive - pass it using '--var otp=<VALUE>'",
+            ));
+
+        // Needed so the file isn't dropped
+        assert!(std::fs::exists(file.path()).unwrap());
+    }
+
+    #[test]
+    fn it_should_redact_secret_variables() {
+        let input = "GET http://127.0.0.1:1/?token={{token}}";
+
+        let dir = tempfile::TempDir::with_prefix("hitt-").unwrap();
+
+        let file = setup_test_input(dir.path(), input);
+
+        run_command(Some(dir.path()))
+            .arg("--secret-var")
+            .arg("token=abc123")
+            .arg(file.path())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "hitt: GET http://127.0.0.1:1/?token=<redacted> - ",
+            ))
+            .stdout(predicates::str::contains("abc123").not());
+
+        // Needed so the file isn't dropped
+        assert!(std::fs::exists(file.path()).unwrap());
+    }
+
+    #[test]
+    fn it_should_let_input_variables_override_declarations() {
+        let input = "@port = 2
+
+GET http://127.0.0.1:{{port}}/";
+
+        let dir = tempfile::TempDir::with_prefix("hitt-").unwrap();
+
+        let file = setup_test_input(dir.path(), input);
+
+        run_command(Some(dir.path()))
+            .arg("--var")
+            .arg("port=1")
+            .arg(file.path())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "hitt: GET http://127.0.0.1:2/ - ",
+            ));
+
+        run_command(Some(dir.path()))
+            .arg("--var")
+            .arg("port=1")
+            .arg("--var-override")
+            .arg(file.path())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "hitt: GET http://127.0.0.1:1/ - ",
+            ));
+
+        // Needed so the file isn't dropped
+        assert!(std::fs::exists(file.path()).unwrap());
+    }
+
+    #[test]
+    fn it_should_support_variable_files() {
+        let input = "GET http://127.0.0.1:1/users/{{user.ids[1]}}?city={{user.address.city}}";
+
+        let dir = tempfile::TempDir::with_prefix("hitt-").unwrap();
+
+        let file = setup_test_input(dir.path(), input);
+
+        let var_file = dir.path().join("variables.json");
+
+        std::fs::write(
+            &var_file,
+            r#"{ "user": { "ids": [1, 2], "address": { "city": "Aarhus" } } }"#,
+        )
+        .unwrap();
+
+        run_command(Some(dir.path()))
+            .arg("--var-file")
+            .arg(&var_file)
+            .arg(file.path())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "hitt: GET http://127.0.0.1:1/users/2?city=Aarhus - ",
+            ));
+
+        // Needed so the file isn't dropped
+        assert!(std::fs::exists(file.path()).unwrap());
+    }
+
+    #[test]
+    fn it_should_reject_invalid_request_lines_in_strict_mode() {
+        let input = "GET http://127.0.0.1:1/ HTTP/1.2";
+
+        let dir = tempfile::TempDir::with_prefix("hitt-").unwrap();
+
+        let file = setup_test_input(dir.path(), input);
+
+        run_command(Some(dir.path()))
+            .arg("--strict")
+            .arg(file.path())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("invalid HTTP version 'HTTP/1.2'"));
+
+        run_command(Some(dir.path()))
+            .arg(file.path())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "hitt: GET http://127.0.0.1:1/ - ",
+            ));
+
+        // Needed so the file isn't dropped
+        assert!(std::fs::exists(file.path()).unwrap());
+    }
+
+    #[test]
+    fn it_should_resolve_relative_urls() {
+        let input = "GET /users";
+
+        let dir = tempfile::TempDir::with_prefix("hitt-").unwrap();
+
+        let file = setup_test_input(dir.path(), input);
+
+        run_command(Some(dir.path()))
+            .arg(file.path())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "relative uri '/users' is missing a base url",
+            ));
+
+        run_command(Some(dir.path()))
+            .arg("--base-url")
+            .arg("http://127.0.0.1:1")
+            .arg(file.path())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "hitt: GET http://127.0.0.1:1/users - ",
+            ));
+
+        // Needed so the file isn't dropped
+        assert!(std::fs::exists(file.path()).unwrap());
+    }
+
+    #[test]
+    fn it_should_run_markdown_files() {
+        let dir = tempfile::TempDir::with_prefix("hitt-").unwrap();
+
+        let file = dir.path().join("api.md");
+
+        std::fs::write(
+            &file,
+            "# API\n\n```http\nGET http://127.0.0.1:1/users\n```\n\n```http\nGET http://127.0.0.1:1/{{id}}\n```\n",
+        )
+        .unwrap();
+
+        run_command(Some(dir.path()))
+            .arg(&file)
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(format!(
+                "error parsing file '{}:8' - variable 'id' was used, but not set",
+                file.display()
+            )));
+
+        run_command(Some(dir.path()))
+            .arg("--var")
+            .arg("id=1")
+            .arg(&file)
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "hitt: GET http://127.0.0.1:1/users - ",
+            ));
+
+        run_command(Some(dir.path()))
+            .arg("--recursive")
+            .arg(dir.path())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains("127.0.0.1").not());
+    }
+
+    #[test]
+    fn it_should_support_custom_extensions_and_globs() {
+        let dir = tempfile::TempDir::with_prefix("hitt-").unwrap();
+
+        std::fs::write(dir.path().join("a.rest"), "GET http://127.0.0.1:1/rest").unwrap();
+        std::fs::write(dir.path().join("b.http"), "GET http://127.0.0.1:1/http").unwrap();
+
+        run_command(Some(dir.path()))
+            .arg("--recursive")
+            .arg("--extension")
+            .arg("http,rest")
+            .arg("--exclude")
+            .arg("b.*")
+            .arg(dir.path())
+            .assert()
+            .success()
+            .stdout(predicates::str::contains(
+                "hitt: GET http://127.0.0.1:1/rest - ",
+            ))
+            .stdout(predicates::str::contains("127.0.0.1:1/http").not());
+    }
 }
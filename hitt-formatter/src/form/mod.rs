@@ -0,0 +1,48 @@
+/// Decodes a form-urlencoded body into one `key = value` pair per line.
+///
+/// Multi-line values are indented, mirroring the syntax used to write form bodies.
+#[inline]
+pub fn format_form(input: &str) -> String {
+    form_urlencoded::parse(input.trim().as_bytes())
+        .map(|(key, value)| {
+            let mut lines = value.lines();
+
+            let mut output = format!("{key} = {}", lines.next().unwrap_or_default());
+
+            for line in lines {
+                output.push_str("\n    ");
+                output.push_str(line);
+            }
+
+            output
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod test_format_form {
+    use crate::form::format_form;
+
+    #[test]
+    fn it_should_decode_pairs() {
+        let input = "name=mads+hougesen&email=mads%40mhouge.dk&query=a%26b%3Dc";
+
+        let expected_output = "name = mads hougesen
+email = mads@mhouge.dk
+query = a&b=c";
+
+        assert_eq!(expected_output, format_form(input));
+    }
+
+    #[test]
+    fn it_should_indent_multi_line_values() {
+        let input = "message=first+line%0Asecond+line&other=";
+
+        let expected_output = "message = first line
+    second line
+other = ";
+
+        assert_eq!(expected_output, format_form(input));
+    }
+}
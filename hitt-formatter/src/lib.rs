@@ -1,9 +1,12 @@
+use form::format_form;
 use json::format_json;
 
+mod form;
 mod json;
 
 #[derive(Copy, Clone, Default, PartialEq, Eq)]
 pub enum ContentType {
+    FormUrlEncoded,
     Json,
     #[default]
     Unknown,
@@ -16,6 +19,7 @@ impl From<&str> for ContentType {
             .parse::<mime::Mime>()
             .map_or(Self::Unknown, |m| match (m.type_(), m.subtype()) {
                 (_, mime::JSON) => Self::Json,
+                (mime::APPLICATION, mime::WWW_FORM_URLENCODED) => Self::FormUrlEncoded,
                 _ => Self::Unknown,
             })
     }
@@ -46,11 +50,24 @@ mod test_from_str_to_content_type {
 
         assert!(ContentType::Json == ContentType::from("application/json; charset=utf-8"));
     }
+
+    #[test]
+    fn it_should_parse_form_urlencoded() {
+        assert!(
+            ContentType::FormUrlEncoded == ContentType::from("application/x-www-form-urlencoded")
+        );
+
+        assert!(
+            ContentType::FormUrlEncoded
+                == ContentType::from("application/x-www-form-urlencoded; charset=utf-8")
+        );
+    }
 }
 
 #[inline]
 pub fn format(input: &str, content_type: ContentType) -> Option<String> {
     match content_type {
+        ContentType::FormUrlEncoded => Some(format_form(input)),
         ContentType::Json => Some(format_json(input)),
         ContentType::Unknown => None,
     }
@@ -67,6 +84,17 @@ mod test_format {
         assert!(crate::format(input, ContentType::Unknown).is_none());
     }
 
+    #[test]
+    fn it_should_format_form() {
+        let input = "key=mads+was+here";
+        let content_type = ContentType::FormUrlEncoded;
+
+        assert_eq!(
+            crate::format(input, content_type),
+            Some(crate::form::format_form(input))
+        );
+    }
+
     #[test]
     fn it_should_format_json() {
         let input = "{ \"key\": \"value\" }";
@@ -0,0 +1,54 @@
+/// Parses a `# @name value` comment line, returning the directive name and its value.
+#[inline]
+pub fn parse_directive(line: &str) -> Option<(&str, &str)> {
+    let comment = line
+        .strip_prefix('#')
+        .or_else(|| line.strip_prefix("//"))?
+        .trim_start();
+
+    let directive = comment.strip_prefix('@')?;
+
+    let (name, value) = directive
+        .split_once(char::is_whitespace)
+        .unwrap_or((directive, ""));
+
+    if name.is_empty() {
+        return None;
+    }
+
+    Some((name, value.trim()))
+}
+
+#[cfg(test)]
+mod test_parse_directive {
+    use super::parse_directive;
+
+    #[test]
+    fn it_should_parse_directives() {
+        for comment_style in ["#", "//"] {
+            assert_eq!(
+                Some(("raw-body", "")),
+                parse_directive(&format!("{comment_style} @raw-body"))
+            );
+
+            assert_eq!(
+                Some(("name", "login")),
+                parse_directive(&format!("{comment_style}@name   login  "))
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_other_lines() {
+        for input in [
+            "# a regular comment",
+            "// a regular comment",
+            "# @",
+            "# @ raw-body",
+            "@raw-body",
+            "GET https://mhouge.dk/",
+        ] {
+            assert_eq!(None, parse_directive(input));
+        }
+    }
+}
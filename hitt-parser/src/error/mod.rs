@@ -1,11 +1,19 @@
 #[derive(Debug)]
 pub enum RequestParseError {
+    DuplicateHttpVersion,
     InvalidHeaderName(String),
     InvalidHeaderValue(String),
     InvalidHttpMethod(String),
+    InvalidHttpVersion(String),
+    InvalidMaxRedirects(String),
     InvalidUri(String),
+    MissingBaseUrl(String),
+    MissingDirectiveValue(String),
     MissingMethod,
+    MissingOutputPath,
     MissingUri,
+    TrailingRequestLineInput(String),
+    UnterminatedHeredoc(String, String),
     VariableNotFound(String),
 }
 
@@ -15,12 +23,31 @@ impl core::fmt::Display for RequestParseError {
     #[inline]
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
+            Self::DuplicateHttpVersion => {
+                write!(f, "the HTTP version can only be set once per request")
+            }
             Self::InvalidHeaderName(name) => write!(f, "invalid header name '{name}'"),
             Self::InvalidHeaderValue(value) => write!(f, "invalid header value '{value}'"),
             Self::InvalidHttpMethod(method) => write!(f, "invalid HTTP method '{method}'"),
+            Self::InvalidHttpVersion(version) => write!(f, "invalid HTTP version '{version}'"),
+            Self::InvalidMaxRedirects(value) => {
+                write!(f, "invalid max redirects '{value}' - expected a number")
+            }
             Self::InvalidUri(uri) => write!(f, "invalid uri '{uri}'"),
+            Self::MissingBaseUrl(uri) => write!(f, "relative uri '{uri}' is missing a base url"),
+            Self::MissingDirectiveValue(directive) => {
+                write!(f, "missing value after '@{directive}'")
+            }
             Self::MissingMethod => write!(f, "missing HTTP method"),
+            Self::MissingOutputPath => write!(f, "missing file path after '>>'"),
             Self::MissingUri => write!(f, "missing uri"),
+            Self::TrailingRequestLineInput(input) => {
+                write!(f, "unexpected input '{input}' after the HTTP version")
+            }
+            Self::UnterminatedHeredoc(name, terminator) => write!(
+                f,
+                "variable '{name}' is missing the closing '{terminator}' line"
+            ),
             Self::VariableNotFound(value) => write!(f, "variable '{value}' was used, but not set"),
         }
     }
@@ -0,0 +1,172 @@
+use crate::{
+    error::RequestParseError,
+    value::VariableValue,
+    variables::{interpolate_variables, interpolate_variables_with},
+};
+
+/// Whether the `content-type` header value is `application/x-www-form-urlencoded`.
+#[inline]
+pub fn is_form_content_type(value: &http::HeaderValue) -> bool {
+    value.to_str().is_ok_and(|content_type| {
+        content_type.split(';').next().is_some_and(|essence| {
+            essence
+                .trim()
+                .eq_ignore_ascii_case("application/x-www-form-urlencoded")
+        })
+    })
+}
+
+#[cfg(test)]
+mod test_is_form_content_type {
+    use super::is_form_content_type;
+
+    #[test]
+    fn it_should_detect_form_content_type() {
+        for input in [
+            "application/x-www-form-urlencoded",
+            "Application/X-WWW-Form-Urlencoded",
+            "application/x-www-form-urlencoded; charset=utf-8",
+        ] {
+            assert!(is_form_content_type(&http::HeaderValue::from_static(input)));
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_other_content_types() {
+        for input in ["application/json", "multipart/form-data", "text/plain"] {
+            assert!(!is_form_content_type(&http::HeaderValue::from_static(
+                input
+            )));
+        }
+    }
+}
+
+/// Encodes a body written as one `key = value` pair per line.
+///
+/// Indented lines continue the value of the previous pair, joined using a newline. Variables are
+/// interpolated after the pairs are split, so values containing `&` or `=` stay in their pair.
+///
+/// Bodies written as a single line without whitespace are assumed to already be encoded, and are
+/// sent as-is with the values of their variables encoded.
+#[inline]
+pub fn encode_form_body(
+    lines: &[String],
+    vars: &std::collections::HashMap<String, VariableValue>,
+) -> Result<String, RequestParseError> {
+    let non_empty_lines = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .collect::<Vec<_>>();
+
+    if let [line] = non_empty_lines.as_slice()
+        && !line.trim().contains(char::is_whitespace)
+    {
+        return interpolate_variables_with(line.trim(), vars, |output, value| {
+            output.extend(form_urlencoded::byte_serialize(value.as_bytes()));
+        });
+    }
+
+    let mut pairs: Vec<(String, String)> = Vec::new();
+
+    for line in non_empty_lines {
+        if line.starts_with(char::is_whitespace)
+            && let Some((_, value)) = pairs.last_mut()
+        {
+            value.push('\n');
+            value.push_str(&interpolate_variables(line.trim(), vars)?);
+
+            continue;
+        }
+
+        let (key, value) = line.split_once('=').unwrap_or((line, ""));
+
+        pairs.push((
+            interpolate_variables(key.trim(), vars)?,
+            interpolate_variables(value.trim(), vars)?,
+        ));
+    }
+
+    Ok(form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(pairs)
+        .finish())
+}
+
+#[cfg(test)]
+mod test_encode_form_body {
+    use super::encode_form_body;
+
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
+
+    #[inline]
+    fn to_lines(input: &str) -> Vec<String> {
+        input.lines().map(ToOwned::to_owned).collect()
+    }
+
+    #[test]
+    fn it_should_encode_pairs() {
+        let input = to_lines("name = mads hougesen\nemail = mads@mhouge.dk\nquery = a&b=c");
+
+        assert_eq!(
+            "name=mads+hougesen&email=mads%40mhouge.dk&query=a%26b%3Dc",
+            encode_form_body(&input, &EMPTY_VARS).expect("it to encode the body")
+        );
+    }
+
+    #[test]
+    fn it_should_support_multi_line_values() {
+        let input = to_lines("message = first line\n    second line\n\nother = value");
+
+        assert_eq!(
+            "message=first+line%0Asecond+line&other=value",
+            encode_form_body(&input, &EMPTY_VARS).expect("it to encode the body")
+        );
+    }
+
+    #[test]
+    fn it_should_keep_encoded_bodies() {
+        let input = to_lines("name=mads+hougesen&email=mads%40mhouge.dk\n");
+
+        assert_eq!(
+            "name=mads+hougesen&email=mads%40mhouge.dk",
+            encode_form_body(&input, &EMPTY_VARS).expect("it to encode the body")
+        );
+    }
+
+    #[test]
+    fn it_should_allow_missing_values() {
+        let input = to_lines("flag\nname = mads");
+
+        assert_eq!(
+            "flag=&name=mads",
+            encode_form_body(&input, &EMPTY_VARS).expect("it to encode the body")
+        );
+    }
+
+    #[test]
+    fn it_should_encode_variable_values() {
+        let vars = std::collections::HashMap::from([
+            ("token".to_owned(), "a&b=c+d%".into()),
+            ("name".to_owned(), "mads hougesen".into()),
+        ]);
+
+        for (input, expected) in [
+            ("token={{token}}", "token=a%26b%3Dc%2Bd%25"),
+            ("token = {{token}}", "token=a%26b%3Dc%2Bd%25"),
+            (
+                "name={{name}}&token={{token}}",
+                "name=mads+hougesen&token=a%26b%3Dc%2Bd%25",
+            ),
+            (
+                "name = {{name}}\ntoken = {{token}}",
+                "name=mads+hougesen&token=a%26b%3Dc%2Bd%25",
+            ),
+        ] {
+            assert_eq!(
+                expected,
+                encode_form_body(&to_lines(input), &vars).expect("it to encode the body")
+            );
+        }
+    }
+}
@@ -1,6 +1,11 @@
 use core::str::FromStr;
 
-use crate::{RequestToken, error::RequestParseError, variables::parse_variable};
+use crate::{
+    RequestToken,
+    error::RequestParseError,
+    value::get_variable,
+    variables::{parse_escaped_braces, parse_variable},
+};
 
 #[derive(Debug)]
 pub struct HeaderToken {
@@ -41,7 +46,7 @@ mod test_from_header_token_for_request_token {
 #[inline]
 pub fn parse_header(
     line: &mut core::iter::Enumerate<core::str::Chars>,
-    vars: &std::collections::HashMap<String, String>,
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
 ) -> Result<Option<HeaderToken>, RequestParseError> {
     let mut key = String::new();
     let mut value = String::new();
@@ -57,11 +62,11 @@ pub fn parse_header(
         } else if ch == '{' {
             // FIXME: remove cloning of enumerator
             if let Some((var, jumps)) = parse_variable(&mut line.clone()) {
-                if let Some(variable_value) = vars.get(&var) {
+                if let Some(variable_value) = get_variable(vars, &var) {
                     if is_key {
-                        key.push_str(variable_value);
+                        key.push_str(&variable_value.to_string());
                     } else {
-                        value.push_str(variable_value);
+                        value.push_str(&variable_value.to_string());
                     }
 
                     for _ in 0..jumps {
@@ -79,6 +84,12 @@ pub fn parse_header(
             } else {
                 value.push(ch);
             }
+        } else if ch == '\\' && parse_escaped_braces(line) {
+            if is_key {
+                key.push_str("{{");
+            } else {
+                value.push_str("{{");
+            }
         } else if is_key {
             key.push(ch);
         } else {
@@ -114,8 +125,9 @@ mod test_parse_header {
 
     use crate::{error::RequestParseError, parse_header, to_enum_chars};
 
-    static EMPTY_VARS: std::sync::LazyLock<std::collections::HashMap<String, String>> =
-        std::sync::LazyLock::new(std::collections::HashMap::new);
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
 
     #[test]
     fn it_should_return_valid_headers() {
@@ -194,8 +206,8 @@ mod test_parse_header {
             let key = format!("key{i}");
             let value = format!("value{i}");
 
-            vars.insert(key.clone(), i.to_string());
-            vars.insert(value.clone(), i.to_string());
+            vars.insert(key.clone(), i.to_string().into());
+            vars.insert(value.clone(), i.to_string().into());
 
             {
                 let input =
@@ -1,16 +1,32 @@
+use directive::parse_directive;
 use error::RequestParseError;
+use form::{encode_form_body, is_form_content_type};
 use header::{HeaderToken, parse_header};
 use method::parse_method_input;
-use uri::parse_uri_input;
-use variables::{parse_variable, parse_variable_declaration};
-use version::parse_http_version;
-
+use output::{ResponseOutput, parse_output_redirect};
+use prompt::{PromptDeclaration, parse_prompt_declaration};
+use tls::{RequestTls, TlsDirective, parse_tls_directive};
+use uri::{is_relative_uri, parse_uri_continuation, parse_uri_input, resolve_relative_uri};
+use value::{VariableValue, get_variable, parse_declared_value};
+use variables::{
+    VariableDeclaration, interpolate_variables, parse_declared_name, parse_variable_declaration,
+};
+use version::{parse_http_version, parse_http_version_strict};
+use warning::RequestParseWarning;
+
+mod directive;
 pub mod error;
+mod form;
 mod header;
 mod method;
+pub mod output;
+pub mod prompt;
+pub mod tls;
 mod uri;
+pub mod value;
 mod variables;
 mod version;
+pub mod warning;
 
 #[derive(Copy, Clone, PartialEq)]
 enum ParserMode {
@@ -26,6 +42,19 @@ enum RequestToken {
     HttpVersion(http::version::Version),
     Header(HeaderToken),
     Body(Option<String>),
+    Secret(String),
+    /// Value of the `baseUrl` variable, if the request uri is relative.
+    BaseUrl(String),
+    /// Set using `# @name`.
+    Name(String),
+    /// Set using `# @max-redirects` or `# @no-redirect`.
+    MaxRedirects(usize),
+    /// Set using `# @insecure`, `# @cacert`, `# @cert`, `# @key` or `# @cert-password`.
+    Tls(TlsDirective),
+    /// Set using `# @unix-socket`.
+    UnixSocket(std::path::PathBuf),
+    Output(ResponseOutput),
+    Warning(RequestParseWarning),
 }
 
 #[inline]
@@ -33,34 +62,213 @@ fn to_enum_chars(input: &str) -> core::iter::Enumerate<core::str::Chars<'_>> {
     input.chars().enumerate()
 }
 
+/// Options controlling how a buffer is parsed.
+#[derive(Debug, Default, Clone)]
+pub struct ParseOptions {
+    /// Let input variables take precedence over every variable declared in the file.
+    pub override_variables: bool,
+    /// Reject unknown HTTP versions, missing variables in the HTTP version and unexpected input
+    /// after the HTTP version, instead of ignoring them.
+    pub strict: bool,
+    /// Base url of relative request uris, if neither a `host` header nor a `baseUrl` variable
+    /// is set.
+    pub base_url: Option<String>,
+}
+
+/// Parses the optional HTTP version at the end of a request line.
+#[inline]
+fn parse_request_line_end(
+    chars: &mut core::iter::Enumerate<core::str::Chars>,
+    vars: &std::collections::HashMap<String, VariableValue>,
+    strict: bool,
+) -> Result<Option<http::version::Version>, RequestParseError> {
+    if !strict {
+        return Ok(parse_http_version(chars, vars));
+    }
+
+    let version = parse_http_version_strict(chars, vars)?;
+
+    let remaining = chars.map(|(_, ch)| ch).collect::<String>();
+
+    if !remaining.trim().is_empty() {
+        return Err(RequestParseError::TrailingRequestLineInput(
+            remaining.trim().to_owned(),
+        ));
+    }
+
+    Ok(version)
+}
+
+/// Removes the `>> path` line ending the request from the body lines, if there is one.
+///
+/// Only the last non-empty line is checked, so body lines starting with `>>` are sent as-is.
+#[inline]
+fn take_output_redirect(
+    body_lines: &mut Vec<String>,
+    vars: &std::collections::HashMap<String, VariableValue>,
+) -> Result<Option<ResponseOutput>, RequestParseError> {
+    let Some(index) = body_lines.iter().rposition(|line| !line.trim().is_empty()) else {
+        return Ok(None);
+    };
+
+    let output = body_lines
+        .get(index)
+        .map(|line| parse_output_redirect(line, vars))
+        .transpose()?
+        .flatten();
+
+    if output.is_some() {
+        body_lines.truncate(index);
+    }
+
+    Ok(output)
+}
+
+/// Builds the body of a request from the lines following the headers.
+#[inline]
+fn build_body(
+    body_lines: &[String],
+    vars: &std::collections::HashMap<String, VariableValue>,
+    is_raw_body: bool,
+    is_form_body: bool,
+) -> Result<String, RequestParseError> {
+    if is_raw_body {
+        return Ok(body_lines.join("\n"));
+    }
+
+    if is_form_body {
+        return encode_form_body(body_lines, vars);
+    }
+
+    let body_parts = body_lines
+        .iter()
+        .map(|line| interpolate_variables(line, vars))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(body_parts.join("\n"))
+}
+
 #[inline]
 fn tokenize(
     buffer: &str,
-    input_variables: &std::collections::HashMap<String, String>,
+    input_variables: &std::collections::HashMap<String, VariableValue>,
+    options: &ParseOptions,
 ) -> Result<Vec<RequestToken>, RequestParseError> {
     let mut tokens: Vec<RequestToken> = Vec::new();
 
     let mut parser_mode = ParserMode::Request;
 
-    let mut body_parts: Vec<String> = Vec::new();
+    // lines following the headers, interpolated once the request has ended
+    let mut body_lines: Vec<String> = Vec::new();
+
+    // variables declared before the first request are shared by every request in the file
+    let mut file_vars = input_variables.to_owned();
+    let mut file_var_names: Vec<String> = Vec::new();
+    let mut file_secret_names: Vec<String> = Vec::new();
+
+    // variables declared after the first request are local to the request that follows them
+    let mut vars = file_vars.clone();
+    let mut secret_names: Vec<String> = Vec::new();
+
+    let mut has_request = false;
 
-    let mut vars = input_variables.to_owned();
+    // heredoc variable declaration, and the lines collected so far
+    let mut heredoc: Option<(VariableDeclaration, Vec<String>)> = None;
+
+    // set using `# @raw-body`, which disables variables in the body of the following request
+    let mut is_raw_body = false;
+
+    // set by a `content-type: application/x-www-form-urlencoded` header
+    let mut is_form_body = false;
+
+    // set by a `>> path` line directly following the headers of a request without a body
+    let mut has_output = false;
 
     for line in buffer.lines() {
         let trimmed_line = line.trim();
 
+        if let Some((declaration, heredoc_lines)) = &mut heredoc
+            && declaration.heredoc_terminator.as_deref() != Some(trimmed_line)
+        {
+            heredoc_lines.push(line.to_owned());
+
+            continue;
+        }
+
+        if parser_mode == ParserMode::Request
+            && let Some((directive, value)) = parse_directive(trimmed_line)
+        {
+            match directive {
+                "raw-body" => is_raw_body = true,
+                "name" if !value.is_empty() => tokens.push(RequestToken::Name(value.to_owned())),
+                "max-redirects" => {
+                    let max_redirects = value
+                        .parse::<usize>()
+                        .map_err(|_err| RequestParseError::InvalidMaxRedirects(value.to_owned()))?;
+
+                    tokens.push(RequestToken::MaxRedirects(max_redirects));
+                }
+                "no-redirect" => tokens.push(RequestToken::MaxRedirects(0)),
+                "unix-socket" => {
+                    let path = interpolate_variables(value, &vars)?;
+
+                    if path.is_empty() {
+                        return Err(RequestParseError::MissingDirectiveValue(
+                            directive.to_owned(),
+                        ));
+                    }
+
+                    tokens.push(RequestToken::UnixSocket(std::path::PathBuf::from(path)));
+                }
+                _ => {
+                    if let Some(tls_directive) = parse_tls_directive(directive, value, &vars)? {
+                        tokens.push(RequestToken::Tls(tls_directive));
+                    }
+                }
+            }
+        }
+
+        if parser_mode == ParserMode::Request
+            && let Some(prompt) = parse_prompt_declaration(trimmed_line)
+            && prompt.is_masked()
+        {
+            if !has_request && !file_secret_names.contains(&prompt.name) {
+                file_secret_names.push(prompt.name.clone());
+            }
+
+            if !secret_names.contains(&prompt.name) {
+                secret_names.push(prompt.name);
+            }
+        }
+
         // check if line is comment (#) OR requests break (###)
         if trimmed_line.starts_with('#') {
             if trimmed_line.starts_with("###") && parser_mode != ParserMode::Request {
-                if body_parts.is_empty() {
+                if let Some(output) = take_output_redirect(&mut body_lines, &vars)? {
+                    tokens.push(RequestToken::Output(output));
+                }
+
+                if body_lines.is_empty() {
                     tokens.push(RequestToken::Body(None));
                 } else {
-                    tokens.push(RequestToken::Body(Some(body_parts.join("\n"))));
-
-                    body_parts.clear();
+                    tokens.push(RequestToken::Body(Some(build_body(
+                        &body_lines,
+                        &vars,
+                        is_raw_body,
+                        is_form_body,
+                    )?)));
+
+                    body_lines.clear();
                 }
 
                 parser_mode = ParserMode::Request;
+
+                is_raw_body = false;
+                is_form_body = false;
+                has_output = false;
+
+                vars.clone_from(&file_vars);
+                secret_names.clone_from(&file_secret_names);
             }
 
             if parser_mode == ParserMode::Request {
@@ -75,16 +283,79 @@ fn tokenize(
 
         match parser_mode {
             ParserMode::Request => {
-                if trimmed_line.starts_with('@') {
+                let is_heredoc_value = heredoc.is_some();
+
+                let declaration = if let Some((mut declaration, heredoc_lines)) = heredoc.take() {
+                    // the current line is the heredoc terminator
+                    declaration.value = interpolate_variables(&heredoc_lines.join("\n"), &vars)?;
+                    declaration.heredoc_terminator = None;
+
+                    Some(declaration)
+                } else if trimmed_line.starts_with('@') {
                     let mut chrs = to_enum_chars(trimmed_line);
 
                     // move forward once since we don't care about the '@'
                     chrs.next();
 
-                    if let Some((name, value)) = parse_variable_declaration(&mut chrs, &vars)? {
-                        vars.insert(name, value);
+                    parse_variable_declaration(&mut chrs, &vars)?
+                } else {
+                    None
+                };
+
+                if let Some(declaration) = declaration {
+                    if declaration.heredoc_terminator.is_some() {
+                        heredoc = Some((declaration, Vec::new()));
+
+                        continue;
+                    }
+
+                    let VariableDeclaration {
+                        name,
+                        value,
+                        is_secret,
+                        is_default,
+                        ..
+                    } = declaration;
+
+                    if is_secret && !secret_names.contains(&name) {
+                        secret_names.push(name.clone());
+                    }
+
+                    if is_secret && !has_request && !file_secret_names.contains(&name) {
+                        file_secret_names.push(name.clone());
+                    }
+
+                    let is_overridden = (options.override_variables
+                        && input_variables.contains_key(&name))
+                        || (is_default && vars.contains_key(&name));
+
+                    if is_overridden {
                         continue;
                     }
+
+                    // heredoc values keep their formatting
+                    let value = if is_heredoc_value {
+                        VariableValue::String(value)
+                    } else {
+                        parse_declared_value(value)
+                    };
+
+                    if has_request {
+                        if file_var_names.contains(&name) {
+                            tokens.push(RequestToken::Warning(
+                                RequestParseWarning::VariableShadowed(name.clone()),
+                            ));
+                        }
+                    } else {
+                        if !file_var_names.contains(&name) {
+                            file_var_names.push(name.clone());
+                        }
+
+                        file_vars.insert(name.clone(), value.clone());
+                    }
+
+                    vars.insert(name, value);
+                    continue;
                 }
 
                 if !trimmed_line.is_empty() {
@@ -93,60 +364,112 @@ fn tokenize(
 
                     tokens.push(RequestToken::Method(method));
 
+                    for secret_name in &secret_names {
+                        if let Some(secret_value) = vars.get(secret_name) {
+                            tokens.push(RequestToken::Secret(secret_value.to_string()));
+                        }
+                    }
+
                     let uri = parse_uri_input(&mut chrs, &vars)?;
 
+                    if is_relative_uri(&uri)
+                        && let Some(base_url) = get_variable(&vars, "baseUrl")
+                    {
+                        tokens.push(RequestToken::BaseUrl(base_url.to_string()));
+                    }
+
                     tokens.push(RequestToken::Uri(uri));
 
-                    if let Some(http_version) = parse_http_version(&mut chrs, &vars) {
+                    if let Some(http_version) =
+                        parse_request_line_end(&mut chrs, &vars, options.strict)?
+                    {
                         tokens.push(RequestToken::HttpVersion(http_version));
                     }
 
                     parser_mode = ParserMode::Headers;
+
+                    has_request = true;
                 }
             }
 
             ParserMode::Headers => {
-                if trimmed_line.is_empty() {
-                    parser_mode = ParserMode::Body;
-                } else if let Some(header_token) =
-                    parse_header(&mut to_enum_chars(trimmed_line), &vars)?
-                {
-                    tokens.push(RequestToken::Header(header_token));
+                if has_output {
+                    continue;
                 }
-            }
 
-            ParserMode::Body => {
-                let mut current_line = String::new();
-                let mut chars = to_enum_chars(line);
+                if let Some(output) = parse_output_redirect(trimmed_line, &vars)? {
+                    tokens.push(RequestToken::Output(output));
 
-                while let Some((_, ch)) = chars.next() {
-                    if ch == '{' {
-                        // FIXME: remove cloning of enumerator
-                        if let Some((var, jumps)) = parse_variable(&mut chars.clone()) {
-                            if let Some(variable_value) = vars.get(&var) {
-                                current_line.push_str(variable_value);
+                    has_output = true;
+                } else if trimmed_line.is_empty() {
+                    parser_mode = ParserMode::Body;
+                } else if trimmed_line.starts_with(['?', '&'])
+                    && matches!(
+                        tokens.last(),
+                        Some(RequestToken::Uri(_) | RequestToken::HttpVersion(_))
+                    )
+                {
+                    // query continuation lines directly following the request line
+                    let has_http_version =
+                        matches!(tokens.last(), Some(RequestToken::HttpVersion(_)));
 
-                                for _ in 0..jumps {
-                                    chars.next();
-                                }
+                    let mut chrs = to_enum_chars(trimmed_line);
 
-                                continue;
-                            }
+                    if let Some(uri) = tokens.iter_mut().rev().find_map(|token| match token {
+                        RequestToken::Uri(uri) => Some(uri),
+                        _ => None,
+                    }) {
+                        *uri = parse_uri_continuation(&mut chrs, &vars, uri)?;
+                    }
 
-                            return Err(RequestParseError::VariableNotFound(var));
+                    if let Some(http_version) =
+                        parse_request_line_end(&mut chrs, &vars, options.strict)?
+                    {
+                        if has_http_version {
+                            return Err(RequestParseError::DuplicateHttpVersion);
                         }
+
+                        tokens.push(RequestToken::HttpVersion(http_version));
+                    }
+                } else if let Some(header_token) =
+                    parse_header(&mut to_enum_chars(trimmed_line), &vars)?
+                {
+                    if header_token.key == http::header::CONTENT_TYPE {
+                        is_form_body = is_form_content_type(&header_token.value);
                     }
 
-                    current_line.push(ch);
+                    tokens.push(RequestToken::Header(header_token));
+                }
+            }
+
+            ParserMode::Body => {
+                if has_output {
+                    continue;
                 }
 
-                body_parts.push(current_line);
+                body_lines.push(line.to_owned());
             }
         }
     }
 
-    if !body_parts.is_empty() {
-        tokens.push(RequestToken::Body(Some(body_parts.join("\n"))));
+    if let Some((declaration, _)) = heredoc {
+        return Err(RequestParseError::UnterminatedHeredoc(
+            declaration.name,
+            declaration.heredoc_terminator.unwrap_or_default(),
+        ));
+    }
+
+    if let Some(output) = take_output_redirect(&mut body_lines, &vars)? {
+        tokens.push(RequestToken::Output(output));
+    }
+
+    if !body_lines.is_empty() {
+        tokens.push(RequestToken::Body(Some(build_body(
+            &body_lines,
+            &vars,
+            is_raw_body,
+            is_form_body,
+        )?)));
     }
 
     Ok(tokens)
@@ -156,10 +479,11 @@ fn tokenize(
 mod test_tokenize {
     use core::fmt::Write as _;
 
-    use crate::{RequestToken, error::RequestParseError, tokenize};
+    use crate::{ParseOptions, RequestToken, error::RequestParseError, tokenize};
 
-    static EMPTY_VARS: std::sync::LazyLock<std::collections::HashMap<String, String>> =
-        std::sync::LazyLock::new(std::collections::HashMap::new);
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
 
     #[test]
     fn should_return_a_list_of_tokens() {
@@ -174,8 +498,8 @@ mod test_tokenize {
             "{method_input} {uri_input} {http_version}\n{header1_key}: {header1_value}\n\n{body_input}"
         );
 
-        let tokens =
-            tokenize(&input_request, &EMPTY_VARS).expect("it to return Result<Vec<RequestToken>>");
+        let tokens = tokenize(&input_request, &EMPTY_VARS, &ParseOptions::default())
+            .expect("it to return Result<Vec<RequestToken>>");
 
         assert_eq!(tokens.len(), 5);
 
@@ -208,6 +532,17 @@ mod test_tokenize {
                 RequestToken::HttpVersion(version_token) => {
                     assert_eq!(version_token, http::version::Version::HTTP_2);
                 }
+
+                RequestToken::Secret(_)
+                | RequestToken::BaseUrl(_)
+                | RequestToken::Name(_)
+                | RequestToken::MaxRedirects(_)
+                | RequestToken::Tls(_)
+                | RequestToken::UnixSocket(_)
+                | RequestToken::Output(_)
+                | RequestToken::Warning(_) => {
+                    unreachable!("no variables were declared")
+                }
             }
         }
     }
@@ -260,7 +595,8 @@ mod test_tokenize {
             }
         }
 
-        let tokens = tokenize(&input, &EMPTY_VARS).expect("it to return a list of tokens");
+        let tokens = tokenize(&input, &EMPTY_VARS, &ParseOptions::default())
+            .expect("it to return a list of tokens");
 
         assert_eq!(tokens.len(), methods.len() * versions.len() * 5);
 
@@ -310,7 +646,8 @@ mod test_tokenize {
 
 DELETE https://mhouge.dk/";
 
-        let tokens = tokenize(input, &EMPTY_VARS).expect("it to parse successfully");
+        let tokens = tokenize(input, &EMPTY_VARS, &ParseOptions::default())
+            .expect("it to parse successfully");
 
         assert_eq!(tokens.len(), 2);
 
@@ -343,7 +680,8 @@ DELETE https://mhouge.dk/";
 {body}"
             );
 
-            let tokens = tokenize(&hashtag, &EMPTY_VARS).expect("it to parse successfully");
+            let tokens = tokenize(&hashtag, &EMPTY_VARS, &ParseOptions::default())
+                .expect("it to parse successfully");
 
             assert_eq!(tokens.len(), 3);
 
@@ -377,7 +715,8 @@ DELETE https://mhouge.dk/";
 
 {{ body_input }}";
 
-            let tokens = tokenize(input, &EMPTY_VARS).expect("it to tokenize successfully");
+            let tokens = tokenize(input, &EMPTY_VARS, &ParseOptions::default())
+                .expect("it to tokenize successfully");
 
             assert_eq!(tokens.len(), 3);
 
@@ -395,7 +734,7 @@ DELETE https://mhouge.dk/";
 
             let body_token = tokens.get(2).expect("it to be set");
 
-            let expected_body_value = "{ \"key\": \"value\" }";
+            let expected_body_value = "{\"key\":\"value\"}";
 
             assert!(matches!(
                 body_token,
@@ -410,7 +749,8 @@ GET https://mhouge.dk/
 
 {{ body_input }}";
 
-            let tokens = tokenize(input, &EMPTY_VARS).expect_err("it to return an error");
+            let tokens = tokenize(input, &EMPTY_VARS, &ParseOptions::default())
+                .expect_err("it to return an error");
 
             assert!(matches!(
                 tokens,
@@ -423,11 +763,11 @@ GET https://mhouge.dk/
     #[test]
     fn it_should_support_input_variables() {
         let vars = std::collections::HashMap::from([
-            ("method".to_owned(), "GET".to_owned()),
-            ("host".to_owned(), "https://mhouge.dk".to_owned()),
-            ("path".to_owned(), "/api".to_owned()),
-            ("query_value".to_owned(), "mads@mhouge.dk".to_owned()),
-            ("body_input".to_owned(), "{ \"key\": \"value\" }".to_owned()),
+            ("method".to_owned(), "GET".into()),
+            ("host".to_owned(), "https://mhouge.dk".into()),
+            ("path".to_owned(), "/api".into()),
+            ("query_value".to_owned(), "mads@mhouge.dk".into()),
+            ("body_input".to_owned(), "{ \"key\": \"value\" }".into()),
         ]);
 
         let input = "
@@ -435,7 +775,8 @@ GET https://mhouge.dk/
 
 {{ body_input }}";
 
-        let tokens = tokenize(input, &vars).expect("it to tokenize successfully");
+        let tokens =
+            tokenize(input, &vars, &ParseOptions::default()).expect("it to tokenize successfully");
 
         assert_eq!(tokens.len(), 3);
 
@@ -464,7 +805,8 @@ GET https://mhouge.dk/
     fn it_should_raise_error_if_missing_variable() {
         let input = "GET {{missing_variable}}";
 
-        let err = tokenize(input, &EMPTY_VARS).expect_err("it to be a missing variable error");
+        let err = tokenize(input, &EMPTY_VARS, &ParseOptions::default())
+            .expect_err("it to be a missing variable error");
 
         assert_eq!(
             "variable 'missing_variable' was used, but not set",
@@ -475,14 +817,15 @@ GET https://mhouge.dk/
 
     #[test]
     fn input_variables_should_be_overwritten_by_local_variables() {
-        let vars = std::collections::HashMap::from([("method".to_owned(), "PUT".to_owned())]);
+        let vars = std::collections::HashMap::from([("method".to_owned(), "PUT".into())]);
 
         let input = "
 @method = POST
 
 {{method}} https://mhouge.dk/";
 
-        let tokens = tokenize(input, &vars).expect("it to parse successfully");
+        let tokens =
+            tokenize(input, &vars, &ParseOptions::default()).expect("it to parse successfully");
 
         assert_eq!(tokens.len(), 2);
 
@@ -513,7 +856,7 @@ OPTIONS https://mhouge.dk/
 
 HEAD https://mhouge.dk/blog/";
 
-        let output = tokenize(input, &EMPTY_VARS).expect("it to parse");
+        let output = tokenize(input, &EMPTY_VARS, &ParseOptions::default()).expect("it to parse");
 
         assert_eq!(output.len(), 5);
 
@@ -538,6 +881,160 @@ HEAD https://mhouge.dk/blog/";
     }
 }
 
+/// Returns every `# @prompt` declaration found in the buffer, in order of appearance.
+///
+/// Prompts are only read before the request line, like other directives, and prompts for
+/// variables declared in the buffer are skipped.
+///
+/// Prompted values are not resolved by the parser. They are expected to be collected by the
+/// caller and passed to [`parse_requests`] as input variables.
+#[inline]
+pub fn parse_prompts(buffer: &str) -> Vec<PromptDeclaration> {
+    let mut prompts: Vec<PromptDeclaration> = Vec::new();
+
+    let mut declared_names: Vec<&str> = Vec::new();
+
+    // headers and bodies are skipped until the next request separator
+    let mut is_request = false;
+
+    let mut heredoc_terminator: Option<&str> = None;
+
+    for line in buffer.lines() {
+        let trimmed_line = line.trim();
+
+        if let Some(terminator) = heredoc_terminator {
+            if trimmed_line == terminator {
+                heredoc_terminator = None;
+            }
+
+            continue;
+        }
+
+        if trimmed_line.starts_with("###") {
+            is_request = false;
+
+            continue;
+        }
+
+        if is_request || trimmed_line.is_empty() {
+            continue;
+        }
+
+        if let Some(prompt) = parse_prompt_declaration(trimmed_line) {
+            if !prompts.iter().any(|existing| existing.name == prompt.name) {
+                prompts.push(prompt);
+            }
+        } else if let Some((name, terminator)) = parse_declared_name(trimmed_line) {
+            declared_names.push(name);
+
+            heredoc_terminator = terminator;
+        } else if !trimmed_line.starts_with('#') && !trimmed_line.starts_with("//") {
+            is_request = true;
+        }
+    }
+
+    prompts.retain(|prompt| !declared_names.contains(&prompt.name.as_str()));
+
+    prompts
+}
+
+#[cfg(test)]
+mod test_parse_prompts {
+    use crate::{parse_prompts, parse_requests};
+
+    #[test]
+    fn it_should_return_prompt_declarations() {
+        let input = "
+# @prompt otp Enter the 2FA code
+// @prompt password
+POST https://mhouge.dk/login
+
+###
+
+# @prompt otp Enter the code again
+GET https://mhouge.dk/";
+
+        let prompts = parse_prompts(input);
+
+        assert_eq!(prompts.len(), 2);
+
+        let otp = prompts.first().expect("it to exist");
+        assert_eq!(otp.name, "otp");
+        assert_eq!(otp.description.as_deref(), Some("Enter the 2FA code"));
+
+        let password = prompts.get(1).expect("it to exist");
+        assert_eq!(password.name, "password");
+        assert!(password.description.is_none());
+    }
+
+    #[test]
+    fn prompt_declarations_should_be_ignored_by_request_parser() {
+        let vars = std::collections::HashMap::from([("otp".to_owned(), "123456".into())]);
+
+        let input = "
+# @prompt otp Enter the 2FA code
+GET https://mhouge.dk/?otp={{otp}}";
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        assert_eq!(requests.len(), 1);
+
+        let request = requests.first().expect("it to exist");
+
+        assert_eq!(request.uri, "https://mhouge.dk/?otp=123456");
+    }
+
+    #[test]
+    fn it_should_ignore_prompts_in_bodies() {
+        let input = "
+POST https://mhouge.dk/notes
+content-type: text/plain
+
+# @prompt otp Enter the 2FA code
+
+###
+
+@body = <<EOF
+# @prompt token
+EOF
+
+# @prompt id
+GET https://mhouge.dk/{{id}}";
+
+        let prompts = parse_prompts(input);
+
+        assert_eq!(
+            vec!["id"],
+            prompts
+                .iter()
+                .map(|prompt| prompt.name.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn it_should_skip_declared_variables() {
+        let input = "
+@otp = 123456
+@secret password ?= hunter2
+
+# @prompt otp
+# @prompt password
+# @prompt user_id
+GET https://mhouge.dk/{{user_id}}?otp={{otp}}";
+
+        let prompts = parse_prompts(input);
+
+        assert_eq!(
+            vec!["user_id"],
+            prompts
+                .iter()
+                .map(|prompt| prompt.name.as_str())
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
 #[derive(Debug)]
 pub struct HittRequest {
     pub method: http::method::Method,
@@ -545,6 +1042,19 @@ pub struct HittRequest {
     pub headers: http::HeaderMap,
     pub body: Option<String>,
     pub http_version: Option<http::version::Version>,
+    /// Values of variables declared using `@secret` or a masked `# @prompt`.
+    pub secrets: Vec<String>,
+    pub warnings: Vec<RequestParseWarning>,
+    /// Set using `# @name`.
+    pub name: Option<String>,
+    /// Set using `# @max-redirects` or `# @no-redirect`.
+    pub max_redirects: Option<usize>,
+    /// Set using `# @insecure`, `# @cacert`, `# @cert`, `# @key` and `# @cert-password`.
+    pub tls: RequestTls,
+    /// Set using `# @unix-socket path`, relative to the file containing the request.
+    pub unix_socket: Option<std::path::PathBuf>,
+    /// Set using `>> path` or `>>! path`.
+    pub output: Option<ResponseOutput>,
 }
 
 #[derive(Default)]
@@ -554,9 +1064,42 @@ struct PartialHittRequest {
     headers: http::HeaderMap,
     body: Option<String>,
     http_version: Option<http::version::Version>,
+    secrets: Vec<String>,
+    warnings: Vec<RequestParseWarning>,
+    base_url: Option<String>,
+    name: Option<String>,
+    max_redirects: Option<usize>,
+    tls: RequestTls,
+    unix_socket: Option<std::path::PathBuf>,
+    output: Option<ResponseOutput>,
 }
 
 impl PartialHittRequest {
+    /// Resolves a relative uri using the `host` header, the `baseUrl` variable or the default
+    /// base url, in that order.
+    #[inline]
+    fn resolve_uri(&mut self, default_base_url: Option<&str>) -> Result<(), RequestParseError> {
+        let Some(uri) = &self.uri else {
+            return Ok(());
+        };
+
+        if !is_relative_uri(uri) {
+            return Ok(());
+        }
+
+        let base_url = self
+            .headers
+            .get(http::header::HOST)
+            .and_then(|host| host.to_str().ok())
+            .or(self.base_url.as_deref())
+            .or(default_base_url)
+            .ok_or_else(|| RequestParseError::MissingBaseUrl(uri.to_string()))?;
+
+        self.uri = Some(resolve_relative_uri(uri, base_url)?);
+
+        Ok(())
+    }
+
     #[inline]
     fn build(self) -> Result<HittRequest, RequestParseError> {
         match self.method {
@@ -567,6 +1110,13 @@ impl PartialHittRequest {
                     headers: self.headers,
                     body: self.body,
                     http_version: self.http_version,
+                    secrets: self.secrets,
+                    warnings: self.warnings,
+                    name: self.name,
+                    max_redirects: self.max_redirects,
+                    tls: self.tls,
+                    unix_socket: self.unix_socket,
+                    output: self.output,
                 }),
                 None => Err(RequestParseError::MissingUri),
             },
@@ -589,6 +1139,14 @@ mod test_partial_http_request {
             http_version: None,
             headers: HeaderMap::default(),
             body: None,
+            secrets: Vec::new(),
+            warnings: Vec::new(),
+            base_url: None,
+            name: None,
+            max_redirects: None,
+            tls: crate::tls::RequestTls::default(),
+            unix_socket: None,
+            output: None,
         }
         .build()
         .expect_err("it to raise RequestParseError::MissingUri");
@@ -608,6 +1166,14 @@ mod test_partial_http_request {
             http_version: None,
             headers: HeaderMap::default(),
             body: None,
+            secrets: Vec::new(),
+            warnings: Vec::new(),
+            base_url: None,
+            name: None,
+            max_redirects: None,
+            tls: crate::tls::RequestTls::default(),
+            unix_socket: None,
+            output: None,
         }
         .build()
         .expect_err("it to raise RequestParseError::MissingMethod");
@@ -618,14 +1184,44 @@ mod test_partial_http_request {
     }
 }
 
+/// Parses every request in the buffer.
+///
+/// Variables are resolved using the following precedence, from lowest to highest:
+///
+/// 1. Defaults declared using `@name ?= value`, which are only used if the variable is not
+///    already set.
+/// 2. Input variables, such as the ones passed using `--var`.
+/// 3. Declarations at the top of the file using `@name = value`.
+/// 4. Declarations between requests using `@name = value`, which only apply to the following
+///    request.
+///
+/// See [`parse_requests_with_options`] for letting input variables take precedence over the
+/// declarations in the file.
 #[inline]
 pub fn parse_requests(
     buffer: &str,
-    input_variables: &std::collections::HashMap<String, String>,
+    input_variables: &std::collections::HashMap<String, VariableValue>,
+) -> Result<Vec<HittRequest>, RequestParseError> {
+    parse_requests_with_options(buffer, input_variables, &ParseOptions::default())
+}
+
+/// Parses every request in the buffer using the given options.
+///
+/// If [`ParseOptions::override_variables`] is set, input variables take precedence over every
+/// declaration in the file, including request-local ones. Otherwise variables are resolved as
+/// described in [`parse_requests`].
+///
+/// Relative uris, such as `GET /users`, are resolved against the `host` header, the `baseUrl`
+/// variable or [`ParseOptions::base_url`], in that order.
+#[inline]
+pub fn parse_requests_with_options(
+    buffer: &str,
+    input_variables: &std::collections::HashMap<String, VariableValue>,
+    options: &ParseOptions,
 ) -> Result<Vec<HittRequest>, RequestParseError> {
     let mut requests = Vec::new();
 
-    let tokens = tokenize(buffer, input_variables)?;
+    let tokens = tokenize(buffer, input_variables, options)?;
 
     let mut partial_request = PartialHittRequest::default();
 
@@ -646,6 +1242,8 @@ pub fn parse_requests(
             RequestToken::Body(body) => {
                 partial_request.body = body;
 
+                partial_request.resolve_uri(options.base_url.as_deref())?;
+
                 requests.push(partial_request.build()?);
 
                 partial_request = PartialHittRequest::default();
@@ -654,10 +1252,44 @@ pub fn parse_requests(
             RequestToken::HttpVersion(version_token) => {
                 partial_request.http_version = Some(version_token);
             }
+
+            RequestToken::Secret(secret) => {
+                partial_request.secrets.push(secret);
+            }
+
+            RequestToken::Warning(warning) => {
+                partial_request.warnings.push(warning);
+            }
+
+            RequestToken::BaseUrl(base_url) => {
+                partial_request.base_url = Some(base_url);
+            }
+
+            RequestToken::Name(name) => {
+                partial_request.name = Some(name);
+            }
+
+            RequestToken::MaxRedirects(max_redirects) => {
+                partial_request.max_redirects = Some(max_redirects);
+            }
+
+            RequestToken::Tls(directive) => {
+                partial_request.tls.apply(directive);
+            }
+
+            RequestToken::UnixSocket(path) => {
+                partial_request.unix_socket = Some(path);
+            }
+
+            RequestToken::Output(output) => {
+                partial_request.output = Some(output);
+            }
         }
     }
 
     if partial_request.method.is_some() {
+        partial_request.resolve_uri(options.base_url.as_deref())?;
+
         requests.push(partial_request.build()?);
     }
 
@@ -668,14 +1300,18 @@ pub fn parse_requests(
 mod test_parse_requests {
     use core::str::FromStr;
 
-    use crate::{error::RequestParseError, parse_requests};
+    use crate::{
+        ParseOptions, error::RequestParseError, output::ResponseOutput, parse_requests,
+        parse_requests_with_options, tls::RequestTls, warning::RequestParseWarning,
+    };
 
     const HTTP_METHODS: [&str; 9] = [
         "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "CONNECT", "TRACE",
     ];
 
-    static EMPTY_VARS: std::sync::LazyLock<std::collections::HashMap<String, String>> =
-        std::sync::LazyLock::new(std::collections::HashMap::new);
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
 
     #[test]
     fn it_should_parse_http_method_correctly() {
@@ -885,7 +1521,7 @@ DELETE https://mhouge.dk/ HTTP/3
         assert_eq!(request.uri, "https://mhouge.dk/api?email=mads@mhouge.dk");
 
         assert_eq!(
-            "{ \"key\": \"value\" }",
+            "{\"key\":\"value\"}",
             request.body.clone().expect("body to be set"),
         );
     }
@@ -894,11 +1530,11 @@ DELETE https://mhouge.dk/ HTTP/3
     fn it_should_support_variable_input() {
         {
             let mut vars = std::collections::HashMap::from([
-                ("method".to_owned(), "GET".to_owned()),
-                ("host".to_owned(), "https://mhouge.dk".to_owned()),
-                ("path".to_owned(), "/api".to_owned()),
-                ("query_value".to_owned(), "mads@mhouge.dk".to_owned()),
-                ("body_input".to_owned(), "{ \"key\": \"value\" }".to_owned()),
+                ("method".to_owned(), "GET".into()),
+                ("host".to_owned(), "https://mhouge.dk".into()),
+                ("path".to_owned(), "/api".into()),
+                ("query_value".to_owned(), "mads@mhouge.dk".into()),
+                ("body_input".to_owned(), "{ \"key\": \"value\" }".into()),
             ]);
 
             let input = "
@@ -911,8 +1547,8 @@ DELETE https://mhouge.dk/ HTTP/3
                 let header_name = format!("mads-was-here{i}");
                 let header_value = format!("or was i{i}?");
 
-                vars.insert("header_name".to_owned(), header_name.clone());
-                vars.insert("header_value".to_owned(), header_value.clone());
+                vars.insert("header_name".to_owned(), header_name.clone().into());
+                vars.insert("header_value".to_owned(), header_value.clone().into());
 
                 let requests = parse_requests(input, &vars).expect("to get a list of requests");
 
@@ -965,7 +1601,7 @@ GET https://mhouge.dk/
 
     #[test]
     fn input_variables_should_be_overwritten_by_local_variables() {
-        let vars = std::collections::HashMap::from([("method".to_owned(), "PUT".to_owned())]);
+        let vars = std::collections::HashMap::from([("method".to_owned(), "PUT".into())]);
 
         let input = "
 @method = POST
@@ -1009,4 +1645,959 @@ DELETE https://mhouge.dk/";
 
         assert!(request.body.is_none());
     }
+
+    #[test]
+    fn it_should_collect_secret_values() {
+        let input = "
+@secret token = abc123
+@user = mads
+
+GET https://mhouge.dk/?user={{user}}
+authorization: Bearer {{token}}
+
+###
+
+@secret api_key = def456
+
+GET https://mhouge.dk/";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        assert_eq!(requests.len(), 2);
+
+        let first = requests.first().expect("it to exist");
+
+        assert_eq!(first.secrets, vec!["abc123".to_owned()]);
+
+        assert_eq!(
+            first
+                .headers
+                .get("authorization")
+                .expect("it to exist")
+                .to_str()
+                .expect("it to be a valid str"),
+            "Bearer abc123"
+        );
+
+        let second = requests.get(1).expect("it to exist");
+
+        assert_eq!(
+            second.secrets,
+            vec!["abc123".to_owned(), "def456".to_owned()]
+        );
+    }
+
+    #[test]
+    fn masked_prompts_should_be_secret() {
+        let vars = std::collections::HashMap::from([
+            ("password".to_owned(), "hunter2".into()),
+            ("otp".to_owned(), "123456".into()),
+        ]);
+
+        let input = "
+# @prompt password
+# @prompt otp
+POST https://mhouge.dk/?otp={{otp}}
+
+{{password}}";
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        let request = requests.first().expect("it to exist");
+
+        assert_eq!(request.secrets, vec!["hunter2".to_owned()]);
+    }
+
+    #[test]
+    fn a_variable_named_secret_should_not_be_secret() {
+        let input = "
+@secret = value
+
+GET https://mhouge.dk/?secret={{secret}}";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        let request = requests.first().expect("it to exist");
+
+        assert!(request.secrets.is_empty());
+
+        assert_eq!(request.uri, "https://mhouge.dk/?secret=value");
+    }
+
+    #[test]
+    fn variables_declared_before_the_first_request_should_be_shared() {
+        let input = "
+@host = https://mhouge.dk
+
+GET {{host}}/first
+
+###
+
+GET {{host}}/second";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        assert_eq!(requests.len(), 2);
+
+        assert_eq!(
+            requests.first().expect("it to exist").uri,
+            "https://mhouge.dk/first"
+        );
+
+        assert_eq!(
+            requests.get(1).expect("it to exist").uri,
+            "https://mhouge.dk/second"
+        );
+    }
+
+    #[test]
+    fn variables_declared_in_a_request_block_should_be_local() {
+        let input = "
+GET https://mhouge.dk/
+
+###
+
+@path = /local
+
+GET https://mhouge.dk{{path}}
+
+###
+
+GET https://mhouge.dk{{path}}";
+
+        let error = parse_requests(input, &EMPTY_VARS).expect_err("it to return an error");
+
+        assert!(matches!(error, RequestParseError::VariableNotFound(var) if var == "path"));
+    }
+
+    #[test]
+    fn local_variables_should_shadow_file_variables() {
+        let input = "
+@path = /global
+
+GET https://mhouge.dk{{path}}
+
+###
+
+@path = /local
+
+GET https://mhouge.dk{{path}}
+
+###
+
+GET https://mhouge.dk{{path}}";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        assert_eq!(requests.len(), 3);
+
+        let first = requests.first().expect("it to exist");
+        assert_eq!(first.uri, "https://mhouge.dk/global");
+        assert!(first.warnings.is_empty());
+
+        let second = requests.get(1).expect("it to exist");
+        assert_eq!(second.uri, "https://mhouge.dk/local");
+        assert_eq!(
+            second.warnings,
+            vec![RequestParseWarning::VariableShadowed("path".to_owned())]
+        );
+        assert_eq!(
+            "request variable 'path' shadows a variable declared at the top of the file",
+            second
+                .warnings
+                .first()
+                .expect("it to have a warning")
+                .to_string()
+        );
+
+        let third = requests.get(2).expect("it to exist");
+        assert_eq!(third.uri, "https://mhouge.dk/global");
+        assert!(third.warnings.is_empty());
+    }
+
+    #[test]
+    fn local_variables_should_not_warn_when_shadowing_input_variables() {
+        let vars = std::collections::HashMap::from([("path".to_owned(), "/input".into())]);
+
+        let input = "
+GET https://mhouge.dk{{path}}
+
+###
+
+@path = /local
+
+GET https://mhouge.dk{{path}}";
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        let second = requests.get(1).expect("it to exist");
+
+        assert_eq!(second.uri, "https://mhouge.dk/local");
+        assert!(second.warnings.is_empty());
+    }
+
+    #[test]
+    fn local_secrets_should_not_leak_into_other_requests() {
+        let input = "
+GET https://mhouge.dk/
+
+###
+
+@secret token = abc123
+
+GET https://mhouge.dk/?token={{token}}
+
+###
+
+GET https://mhouge.dk/";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        assert!(requests.first().expect("it to exist").secrets.is_empty());
+
+        assert_eq!(
+            requests.get(1).expect("it to exist").secrets,
+            vec!["abc123".to_owned()]
+        );
+
+        assert!(requests.get(2).expect("it to exist").secrets.is_empty());
+    }
+
+    #[test]
+    fn default_variables_should_be_used_if_not_set() {
+        let input = "
+@host ?= https://mhouge.dk
+
+GET {{host}}/";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        assert_eq!(
+            requests.first().expect("it to exist").uri,
+            "https://mhouge.dk/"
+        );
+    }
+
+    #[test]
+    fn default_variables_should_not_overwrite_input_variables() {
+        let vars =
+            std::collections::HashMap::from([("host".to_owned(), "http://localhost:5000".into())]);
+
+        let input = "
+@host ?= https://mhouge.dk
+
+GET {{host}}/";
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        assert_eq!(
+            requests.first().expect("it to exist").uri,
+            "http://localhost:5000/"
+        );
+    }
+
+    #[test]
+    fn default_variables_should_not_overwrite_file_variables() {
+        let input = "
+@host = https://mhouge.dk
+
+GET {{host}}/
+
+###
+
+@host ?= http://localhost:5000
+
+GET {{host}}/";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        let second = requests.get(1).expect("it to exist");
+
+        assert_eq!(second.uri, "https://mhouge.dk/");
+        assert!(second.warnings.is_empty());
+    }
+
+    #[test]
+    fn default_secrets_should_be_redacted_when_overwritten() {
+        let vars = std::collections::HashMap::from([("token".to_owned(), "abc123".into())]);
+
+        let input = "
+@secret token ?= default
+
+GET https://mhouge.dk/?token={{token}}";
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        let request = requests.first().expect("it to exist");
+
+        assert_eq!(request.uri, "https://mhouge.dk/?token=abc123");
+        assert_eq!(request.secrets, vec!["abc123".to_owned()]);
+    }
+
+    #[test]
+    fn input_variables_should_overwrite_declarations_when_overriding() {
+        let vars = std::collections::HashMap::from([("method".to_owned(), "PUT".into())]);
+
+        let options = ParseOptions {
+            override_variables: true,
+            ..ParseOptions::default()
+        };
+
+        let input = "
+@method = POST
+@path = /global
+
+{{method}} https://mhouge.dk{{path}}
+
+###
+
+@method = DELETE
+
+{{method}} https://mhouge.dk{{path}}";
+
+        let requests =
+            parse_requests_with_options(input, &vars, &options).expect("it to parse successfully");
+
+        assert_eq!(requests.len(), 2);
+
+        for request in &requests {
+            assert_eq!(request.method, http::method::Method::PUT);
+            assert_eq!(request.uri, "https://mhouge.dk/global");
+            assert!(request.warnings.is_empty());
+        }
+    }
+
+    #[test]
+    fn it_should_support_escaped_braces() {
+        let vars = std::collections::HashMap::from([("name".to_owned(), "mads".into())]);
+
+        let input = r"
+@template = \{{ name }}
+
+POST https://mhouge.dk/
+x-template: \{{name}}
+
+{{name}} \{{name}} {{ template }}";
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        let request = requests.first().expect("it to exist");
+
+        assert_eq!(
+            request
+                .headers
+                .get("x-template")
+                .expect("it to have a header"),
+            "{{name}}"
+        );
+
+        assert_eq!(request.body.as_deref(), Some("mads {{name}} {{ name }}"));
+    }
+
+    #[test]
+    fn it_should_not_interpolate_raw_bodies() {
+        let input = r"
+# @raw-body
+POST https://mhouge.dk/
+
+{{#each users}}{{name}}{{/each}} \{{
+
+###
+
+POST https://mhouge.dk/
+
+{{name}}";
+
+        let err = parse_requests(input, &EMPTY_VARS).expect_err("it to return an error");
+
+        assert!(matches!(err, RequestParseError::VariableNotFound(var) if var == "name"));
+
+        let vars = std::collections::HashMap::from([("name".to_owned(), "mads".into())]);
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        assert_eq!(
+            requests.first().expect("it to exist").body.as_deref(),
+            Some("{{#each users}}{{name}}{{/each}} \\{{\n")
+        );
+
+        assert_eq!(
+            requests.get(1).expect("it to exist").body.as_deref(),
+            Some("mads")
+        );
+    }
+
+    #[test]
+    fn it_should_support_query_continuation_lines() {
+        let vars = std::collections::HashMap::from([("name".to_owned(), "mads was here".into())]);
+
+        let input = "
+GET https://mhouge.dk/api
+    ?name={{name}}
+    &page=1 HTTP/2
+content-type: application/json";
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        let request = requests.first().expect("it to exist");
+
+        assert_eq!(
+            request.uri,
+            "https://mhouge.dk/api?name=mads%20was%20here&page=1"
+        );
+        assert_eq!(request.http_version, Some(http::Version::HTTP_2));
+        assert_eq!(request.headers.len(), 1);
+    }
+
+    #[test]
+    fn it_should_support_query_continuation_lines_after_http_version() {
+        let input = "
+GET https://mhouge.dk/x HTTP/1.1
+?a=1
+content-type: application/json";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        let request = requests.first().expect("it to exist");
+
+        assert_eq!(request.uri, "https://mhouge.dk/x?a=1");
+        assert_eq!(request.http_version, Some(http::Version::HTTP_11));
+        assert_eq!(request.headers.len(), 1);
+    }
+
+    #[test]
+    fn it_should_reject_http_version_on_both_lines() {
+        let input = "
+GET https://mhouge.dk/x HTTP/1.1
+?a=1 HTTP/2";
+
+        let error = parse_requests(input, &EMPTY_VARS).expect_err("it to reject the request");
+
+        assert!(matches!(error, RequestParseError::DuplicateHttpVersion));
+        assert_eq!(
+            "the HTTP version can only be set once per request",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn it_should_encode_form_bodies() {
+        let vars = std::collections::HashMap::from([("name".to_owned(), "mads & co".into())]);
+
+        let input = "
+POST https://mhouge.dk/
+content-type: application/x-www-form-urlencoded
+
+name = {{name}}
+message = first line
+    second line
+
+###
+
+POST https://mhouge.dk/
+content-type: text/plain
+
+name = {{name}}";
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        assert_eq!(
+            requests.first().expect("it to exist").body.as_deref(),
+            Some("name=mads+%26+co&message=first+line%0Asecond+line")
+        );
+
+        assert_eq!(
+            requests.get(1).expect("it to exist").body.as_deref(),
+            Some("name = mads & co")
+        );
+    }
+
+    #[test]
+    fn it_should_support_heredoc_variables() {
+        let input = "
+@name = mads
+@payload = <<EOF
+{
+  # not a comment
+  \"name\": \"{{name}}\"
+}
+EOF
+
+POST https://mhouge.dk/
+content-type: application/json
+
+{{payload}}";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        assert_eq!(
+            requests.first().expect("it to exist").body.as_deref(),
+            Some("{\n  # not a comment\n  \"name\": \"mads\"\n}")
+        );
+    }
+
+    #[test]
+    fn it_should_support_heredoc_variables_in_headers() {
+        let input = "
+@secret token = <<TOKEN
+abc123
+TOKEN
+
+GET https://mhouge.dk/
+authorization: Bearer {{token}}";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        let request = requests.first().expect("it to exist");
+
+        assert_eq!(
+            request
+                .headers
+                .get("authorization")
+                .expect("it to have a header"),
+            "Bearer abc123"
+        );
+
+        assert_eq!(request.secrets, vec!["abc123".to_owned()]);
+    }
+
+    #[test]
+    fn it_should_reject_unterminated_heredoc_variables() {
+        let input = "
+@payload = <<EOF
+{}
+
+GET https://mhouge.dk/";
+
+        let err = parse_requests(input, &EMPTY_VARS).expect_err("it to return an error");
+
+        assert_eq!(
+            "variable 'payload' is missing the closing 'EOF' line",
+            err.to_string()
+        );
+
+        assert!(matches!(
+            err,
+            RequestParseError::UnterminatedHeredoc(name, terminator)
+            if name == "payload" && terminator == "EOF"
+        ));
+    }
+
+    #[test]
+    fn it_should_support_typed_variables() {
+        let vars = std::collections::HashMap::from([
+            (
+                "user".to_owned(),
+                crate::value::VariableValue::from(serde_json::json!({
+                    "id": 1,
+                    "address": { "city": "Aarhus" }
+                })),
+            ),
+            (
+                "ids".to_owned(),
+                crate::value::VariableValue::from(serde_json::json!([10, 20])),
+            ),
+        ]);
+
+        let input = "
+POST https://mhouge.dk/users/{{ids[1]}}?city={{user.address.city}}
+x-user-id: {{user.id}}
+
+{{user}}";
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        let request = requests.first().expect("it to exist");
+
+        assert_eq!(request.uri, "https://mhouge.dk/users/20?city=Aarhus");
+
+        assert_eq!(
+            request
+                .headers
+                .get("x-user-id")
+                .expect("it to have a header"),
+            "1"
+        );
+
+        assert_eq!(
+            request.body.as_deref(),
+            Some("{\"address\":{\"city\":\"Aarhus\"},\"id\":1}")
+        );
+
+        let err = parse_requests("GET https://mhouge.dk/{{user.name}}", &vars)
+            .expect_err("it to return an error");
+
+        assert!(matches!(err, RequestParseError::VariableNotFound(var) if var == "user.name"));
+    }
+
+    #[test]
+    fn it_should_keep_types_of_declared_json_variables() {
+        let input = r#"
+@user = { "id": 1, "address": { "city": "Aarhus" } }
+@ids = [10, 20]
+
+POST https://mhouge.dk/users/{{ids[1]}}?city={{user.address.city}}
+x-user-id: {{user.id}}
+"#;
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        let request = requests.first().expect("it to exist");
+
+        assert_eq!(request.uri, "https://mhouge.dk/users/20?city=Aarhus");
+
+        assert_eq!(
+            request
+                .headers
+                .get("x-user-id")
+                .expect("it to have a header"),
+            "1"
+        );
+    }
+
+    #[test]
+    fn strict_mode_should_reject_invalid_request_lines() {
+        let options = ParseOptions {
+            strict: true,
+            ..ParseOptions::default()
+        };
+
+        for (input, expected_error) in [
+            (
+                "GET https://mhouge.dk/ HTTP/1.2",
+                "invalid HTTP version 'HTTP/1.2'",
+            ),
+            (
+                "GET https://mhouge.dk/ HTTP/1.1 mads",
+                "unexpected input 'mads' after the HTTP version",
+            ),
+            (
+                "GET https://mhouge.dk/ {{version}}",
+                "variable 'version' was used, but not set",
+            ),
+            (
+                "GET https://mhouge.dk/\n    ?key=value HTTP/2 mads",
+                "unexpected input 'mads' after the HTTP version",
+            ),
+        ] {
+            let error = parse_requests_with_options(input, &EMPTY_VARS, &options)
+                .expect_err("it to return an error");
+
+            assert_eq!(expected_error, error.to_string());
+
+            parse_requests(input, &EMPTY_VARS).expect("it to be ignored when not strict");
+        }
+
+        let requests = parse_requests_with_options(
+            "GET https://mhouge.dk/\n    ?key=value HTTP/2\n\n###\n\nGET https://mhouge.dk/",
+            &EMPTY_VARS,
+            &options,
+        )
+        .expect("it to parse successfully");
+
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests.first().expect("it to exist").http_version,
+            Some(http::Version::HTTP_2)
+        );
+    }
+
+    #[test]
+    fn it_should_resolve_relative_uris() {
+        let options = ParseOptions {
+            base_url: Some("https://example.com/".to_owned()),
+            ..ParseOptions::default()
+        };
+
+        let input = "
+@baseUrl = https://mhouge.dk/api
+
+GET /users
+    ?page=1
+
+###
+
+GET /users
+host: localhost:5000
+
+###
+
+@baseUrl = https://mhouge.dk/v2
+GET https://example.org/users
+
+###
+
+GET /users";
+
+        let requests =
+            parse_requests_with_options(input, &EMPTY_VARS, &options).expect("it to parse");
+
+        let uris = requests
+            .iter()
+            .map(|request| request.uri.to_string())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            uris,
+            [
+                "https://mhouge.dk/api/users?page=1",
+                "http://localhost:5000/users",
+                "https://example.org/users",
+                "https://mhouge.dk/api/users",
+            ]
+        );
+    }
+
+    #[test]
+    fn it_should_use_base_url_option() {
+        let options = ParseOptions {
+            base_url: Some("localhost:5000".to_owned()),
+            ..ParseOptions::default()
+        };
+
+        let requests = parse_requests_with_options("GET /users?page=1", &EMPTY_VARS, &options)
+            .expect("it to parse");
+
+        assert_eq!(
+            requests.first().expect("it to exist").uri,
+            "http://localhost:5000/users?page=1"
+        );
+    }
+
+    #[test]
+    fn it_should_reject_relative_uris_without_base_url() {
+        let error = parse_requests("GET /users", &EMPTY_VARS).expect_err("it to return an error");
+
+        assert_eq!(
+            "relative uri '/users' is missing a base url",
+            error.to_string()
+        );
+        assert!(matches!(error, RequestParseError::MissingBaseUrl(uri) if uri == "/users"));
+    }
+
+    #[test]
+    fn it_should_support_request_names_and_outputs() {
+        let vars = std::collections::HashMap::from([("dir".to_owned(), "out".into())]);
+
+        let input = "
+# @name get-user
+GET https://mhouge.dk/users/1
+
+>> ./{{dir}}/user.json
+
+###
+
+// @name create-user
+POST https://mhouge.dk/users
+content-type: application/json
+
+{ \"name\": \"mads\" }
+
+>>! ./out.json
+
+###
+
+GET https://mhouge.dk/
+>> ./index.html";
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        assert_eq!(requests.len(), 3);
+
+        let get_user = requests.first().expect("it to exist");
+        assert_eq!(get_user.name.as_deref(), Some("get-user"));
+        assert_eq!(
+            get_user.output,
+            Some(ResponseOutput {
+                path: std::path::PathBuf::from("./out/user.json"),
+                force: false,
+            })
+        );
+
+        let create_user = requests.get(1).expect("it to exist");
+        assert_eq!(create_user.name.as_deref(), Some("create-user"));
+        assert_eq!(
+            create_user.body.as_deref(),
+            Some("{ \"name\": \"mads\" }\n")
+        );
+        assert_eq!(
+            create_user.output,
+            Some(ResponseOutput {
+                path: std::path::PathBuf::from("./out.json"),
+                force: true,
+            })
+        );
+
+        let index = requests.get(2).expect("it to exist");
+        assert_eq!(index.name, None);
+        assert_eq!(index.headers.len(), 0);
+        assert_eq!(
+            index.output.as_ref().map(|output| output.path.as_path()),
+            Some(std::path::Path::new("./index.html"))
+        );
+    }
+
+    #[test]
+    fn it_should_only_accept_output_redirects_after_the_body() {
+        let input = "
+POST https://mhouge.dk/notes
+content-type: text/markdown
+
+> quote
+>> nested quote
+done
+
+###
+
+# @raw-body
+POST https://mhouge.dk/notes
+content-type: text/markdown
+
+>> {{not a path}}
+done
+
+>> ./out.md
+";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        let quotes = requests.first().expect("it to exist");
+        assert_eq!(
+            quotes.body.as_deref(),
+            Some("> quote\n>> nested quote\ndone\n")
+        );
+        assert_eq!(quotes.output, None);
+
+        let raw = requests.get(1).expect("it to exist");
+        assert_eq!(raw.body.as_deref(), Some(">> {{not a path}}\ndone\n"));
+        assert_eq!(
+            raw.output.as_ref().map(|output| output.path.as_path()),
+            Some(std::path::Path::new("./out.md"))
+        );
+    }
+
+    #[test]
+    fn it_should_support_redirect_directives() {
+        let input = "
+# @max-redirects 3
+GET https://mhouge.dk/a
+
+###
+
+// @no-redirect
+GET https://mhouge.dk/b
+
+###
+
+GET https://mhouge.dk/c";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        assert_eq!(
+            [Some(3), Some(0), None],
+            [
+                requests.first().expect("it to exist").max_redirects,
+                requests.get(1).expect("it to exist").max_redirects,
+                requests.get(2).expect("it to exist").max_redirects,
+            ]
+        );
+
+        let error = parse_requests("# @max-redirects many\nGET https://mhouge.dk/", &EMPTY_VARS)
+            .expect_err("it to reject invalid values");
+
+        assert_eq!(
+            "invalid max redirects 'many' - expected a number",
+            error.to_string()
+        );
+
+        assert!(matches!(error, RequestParseError::InvalidMaxRedirects(value) if value == "many"));
+    }
+
+    #[test]
+    fn it_should_support_unix_socket_directive() {
+        let vars = std::collections::HashMap::from([(
+            "socket".to_owned(),
+            crate::value::VariableValue::from("docker.sock"),
+        )]);
+
+        let input = "
+# @unix-socket /var/run/{{ socket }}
+GET http://localhost/containers/json
+
+###
+
+GET http://localhost/info";
+
+        let requests = parse_requests(input, &vars).expect("it to parse successfully");
+
+        assert_eq!(
+            [Some(std::path::Path::new("/var/run/docker.sock")), None],
+            [
+                requests
+                    .first()
+                    .expect("it to exist")
+                    .unix_socket
+                    .as_deref(),
+                requests.get(1).expect("it to exist").unix_socket.as_deref(),
+            ]
+        );
+
+        let error = parse_requests("# @unix-socket\nGET http://localhost/", &EMPTY_VARS)
+            .expect_err("it to reject missing paths");
+
+        assert!(
+            matches!(error, RequestParseError::MissingDirectiveValue(directive) if directive == "unix-socket")
+        );
+    }
+
+    #[test]
+    fn it_should_support_tls_directives() {
+        let input = "
+@certDir = ./certs
+
+# @cacert {{certDir}}/ca.pem
+# @cert {{certDir}}/client.p12
+# @cert-password hunter2
+GET https://mhouge.dk/a
+
+###
+
+// @insecure
+// @cert client.pem
+// @key client.key
+GET https://mhouge.dk/b
+
+###
+
+GET https://mhouge.dk/c";
+
+        let requests = parse_requests(input, &EMPTY_VARS).expect("it to parse successfully");
+
+        assert_eq!(
+            RequestTls {
+                ca_certificate: Some(std::path::PathBuf::from("./certs/ca.pem")),
+                client_certificate: Some(std::path::PathBuf::from("./certs/client.p12")),
+                client_key: None,
+                certificate_password: Some("hunter2".to_owned()),
+                insecure: false,
+            },
+            requests.first().expect("it to exist").tls
+        );
+
+        assert_eq!(
+            RequestTls {
+                ca_certificate: None,
+                client_certificate: Some(std::path::PathBuf::from("client.pem")),
+                client_key: Some(std::path::PathBuf::from("client.key")),
+                certificate_password: None,
+                insecure: true,
+            },
+            requests.get(1).expect("it to exist").tls
+        );
+
+        assert_eq!(
+            RequestTls::default(),
+            requests.get(2).expect("it to exist").tls
+        );
+    }
 }
@@ -1,6 +1,8 @@
 use core::str::FromStr;
 
-use crate::{RequestToken, error::RequestParseError, variables::parse_variable};
+use crate::{
+    RequestToken, error::RequestParseError, value::get_variable, variables::parse_variable,
+};
 
 impl From<http::method::Method> for RequestToken {
     #[inline]
@@ -36,7 +38,7 @@ mod test_from_method_from_request_token {
 #[inline]
 pub fn parse_method_input(
     chars: &mut core::iter::Enumerate<core::str::Chars>,
-    vars: &std::collections::HashMap<String, String>,
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
 ) -> Result<http::method::Method, RequestParseError> {
     let mut method = String::new();
 
@@ -48,8 +50,8 @@ pub fn parse_method_input(
         } else if ch == '{' {
             // FIXME: remove clone
             if let Some((var, jumps)) = parse_variable(&mut chars.clone()) {
-                if let Some(var_value) = vars.get(&var) {
-                    method.push_str(var_value);
+                if let Some(var_value) = get_variable(vars, &var) {
+                    method.push_str(&var_value.to_string());
 
                     for _ in 0..jumps {
                         chars.next();
@@ -79,8 +81,9 @@ mod test_parse_method_input {
 
     use crate::{error::RequestParseError, method::parse_method_input, to_enum_chars};
 
-    static EMPTY_VARS: std::sync::LazyLock<std::collections::HashMap<String, String>> =
-        std::sync::LazyLock::new(std::collections::HashMap::new);
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
 
     const HTTP_METHODS: [&str; 9] = [
         "GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS", "CONNECT", "TRACE",
@@ -130,7 +133,7 @@ mod test_parse_method_input {
         };
 
         for method in HTTP_METHODS {
-            vars.insert("method".to_owned(), method.to_owned());
+            vars.insert("method".to_owned(), method.to_owned().into());
 
             let expected_method =
                 http::method::Method::from_str(method).expect("it should return a valid method");
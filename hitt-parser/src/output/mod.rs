@@ -0,0 +1,107 @@
+use crate::{error::RequestParseError, variables::interpolate_variables};
+
+/// File the response of a request should be written to, declared using `>> path` or `>>! path`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ResponseOutput {
+    /// Path of the file, relative to the file containing the request.
+    pub path: std::path::PathBuf,
+    /// Set using `>>!`, which writes the response even if the status code is an error.
+    pub force: bool,
+}
+
+/// Parses a `>> path` or `>>! path` line.
+#[inline]
+pub fn parse_output_redirect(
+    line: &str,
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
+) -> Result<Option<ResponseOutput>, RequestParseError> {
+    let Some(redirect) = line.trim().strip_prefix(">>") else {
+        return Ok(None);
+    };
+
+    let (force, path) = redirect
+        .strip_prefix('!')
+        .map_or((false, redirect), |path| (true, path));
+
+    let path = interpolate_variables(path.trim(), vars)?;
+
+    if path.is_empty() {
+        return Err(RequestParseError::MissingOutputPath);
+    }
+
+    Ok(Some(ResponseOutput {
+        path: std::path::PathBuf::from(path),
+        force,
+    }))
+}
+
+#[cfg(test)]
+mod test_parse_output_redirect {
+    use super::{ResponseOutput, parse_output_redirect};
+    use crate::error::RequestParseError;
+
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
+
+    #[test]
+    fn it_should_parse_redirects() {
+        for (input, path, force) in [
+            (">> ./out/user.json", "./out/user.json", false),
+            (">>./out/user.json", "./out/user.json", false),
+            (">>! ./out.json", "./out.json", true),
+            ("  >>!   out.json  ", "out.json", true),
+        ] {
+            let output = parse_output_redirect(input, &EMPTY_VARS)
+                .expect("it to parse")
+                .expect("it to return an output");
+
+            assert_eq!(
+                output,
+                ResponseOutput {
+                    path: std::path::PathBuf::from(path),
+                    force,
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_support_variables() {
+        let vars = std::collections::HashMap::from([("dir".to_owned(), "fixtures".into())]);
+
+        let output = parse_output_redirect(">> ./{{dir}}/user.json", &vars)
+            .expect("it to parse")
+            .expect("it to return an output");
+
+        assert_eq!(
+            output.path,
+            std::path::PathBuf::from("./fixtures/user.json")
+        );
+    }
+
+    #[test]
+    fn it_should_ignore_other_lines() {
+        for input in [
+            "> out.json",
+            "{\"key\": \">> value\"}",
+            "GET https://mhouge.dk/",
+        ] {
+            assert_eq!(
+                None,
+                parse_output_redirect(input, &EMPTY_VARS).expect("it to parse")
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_reject_missing_path() {
+        for input in [">>", ">>!  "] {
+            let error =
+                parse_output_redirect(input, &EMPTY_VARS).expect_err("it to return an error");
+
+            assert_eq!("missing file path after '>>'", error.to_string());
+            assert!(matches!(error, RequestParseError::MissingOutputPath));
+        }
+    }
+}
@@ -0,0 +1,136 @@
+const MASKED_NAME_PATTERNS: [&str; 6] =
+    ["password", "passwd", "secret", "token", "apikey", "api_key"];
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PromptDeclaration {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+impl PromptDeclaration {
+    /// Whether the input should be hidden while it is being typed.
+    #[inline]
+    pub fn is_masked(&self) -> bool {
+        let lowercase_name = self.name.to_lowercase();
+
+        MASKED_NAME_PATTERNS
+            .iter()
+            .any(|pattern| lowercase_name.contains(pattern))
+    }
+}
+
+#[cfg(test)]
+mod test_is_masked {
+    use super::PromptDeclaration;
+
+    #[test]
+    fn it_should_mask_sensitive_names() {
+        for name in [
+            "password",
+            "PASSWORD",
+            "user_password",
+            "passwd",
+            "client_secret",
+            "token",
+            "accessToken",
+            "apikey",
+            "api_key",
+        ] {
+            let prompt = PromptDeclaration {
+                name: name.to_owned(),
+                description: None,
+            };
+
+            assert!(prompt.is_masked(), "expected '{name}' to be masked");
+        }
+    }
+
+    #[test]
+    fn it_should_not_mask_other_names() {
+        for name in ["otp", "user_id", "username", "host"] {
+            let prompt = PromptDeclaration {
+                name: name.to_owned(),
+                description: None,
+            };
+
+            assert!(!prompt.is_masked(), "expected '{name}' not to be masked");
+        }
+    }
+}
+
+/// Parses a `# @prompt name description` comment line.
+#[inline]
+pub fn parse_prompt_declaration(line: &str) -> Option<PromptDeclaration> {
+    let comment = line
+        .strip_prefix('#')
+        .or_else(|| line.strip_prefix("//"))?
+        .trim_start();
+
+    let declaration = comment.strip_prefix("@prompt")?;
+
+    if !declaration.starts_with(char::is_whitespace) {
+        return None;
+    }
+
+    let mut parts = declaration.trim().splitn(2, char::is_whitespace);
+
+    let name = parts.next().filter(|name| !name.is_empty())?;
+
+    let description = parts
+        .next()
+        .map(str::trim)
+        .filter(|description| !description.is_empty())
+        .map(ToOwned::to_owned);
+
+    Some(PromptDeclaration {
+        name: name.to_owned(),
+        description,
+    })
+}
+
+#[cfg(test)]
+mod test_parse_prompt_declaration {
+    use super::{PromptDeclaration, parse_prompt_declaration};
+
+    #[test]
+    fn it_should_parse_prompt_declarations() {
+        for comment_style in ["#", "//"] {
+            let input = format!("{comment_style} @prompt otp Enter the 2FA code");
+
+            let prompt = parse_prompt_declaration(&input).expect("it to return a prompt");
+
+            assert_eq!(
+                prompt,
+                PromptDeclaration {
+                    name: "otp".to_owned(),
+                    description: Some("Enter the 2FA code".to_owned()),
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_allow_missing_description() {
+        for input in ["# @prompt otp", "#@prompt otp   ", "//   @prompt    otp"] {
+            let prompt = parse_prompt_declaration(input).expect("it to return a prompt");
+
+            assert_eq!(prompt.name, "otp");
+            assert_eq!(prompt.description, None);
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_other_lines() {
+        for input in [
+            "# a regular comment",
+            "// a regular comment",
+            "# @prompt",
+            "# @prompt   ",
+            "# @prompted otp",
+            "@prompt otp",
+            "GET https://mhouge.dk/",
+        ] {
+            assert_eq!(None, parse_prompt_declaration(input));
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{error::RequestParseError, variables::interpolate_variables};
+
+/// TLS settings of a single request, overriding the ones of the client.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct RequestTls {
+    /// Set using `# @cacert path`, relative to the file containing the request.
+    pub ca_certificate: Option<std::path::PathBuf>,
+    /// Set using `# @cert path`, relative to the file containing the request.
+    pub client_certificate: Option<std::path::PathBuf>,
+    /// Set using `# @key path`, relative to the file containing the request.
+    pub client_key: Option<std::path::PathBuf>,
+    /// Set using `# @cert-password password`.
+    pub certificate_password: Option<String>,
+    /// Set using `# @insecure`.
+    pub insecure: bool,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum TlsDirective {
+    CaCertificate(std::path::PathBuf),
+    CertificatePassword(String),
+    ClientCertificate(std::path::PathBuf),
+    ClientKey(std::path::PathBuf),
+    Insecure,
+}
+
+impl RequestTls {
+    #[inline]
+    pub fn apply(&mut self, directive: TlsDirective) {
+        match directive {
+            TlsDirective::CaCertificate(path) => self.ca_certificate = Some(path),
+            TlsDirective::CertificatePassword(password) => {
+                self.certificate_password = Some(password);
+            }
+            TlsDirective::ClientCertificate(path) => self.client_certificate = Some(path),
+            TlsDirective::ClientKey(path) => self.client_key = Some(path),
+            TlsDirective::Insecure => self.insecure = true,
+        }
+    }
+}
+
+/// Parses the value of a `# @insecure`, `# @cacert`, `# @cert`, `# @key` or `# @cert-password`
+/// directive.
+#[inline]
+pub fn parse_tls_directive(
+    directive: &str,
+    value: &str,
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
+) -> Result<Option<TlsDirective>, RequestParseError> {
+    if directive == "insecure" {
+        return Ok(Some(TlsDirective::Insecure));
+    }
+
+    if !matches!(directive, "cacert" | "cert" | "key" | "cert-password") {
+        return Ok(None);
+    }
+
+    let value = interpolate_variables(value, vars)?;
+
+    if value.is_empty() {
+        return Err(RequestParseError::MissingDirectiveValue(
+            directive.to_owned(),
+        ));
+    }
+
+    Ok(Some(match directive {
+        "cacert" => TlsDirective::CaCertificate(std::path::PathBuf::from(value)),
+        "cert" => TlsDirective::ClientCertificate(std::path::PathBuf::from(value)),
+        "key" => TlsDirective::ClientKey(std::path::PathBuf::from(value)),
+        _ => TlsDirective::CertificatePassword(value),
+    }))
+}
+
+#[cfg(test)]
+mod test_parse_tls_directive {
+    use super::{TlsDirective, parse_tls_directive};
+    use crate::error::RequestParseError;
+
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
+
+    #[test]
+    fn it_should_parse_directives() {
+        for (directive, value, expected) in [
+            ("insecure", "", TlsDirective::Insecure),
+            (
+                "cacert",
+                "./certs/ca.pem",
+                TlsDirective::CaCertificate(std::path::PathBuf::from("./certs/ca.pem")),
+            ),
+            (
+                "cert",
+                "client.p12",
+                TlsDirective::ClientCertificate(std::path::PathBuf::from("client.p12")),
+            ),
+            (
+                "key",
+                "client.key",
+                TlsDirective::ClientKey(std::path::PathBuf::from("client.key")),
+            ),
+            (
+                "cert-password",
+                "hunter2",
+                TlsDirective::CertificatePassword("hunter2".to_owned()),
+            ),
+        ] {
+            assert_eq!(
+                Some(expected),
+                parse_tls_directive(directive, value, &EMPTY_VARS).expect("it to parse")
+            );
+        }
+
+        assert_eq!(
+            None,
+            parse_tls_directive("name", "login", &EMPTY_VARS).expect("it to parse")
+        );
+    }
+
+    #[test]
+    fn it_should_support_variables() {
+        let vars = std::collections::HashMap::from([("password".to_owned(), "hunter2".into())]);
+
+        assert_eq!(
+            Some(TlsDirective::CertificatePassword("hunter2".to_owned())),
+            parse_tls_directive("cert-password", "{{password}}", &vars).expect("it to parse")
+        );
+    }
+
+    #[test]
+    fn it_should_reject_missing_values() {
+        let error =
+            parse_tls_directive("cacert", "", &EMPTY_VARS).expect_err("it to return an error");
+
+        assert_eq!("missing value after '@cacert'", error.to_string());
+        assert!(
+            matches!(error, RequestParseError::MissingDirectiveValue(directive) if directive == "cacert")
+        );
+    }
+}
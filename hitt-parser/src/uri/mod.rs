@@ -1,6 +1,13 @@
 use core::str::FromStr;
 
-use crate::{RequestToken, error::RequestParseError, variables::parse_variable};
+use percent_encoding::{AsciiSet, CONTROLS, utf8_percent_encode};
+
+use crate::{
+    RequestToken,
+    error::RequestParseError,
+    value::get_variable,
+    variables::{parse_escaped_braces, parse_variable},
+};
 
 impl From<http::uri::Uri> for RequestToken {
     #[inline]
@@ -23,23 +30,158 @@ mod test_from_uri_for_request_token {
     }
 }
 
+/// Characters encoded when a variable is used in the path.
+///
+/// `/` is kept, so a value such as `api/v1` can contain multiple segments.
+const PATH_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'\\')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// Characters encoded when a variable is used in the query.
+const QUERY_ENCODE_SET: &AsciiSet = &PATH_ENCODE_SET.add(b'&').add(b'+').add(b'=');
+
+#[derive(Debug, PartialEq, Eq)]
+enum UriComponent {
+    Authority,
+    Path,
+    Query,
+}
+
 #[inline]
-pub fn parse_uri_input(
+fn current_uri_component(uri: &str) -> UriComponent {
+    let without_scheme = uri.split_once("://").map_or(uri, |(_, rest)| rest);
+
+    if without_scheme.contains(['?', '#']) {
+        UriComponent::Query
+    } else if without_scheme.contains('/') {
+        UriComponent::Path
+    } else {
+        UriComponent::Authority
+    }
+}
+
+#[cfg(test)]
+mod test_current_uri_component {
+    use super::{UriComponent, current_uri_component};
+
+    #[test]
+    fn it_should_detect_authority() {
+        for input in ["", "https://", "https://mhouge.dk", "localhost:5000"] {
+            assert_eq!(UriComponent::Authority, current_uri_component(input));
+        }
+    }
+
+    #[test]
+    fn it_should_detect_path() {
+        for input in [
+            "https://mhouge.dk/",
+            "https://mhouge.dk/api/",
+            "localhost:5000/",
+        ] {
+            assert_eq!(UriComponent::Path, current_uri_component(input));
+        }
+    }
+
+    #[test]
+    fn it_should_detect_query() {
+        for input in [
+            "https://mhouge.dk?",
+            "https://mhouge.dk/?key=",
+            "https://mhouge.dk/#",
+            "localhost:5000/api?key=value&other=",
+        ] {
+            assert_eq!(UriComponent::Query, current_uri_component(input));
+        }
+    }
+}
+
+/// Percent-encodes a variable value based on where in the uri it is used.
+///
+/// Values used in the scheme or authority are left untouched, so variables such as `{{host}}`
+/// can contain a full base url.
+#[inline]
+fn push_variable_value(uri: &mut String, value: &str) {
+    match current_uri_component(uri) {
+        UriComponent::Authority => uri.push_str(value),
+        UriComponent::Path => uri.extend(utf8_percent_encode(value, PATH_ENCODE_SET)),
+        UriComponent::Query => uri.extend(utf8_percent_encode(value, QUERY_ENCODE_SET)),
+    }
+}
+
+#[cfg(test)]
+mod test_push_variable_value {
+    use super::push_variable_value;
+
+    #[test]
+    fn it_should_not_encode_authority() {
+        let mut uri = String::new();
+
+        push_variable_value(&mut uri, "https://mhouge.dk/api?key=value");
+
+        assert_eq!(uri, "https://mhouge.dk/api?key=value");
+    }
+
+    #[test]
+    fn it_should_encode_path() {
+        let mut uri = "https://mhouge.dk/".to_owned();
+
+        push_variable_value(&mut uri, "mads was/here?#");
+
+        assert_eq!(uri, "https://mhouge.dk/mads%20was/here%3F%23");
+    }
+
+    #[test]
+    fn it_should_keep_slashes_in_path_values() {
+        let mut uri = "https://mhouge.dk/".to_owned();
+
+        push_variable_value(&mut uri, "api/v1");
+
+        assert_eq!(uri, "https://mhouge.dk/api/v1");
+    }
+
+    #[test]
+    fn it_should_encode_query() {
+        let mut uri = "https://mhouge.dk/?name=".to_owned();
+
+        push_variable_value(&mut uri, "mads & co=1+2/3 æ");
+
+        assert_eq!(
+            uri,
+            "https://mhouge.dk/?name=mads%20%26%20co%3D1%2B2/3%20%C3%A6"
+        );
+    }
+}
+
+/// Parses the uri until the next whitespace, appending it to the given buffer.
+#[inline]
+fn parse_uri_string(
     chars: &mut core::iter::Enumerate<core::str::Chars>,
-    vars: &std::collections::HashMap<String, String>,
-) -> Result<http::uri::Uri, RequestParseError> {
-    let mut uri = String::new();
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
+    uri: &mut String,
+) -> Result<(), RequestParseError> {
+    let initial_len = uri.len();
 
     while let Some((_, ch)) = chars.next() {
         if ch.is_whitespace() {
-            if !uri.is_empty() {
+            if uri.len() > initial_len {
                 break;
             }
         } else if ch == '{' {
             // FIXME: remove clone
             if let Some((var, jumps)) = parse_variable(&mut chars.clone()) {
-                if let Some(var_value) = vars.get(&var) {
-                    uri.push_str(var_value);
+                if let Some(var_value) = get_variable(vars, &var) {
+                    push_variable_value(uri, &var_value.to_string());
 
                     for _ in 0..jumps {
                         chars.next();
@@ -50,11 +192,25 @@ pub fn parse_uri_input(
             } else {
                 uri.push(ch);
             }
+        } else if ch == '\\' && parse_escaped_braces(chars) {
+            uri.push_str("{{");
         } else {
             uri.push(ch);
         }
     }
 
+    Ok(())
+}
+
+#[inline]
+pub fn parse_uri_input(
+    chars: &mut core::iter::Enumerate<core::str::Chars>,
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
+) -> Result<http::uri::Uri, RequestParseError> {
+    let mut uri = String::new();
+
+    parse_uri_string(chars, vars, &mut uri)?;
+
     http::uri::Uri::from_str(&uri).map_err(|_err| RequestParseError::InvalidUri(uri))
 }
 
@@ -62,8 +218,9 @@ pub fn parse_uri_input(
 mod test_parse_uri_input {
     use crate::{error::RequestParseError, to_enum_chars, uri::parse_uri_input};
 
-    static EMPTY_VARS: std::sync::LazyLock<std::collections::HashMap<String, String>> =
-        std::sync::LazyLock::new(std::collections::HashMap::new);
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
 
     #[test]
     fn it_should_be_able_to_parse_uris() {
@@ -125,6 +282,22 @@ mod test_parse_uri_input {
         }
     }
 
+    #[test]
+    fn it_should_support_multi_segment_path_variables() {
+        let vars = std::collections::HashMap::from([
+            ("baseUrl".to_owned(), "https://mhouge.dk".into()),
+            ("endpoint".to_owned(), "api/v1/users".into()),
+        ]);
+
+        let uri = parse_uri_input(
+            &mut to_enum_chars("{{baseUrl}}/{{endpoint}} HTTP/1.1"),
+            &vars,
+        )
+        .expect("it to parse the uri");
+
+        assert_eq!("https://mhouge.dk/api/v1/users", uri.to_string());
+    }
+
     #[test]
     fn it_should_support_variables() {
         let mut vars = std::collections::HashMap::new();
@@ -135,7 +308,7 @@ mod test_parse_uri_input {
         let variable_close = "}}";
 
         for i in i8::MIN..i8::MAX {
-            vars.insert(format!("i{i}"), i.to_string());
+            vars.insert(format!("i{i}"), i.to_string().into());
 
             let input = format!("{input_uri}?key={variable_open}i{i}{variable_close}");
 
@@ -229,4 +402,213 @@ mod test_parse_uri_input {
             ));
         }
     }
+
+    #[test]
+    fn it_should_encode_variables_in_path_and_query() {
+        let vars = std::collections::HashMap::from([
+            ("host".to_owned(), "https://mhouge.dk".into()),
+            ("name".to_owned(), "mads was here".into()),
+        ]);
+
+        let input = "{{host}}/users/{{name}}?name={{name}}&literal=a+b";
+
+        let uri = parse_uri_input(&mut to_enum_chars(input), &vars)
+            .expect("it should return a valid uri");
+
+        assert_eq!(
+            uri,
+            "https://mhouge.dk/users/mads%20was%20here?name=mads%20was%20here&literal=a+b"
+        );
+    }
+}
+
+/// Appends a query continuation line, starting with either `?` or `&`, to the uri.
+///
+/// The leading character is normalized, so the first parameter always starts with `?` and the
+/// following parameters with `&`.
+#[inline]
+pub fn parse_uri_continuation(
+    chars: &mut core::iter::Enumerate<core::str::Chars>,
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
+    uri: &http::uri::Uri,
+) -> Result<http::uri::Uri, RequestParseError> {
+    let mut output = uri.to_string();
+
+    if chars.next().is_some_and(|(_, ch)| ch == '?' || ch == '&') {
+        output.push(if output.contains('?') { '&' } else { '?' });
+    }
+
+    parse_uri_string(chars, vars, &mut output)?;
+
+    http::uri::Uri::from_str(&output).map_err(|_err| RequestParseError::InvalidUri(output))
+}
+
+#[cfg(test)]
+mod test_parse_uri_continuation {
+    use super::parse_uri_continuation;
+    use crate::{error::RequestParseError, to_enum_chars};
+
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
+
+    #[test]
+    fn it_should_append_query_parameters() {
+        let uri = http::Uri::from_static("https://mhouge.dk/api");
+
+        let uri = parse_uri_continuation(&mut to_enum_chars("?key=value"), &EMPTY_VARS, &uri)
+            .expect("it to return a valid uri");
+
+        assert_eq!(uri, "https://mhouge.dk/api?key=value");
+
+        let uri = parse_uri_continuation(&mut to_enum_chars("&other=value"), &EMPTY_VARS, &uri)
+            .expect("it to return a valid uri");
+
+        assert_eq!(uri, "https://mhouge.dk/api?key=value&other=value");
+    }
+
+    #[test]
+    fn it_should_normalize_separator() {
+        let uri = http::Uri::from_static("https://mhouge.dk/");
+
+        let uri = parse_uri_continuation(&mut to_enum_chars("&key=value"), &EMPTY_VARS, &uri)
+            .expect("it to return a valid uri");
+
+        assert_eq!(uri, "https://mhouge.dk/?key=value");
+
+        let uri = parse_uri_continuation(&mut to_enum_chars("?other=value"), &EMPTY_VARS, &uri)
+            .expect("it to return a valid uri");
+
+        assert_eq!(uri, "https://mhouge.dk/?key=value&other=value");
+    }
+
+    #[test]
+    fn it_should_encode_variables() {
+        let vars = std::collections::HashMap::from([("name".to_owned(), "mads & co".into())]);
+
+        let uri = http::Uri::from_static("https://mhouge.dk/");
+
+        let uri = parse_uri_continuation(&mut to_enum_chars("?name={{name}}"), &vars, &uri)
+            .expect("it to return a valid uri");
+
+        assert_eq!(uri, "https://mhouge.dk/?name=mads%20%26%20co");
+    }
+
+    #[test]
+    fn it_should_raise_if_variable_isnt_found() {
+        let uri = http::Uri::from_static("https://mhouge.dk/");
+
+        let err = parse_uri_continuation(&mut to_enum_chars("?name={{name}}"), &EMPTY_VARS, &uri)
+            .expect_err("it to return an error");
+
+        assert!(matches!(err, RequestParseError::VariableNotFound(var) if var == "name"));
+    }
+}
+
+/// Whether the uri only consists of a path, such as `/api/users`.
+#[inline]
+pub fn is_relative_uri(uri: &http::uri::Uri) -> bool {
+    uri.scheme().is_none() && uri.authority().is_none() && uri.path().starts_with('/')
+}
+
+#[cfg(test)]
+mod test_is_relative_uri {
+    use super::is_relative_uri;
+
+    #[test]
+    fn it_should_detect_relative_uris() {
+        for input in ["/", "/api/users", "/api/users?page=1"] {
+            assert!(is_relative_uri(&http::Uri::from_static(input)));
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_absolute_uris() {
+        for input in [
+            "https://mhouge.dk/",
+            "https://mhouge.dk/api",
+            "mhouge.dk",
+            "*",
+        ] {
+            assert!(!is_relative_uri(&http::Uri::from_static(input)));
+        }
+    }
+}
+
+/// Joins a relative uri with the base url.
+///
+/// The base url defaults to `http` if it does not include a scheme.
+#[inline]
+pub fn resolve_relative_uri(
+    uri: &http::uri::Uri,
+    base_url: &str,
+) -> Result<http::uri::Uri, RequestParseError> {
+    let mut output = if base_url.contains("://") {
+        base_url.trim().to_owned()
+    } else {
+        format!("http://{}", base_url.trim())
+    };
+
+    while output.ends_with('/') {
+        output.pop();
+    }
+
+    output.push_str(
+        uri.path_and_query()
+            .map_or("/", http::uri::PathAndQuery::as_str),
+    );
+
+    http::uri::Uri::from_str(&output).map_err(|_err| RequestParseError::InvalidUri(output))
+}
+
+#[cfg(test)]
+mod test_resolve_relative_uri {
+    use super::resolve_relative_uri;
+    use crate::error::RequestParseError;
+
+    #[test]
+    fn it_should_join_base_url() {
+        let uri = http::Uri::from_static("/api/users?page=1");
+
+        for base_url in [
+            "https://mhouge.dk",
+            "https://mhouge.dk/",
+            "https://mhouge.dk///",
+        ] {
+            let resolved = resolve_relative_uri(&uri, base_url).expect("it to return a valid uri");
+
+            assert_eq!(resolved, "https://mhouge.dk/api/users?page=1");
+        }
+    }
+
+    #[test]
+    fn it_should_keep_base_path() {
+        let uri = http::Uri::from_static("/users");
+
+        let resolved =
+            resolve_relative_uri(&uri, "https://mhouge.dk/v1/").expect("it to return a valid uri");
+
+        assert_eq!(resolved, "https://mhouge.dk/v1/users");
+    }
+
+    #[test]
+    fn it_should_default_to_http() {
+        let uri = http::Uri::from_static("/users");
+
+        let resolved =
+            resolve_relative_uri(&uri, "localhost:5000").expect("it to return a valid uri");
+
+        assert_eq!(resolved, "http://localhost:5000/users");
+    }
+
+    #[test]
+    fn it_should_reject_invalid_base_urls() {
+        let uri = http::Uri::from_static("/users");
+
+        let error = resolve_relative_uri(&uri, "mads was here").expect_err("it to return an error");
+
+        assert!(
+            matches!(error, RequestParseError::InvalidUri(uri) if uri == "http://mads was here/users")
+        );
+    }
 }
@@ -0,0 +1,338 @@
+/// A typed variable value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum VariableValue {
+    Null,
+    Bool(bool),
+    Number(serde_json::Number),
+    String(String),
+    Array(Vec<VariableValue>),
+    Object(std::collections::BTreeMap<String, VariableValue>),
+}
+
+impl From<String> for VariableValue {
+    #[inline]
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for VariableValue {
+    #[inline]
+    fn from(value: &str) -> Self {
+        Self::String(value.to_owned())
+    }
+}
+
+impl From<serde_json::Value> for VariableValue {
+    #[inline]
+    fn from(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Null => Self::Null,
+            serde_json::Value::Bool(b) => Self::Bool(b),
+            serde_json::Value::Number(n) => Self::Number(n),
+            serde_json::Value::String(s) => Self::String(s),
+            serde_json::Value::Array(a) => Self::Array(a.into_iter().map(Self::from).collect()),
+            serde_json::Value::Object(o) => {
+                Self::Object(o.into_iter().map(|(k, v)| (k, Self::from(v))).collect())
+            }
+        }
+    }
+}
+
+impl From<&VariableValue> for serde_json::Value {
+    #[inline]
+    fn from(value: &VariableValue) -> Self {
+        match value {
+            VariableValue::Null => Self::Null,
+            VariableValue::Bool(b) => Self::Bool(*b),
+            VariableValue::Number(n) => Self::Number(n.clone()),
+            VariableValue::String(s) => Self::String(s.clone()),
+            VariableValue::Array(a) => Self::Array(a.iter().map(Self::from).collect()),
+            VariableValue::Object(o) => {
+                Self::Object(o.iter().map(|(k, v)| (k.clone(), Self::from(v))).collect())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_from_json {
+    use super::VariableValue;
+
+    #[test]
+    fn it_should_convert_json_values() {
+        let json = serde_json::json!({
+            "name": "mads",
+            "age": 28,
+            "admin": true,
+            "ids": [1, 2],
+            "manager": null
+        });
+
+        let value = VariableValue::from(json.clone());
+
+        let VariableValue::Object(fields) = &value else {
+            panic!("expected an object");
+        };
+
+        assert_eq!(fields.get("name"), Some(&VariableValue::from("mads")));
+        assert_eq!(fields.get("admin"), Some(&VariableValue::Bool(true)));
+        assert_eq!(fields.get("manager"), Some(&VariableValue::Null));
+
+        assert_eq!(serde_json::Value::from(&value), json);
+    }
+}
+
+impl core::fmt::Display for VariableValue {
+    /// Strings are written as-is, while every other value is serialized as JSON.
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::String(s) => write!(f, "{s}"),
+            Self::Null => write!(f, "null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Array(_) | Self::Object(_) => write!(f, "{}", serde_json::Value::from(self)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_display {
+    use super::VariableValue;
+
+    #[test]
+    fn it_should_write_strings_as_is() {
+        assert_eq!(
+            "mads \"was\" here",
+            VariableValue::from("mads \"was\" here").to_string()
+        );
+    }
+
+    #[test]
+    fn it_should_write_scalars() {
+        assert_eq!("null", VariableValue::Null.to_string());
+        assert_eq!("true", VariableValue::Bool(true).to_string());
+        assert_eq!(
+            "1.5",
+            VariableValue::from(serde_json::json!(1.5)).to_string()
+        );
+    }
+
+    #[test]
+    fn it_should_serialize_objects_and_arrays() {
+        let value = VariableValue::from(serde_json::json!({
+            "name": "mads",
+            "ids": [1, 2]
+        }));
+
+        assert_eq!("{\"ids\":[1,2],\"name\":\"mads\"}", value.to_string());
+    }
+}
+
+/// A single step when accessing a nested value.
+#[derive(Debug, PartialEq, Eq)]
+enum PathSegment<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+/// Splits a variable path like `user.addresses[0].city` into segments.
+#[inline]
+fn parse_path(path: &str) -> Option<Vec<PathSegment<'_>>> {
+    let mut segments = Vec::new();
+
+    for part in path.split('.') {
+        let (key, mut indexes) = part
+            .find('[')
+            .map_or((part, ""), |index| part.split_at(index));
+
+        if key.is_empty() {
+            return None;
+        }
+
+        segments.push(PathSegment::Key(key));
+
+        while !indexes.is_empty() {
+            let (index, rest) = indexes.strip_prefix('[')?.split_once(']')?;
+
+            segments.push(PathSegment::Index(index.trim().parse().ok()?));
+
+            indexes = rest;
+        }
+    }
+
+    Some(segments)
+}
+
+#[cfg(test)]
+mod test_parse_path {
+    use super::{PathSegment, parse_path};
+
+    #[test]
+    fn it_should_parse_keys_and_indexes() {
+        assert_eq!(
+            Some(vec![
+                PathSegment::Key("user"),
+                PathSegment::Key("addresses"),
+                PathSegment::Index(0),
+                PathSegment::Index(12),
+                PathSegment::Key("city"),
+            ]),
+            parse_path("user.addresses[0][12].city")
+        );
+
+        assert_eq!(Some(vec![PathSegment::Key("name")]), parse_path("name"));
+    }
+
+    #[test]
+    fn it_should_reject_invalid_paths() {
+        for input in [
+            "", "user.", ".user", "ids[", "ids[a]", "ids[0", "ids]0[", "[0]",
+        ] {
+            assert_eq!(None, parse_path(input), "expected '{input}' to be invalid");
+        }
+    }
+}
+
+/// Parses the value of a variable declared in a file.
+///
+/// Values that are JSON objects or arrays keep their type, so nested fields can be accessed.
+/// Every other value is a string.
+#[inline]
+pub fn parse_declared_value(value: String) -> VariableValue {
+    if value.starts_with(['{', '['])
+        && let Ok(json) = serde_json::from_str::<serde_json::Value>(&value)
+    {
+        return VariableValue::from(json);
+    }
+
+    VariableValue::String(value)
+}
+
+#[cfg(test)]
+mod test_parse_declared_value {
+    use super::{VariableValue, parse_declared_value};
+
+    #[test]
+    fn it_should_parse_objects_and_arrays() {
+        assert_eq!(
+            VariableValue::from(serde_json::json!({ "id": 1, "tags": ["a"] })),
+            parse_declared_value(r#"{ "id": 1, "tags": ["a"] }"#.to_owned())
+        );
+
+        assert_eq!(
+            VariableValue::from(serde_json::json!([1, 2])),
+            parse_declared_value("[1, 2]".to_owned())
+        );
+    }
+
+    #[test]
+    fn it_should_keep_other_values_as_strings() {
+        for value in ["1", "true", "null", "\"mads\"", "{ not json }", "[1, 2"] {
+            assert_eq!(
+                VariableValue::from(value),
+                parse_declared_value(value.to_owned())
+            );
+        }
+    }
+}
+
+/// Returns the variable with the given name, supporting nested access such as
+/// `user.address.city` and `ids[0]`.
+///
+/// Variables whose name is an exact match are preferred over nested access.
+#[inline]
+pub fn get_variable<'a>(
+    vars: &'a std::collections::HashMap<String, VariableValue>,
+    name: &str,
+) -> Option<&'a VariableValue> {
+    if let Some(value) = vars.get(name) {
+        return Some(value);
+    }
+
+    let mut segments = parse_path(name)?.into_iter();
+
+    let Some(PathSegment::Key(root)) = segments.next() else {
+        return None;
+    };
+
+    segments.try_fold(vars.get(root)?, |value, segment| match (value, segment) {
+        (VariableValue::Object(fields), PathSegment::Key(key)) => fields.get(key),
+        (VariableValue::Array(items), PathSegment::Index(index)) => items.get(index),
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod test_get_variable {
+    use super::{VariableValue, get_variable};
+
+    #[inline]
+    fn vars() -> std::collections::HashMap<String, VariableValue> {
+        std::collections::HashMap::from([
+            (
+                "user".to_owned(),
+                VariableValue::from(serde_json::json!({
+                    "address": { "city": "Aarhus" },
+                    "ids": [1, 2, 3]
+                })),
+            ),
+            (
+                "ids".to_owned(),
+                VariableValue::from(serde_json::json!([10, 20])),
+            ),
+            ("user.name".to_owned(), VariableValue::from("mads")),
+        ])
+    }
+
+    #[test]
+    fn it_should_support_nested_access() {
+        let vars = vars();
+
+        assert_eq!(
+            Some(&VariableValue::from("Aarhus")),
+            get_variable(&vars, "user.address.city")
+        );
+
+        assert_eq!(
+            Some("20".to_owned()),
+            get_variable(&vars, "ids[1]").map(ToString::to_string)
+        );
+
+        assert_eq!(
+            Some("3".to_owned()),
+            get_variable(&vars, "user.ids[2]").map(ToString::to_string)
+        );
+    }
+
+    #[test]
+    fn it_should_prefer_exact_names() {
+        let vars = vars();
+
+        assert_eq!(
+            Some(&VariableValue::from("mads")),
+            get_variable(&vars, "user.name")
+        );
+    }
+
+    #[test]
+    fn it_should_return_none_for_missing_values() {
+        let vars = vars();
+
+        for name in [
+            "missing",
+            "user.missing",
+            "user.address.city.name",
+            "ids[2]",
+            "ids.first",
+            "user[0]",
+        ] {
+            assert_eq!(
+                None,
+                get_variable(&vars, name),
+                "expected '{name}' to be missing"
+            );
+        }
+    }
+}
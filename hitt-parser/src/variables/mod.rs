@@ -1,10 +1,23 @@
-use crate::error::RequestParseError;
+use crate::{error::RequestParseError, value::get_variable};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct VariableDeclaration {
+    pub name: String,
+    pub value: String,
+    /// Declared using `@secret name = value`.
+    pub is_secret: bool,
+    /// Declared using `@name ?= value`.
+    pub is_default: bool,
+    /// Declared using `@name = <<EOF`, in which case the value is the following lines up until
+    /// a line only containing the terminator.
+    pub heredoc_terminator: Option<String>,
+}
 
 #[inline]
 pub fn parse_variable_declaration(
     chars: &mut core::iter::Enumerate<core::str::Chars>,
-    vars: &std::collections::HashMap<String, String>,
-) -> Result<Option<(String, String)>, RequestParseError> {
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
+) -> Result<Option<VariableDeclaration>, RequestParseError> {
     let mut declaration = String::new();
 
     let mut value = String::new();
@@ -22,8 +35,8 @@ pub fn parse_variable_declaration(
             if ch == '{' {
                 // FIXME: remove cloning of enumerator
                 if let Some((var, jumps)) = parse_variable(&mut chars.clone()) {
-                    if let Some(variable_value) = vars.get(&var) {
-                        value.push_str(variable_value);
+                    if let Some(variable_value) = get_variable(vars, &var) {
+                        value.push_str(&variable_value.to_string());
 
                         for _ in 0..jumps {
                             chars.next();
@@ -34,6 +47,10 @@ pub fn parse_variable_declaration(
 
                     return Err(RequestParseError::VariableNotFound(var));
                 }
+            } else if ch == '\\' && parse_escaped_braces(chars) {
+                value.push_str("{{");
+
+                continue;
             }
 
             value.push(ch);
@@ -44,10 +61,29 @@ pub fn parse_variable_declaration(
         return Ok(None);
     }
 
-    Ok(Some((
-        declaration.trim().to_owned(),
-        value.trim().to_owned(),
-    )))
+    let (declaration, is_default) = match parse_default_name(declaration.trim()) {
+        Some(name) => (name, true),
+        None => (declaration.trim(), false),
+    };
+
+    let (name, is_secret) = match parse_secret_name(declaration) {
+        Some(name) => (name, true),
+        None => (declaration, false),
+    };
+
+    let heredoc_terminator = parse_heredoc_terminator(value.trim()).map(ToOwned::to_owned);
+
+    Ok(Some(VariableDeclaration {
+        name: name.to_owned(),
+        value: if heredoc_terminator.is_some() {
+            String::new()
+        } else {
+            value.trim().to_owned()
+        },
+        is_secret,
+        is_default,
+        heredoc_terminator,
+    }))
 }
 
 #[cfg(test)]
@@ -55,8 +91,9 @@ mod test_parse_variable_declarations {
     use super::parse_variable_declaration;
     use crate::{error::RequestParseError, to_enum_chars};
 
-    static EMPTY_VARS: std::sync::LazyLock<std::collections::HashMap<String, String>> =
-        std::sync::LazyLock::new(std::collections::HashMap::new);
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
 
     #[test]
     fn it_should_parse_variable_declarations() {
@@ -67,12 +104,14 @@ mod test_parse_variable_declarations {
             // NOTE: we do not start with a '@' here since it is expected to already be removed
             let input = format!("{input_declaration}={input_value}");
 
-            let (key, value) = parse_variable_declaration(&mut to_enum_chars(&input), &EMPTY_VARS)
+            let declaration = parse_variable_declaration(&mut to_enum_chars(&input), &EMPTY_VARS)
                 .expect("it to not return an error")
                 .expect("it to return a variable declaration");
 
-            assert_eq!(input_declaration, key);
-            assert_eq!(input_value, value);
+            assert_eq!(input_declaration, declaration.name);
+            assert_eq!(input_value, declaration.value);
+            assert!(!declaration.is_secret);
+            assert!(!declaration.is_default);
         }
     }
 
@@ -91,12 +130,14 @@ mod test_parse_variable_declarations {
                 "{input_declaration}{extra_spaces}={extra_spaces}{input_value}{extra_spaces}"
             );
 
-            let (key, value) = parse_variable_declaration(&mut to_enum_chars(&input), &EMPTY_VARS)
+            let declaration = parse_variable_declaration(&mut to_enum_chars(&input), &EMPTY_VARS)
                 .expect("it to not return an error")
                 .expect("it to return a variable declaration");
 
-            assert_eq!(input_declaration, key);
-            assert_eq!(input_value, value);
+            assert_eq!(input_declaration, declaration.name);
+            assert_eq!(input_value, declaration.value);
+            assert!(!declaration.is_secret);
+            assert!(!declaration.is_default);
         }
     }
 
@@ -110,22 +151,34 @@ mod test_parse_variable_declarations {
         assert_eq!(None, result);
     }
 
+    #[test]
+    fn it_should_parse_heredoc_declarations() {
+        let declaration =
+            parse_variable_declaration(&mut to_enum_chars("payload = <<EOF"), &EMPTY_VARS)
+                .expect("it to not return an error")
+                .expect("it to return a variable declaration");
+
+        assert_eq!("payload", declaration.name);
+        assert_eq!("", declaration.value);
+        assert_eq!(Some("EOF"), declaration.heredoc_terminator.as_deref());
+    }
+
     #[test]
     fn it_should_support_variables() {
         let input = "host={{ hostname }}:{{ port }}";
 
         {
             let vars = std::collections::HashMap::from([
-                ("hostname".to_owned(), "localhost".to_owned()),
-                ("port".to_owned(), "5000".to_owned()),
+                ("hostname".to_owned(), "localhost".into()),
+                ("port".to_owned(), "5000".into()),
             ]);
 
-            let (name, value) = parse_variable_declaration(&mut to_enum_chars(input), &vars)
+            let declaration = parse_variable_declaration(&mut to_enum_chars(input), &vars)
                 .expect("it to not return an error")
                 .expect("it to be some");
 
-            assert_eq!(name, "host");
-            assert_eq!(value, "localhost:5000");
+            assert_eq!(declaration.name, "host");
+            assert_eq!(declaration.value, "localhost:5000");
         };
 
         {
@@ -139,7 +192,7 @@ mod test_parse_variable_declarations {
 
         {
             let vars =
-                std::collections::HashMap::from([("hostname".to_owned(), "localhost".to_owned())]);
+                std::collections::HashMap::from([("hostname".to_owned(), "localhost".into())]);
 
             let result = parse_variable_declaration(&mut to_enum_chars(input), &vars)
                 .expect_err("it should return RequestParseError::VariableNotFound");
@@ -149,6 +202,144 @@ mod test_parse_variable_declarations {
     }
 }
 
+/// Returns the terminator if the value starts a heredoc, as in `@name = <<EOF`.
+#[inline]
+pub fn parse_heredoc_terminator(value: &str) -> Option<&str> {
+    value.strip_prefix("<<").filter(|terminator| {
+        !terminator.is_empty()
+            && terminator
+                .chars()
+                .all(|ch| ch.is_alphanumeric() || ch == '_' || ch == '-')
+    })
+}
+
+#[cfg(test)]
+mod test_parse_heredoc_terminator {
+    use super::parse_heredoc_terminator;
+
+    #[test]
+    fn it_should_return_terminator() {
+        assert_eq!(Some("EOF"), parse_heredoc_terminator("<<EOF"));
+        assert_eq!(
+            Some("END_OF-JSON"),
+            parse_heredoc_terminator("<<END_OF-JSON")
+        );
+    }
+
+    #[test]
+    fn it_should_ignore_other_values() {
+        for input in [
+            "",
+            "EOF",
+            "<<",
+            "<< EOF",
+            "<<EOF mads",
+            "<<{{name}}",
+            "<EOF",
+        ] {
+            assert_eq!(None, parse_heredoc_terminator(input));
+        }
+    }
+}
+
+/// Returns the variable name if the declaration is suffixed with `?`, as in `@name ?= value`.
+#[inline]
+pub fn parse_default_name(declaration: &str) -> Option<&str> {
+    declaration
+        .strip_suffix('?')
+        .map(str::trim_end)
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod test_parse_default_name {
+    use super::parse_default_name;
+
+    #[test]
+    fn it_should_strip_question_mark() {
+        assert_eq!(Some("host"), parse_default_name("host?"));
+        assert_eq!(Some("host"), parse_default_name("host   ?"));
+        assert_eq!(Some("secret host"), parse_default_name("secret host ?"));
+    }
+
+    #[test]
+    fn it_should_ignore_regular_declarations() {
+        assert_eq!(None, parse_default_name("host"));
+        assert_eq!(None, parse_default_name("?"));
+        assert_eq!(None, parse_default_name("?host"));
+    }
+}
+
+/// Returns the variable name if the declaration is prefixed with the `secret` keyword.
+#[inline]
+pub fn parse_secret_name(declaration: &str) -> Option<&str> {
+    declaration
+        .strip_prefix("secret")
+        .filter(|name| name.starts_with(char::is_whitespace))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod test_parse_secret_name {
+    use super::parse_secret_name;
+
+    #[test]
+    fn it_should_strip_secret_keyword() {
+        assert_eq!(Some("token"), parse_secret_name("secret token"));
+        assert_eq!(Some("token"), parse_secret_name("secret    token"));
+    }
+
+    #[test]
+    fn it_should_ignore_regular_declarations() {
+        assert_eq!(None, parse_secret_name("token"));
+        assert_eq!(None, parse_secret_name("secret"));
+        assert_eq!(None, parse_secret_name("secret   "));
+        assert_eq!(None, parse_secret_name("secret_token"));
+    }
+}
+
+/// Returns the name and heredoc terminator of a `@name = value` line, without resolving the value.
+#[inline]
+pub fn parse_declared_name(line: &str) -> Option<(&str, Option<&str>)> {
+    let (declaration, value) = line.strip_prefix('@')?.split_once('=')?;
+
+    let declaration = declaration.trim();
+    let declaration = parse_default_name(declaration).unwrap_or(declaration);
+
+    let name = parse_secret_name(declaration).unwrap_or(declaration);
+
+    if name.is_empty() {
+        return None;
+    }
+
+    Some((name, parse_heredoc_terminator(value.trim())))
+}
+
+#[cfg(test)]
+mod test_parse_declared_name {
+    use super::parse_declared_name;
+
+    #[test]
+    fn it_should_return_declared_names() {
+        for (input, expected) in [
+            ("@otp = {{missing}}", ("otp", None)),
+            ("@secret token = abc", ("token", None)),
+            ("@host ?= localhost", ("host", None)),
+            ("@body = <<EOF", ("body", Some("EOF"))),
+        ] {
+            assert_eq!(Some(expected), parse_declared_name(input));
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_other_lines() {
+        for input in ["@otp", "otp = 1", "@ = 1", "GET https://mhouge.dk/"] {
+            assert_eq!(None, parse_declared_name(input));
+        }
+    }
+}
+
 #[inline]
 pub fn parse_variable(
     chars: &mut core::iter::Enumerate<core::str::Chars>,
@@ -293,3 +484,135 @@ mod test_parse_variable {
         assert_eq!(None, parse_variable(&mut to_enum_chars(input)));
     }
 }
+
+/// Consumes the `{{` following a `\`, which is used to write a literal `{{` instead of a variable.
+///
+/// Expects the `\` to already have been consumed.
+#[inline]
+pub fn parse_escaped_braces(chars: &mut core::iter::Enumerate<core::str::Chars>) -> bool {
+    let mut lookahead = chars.clone();
+
+    if matches!(lookahead.next(), Some((_, '{'))) && matches!(lookahead.next(), Some((_, '{'))) {
+        chars.next();
+        chars.next();
+
+        return true;
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod test_parse_escaped_braces {
+    use super::parse_escaped_braces;
+    use crate::to_enum_chars;
+
+    #[test]
+    fn it_should_consume_escaped_braces() {
+        let mut chars = to_enum_chars("{{name}}");
+
+        assert!(parse_escaped_braces(&mut chars));
+
+        assert_eq!("name}}", chars.map(|(_, ch)| ch).collect::<String>());
+    }
+
+    #[test]
+    fn it_should_ignore_other_input() {
+        for input in ["", "{", "{name}}", "name", "\\{{"] {
+            let mut chars = to_enum_chars(input);
+
+            assert!(!parse_escaped_braces(&mut chars));
+
+            assert_eq!(input, chars.map(|(_, ch)| ch).collect::<String>());
+        }
+    }
+}
+
+/// Replaces every `{{name}}` in the input with the value of the variable.
+#[inline]
+pub fn interpolate_variables(
+    input: &str,
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
+) -> Result<String, RequestParseError> {
+    interpolate_variables_with(input, vars, String::push_str)
+}
+
+/// Replaces every `{{name}}` in the input, using `push_value` to add the value of the variable
+/// to the output.
+#[inline]
+pub fn interpolate_variables_with(
+    input: &str,
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
+    mut push_value: impl FnMut(&mut String, &str),
+) -> Result<String, RequestParseError> {
+    let mut output = String::new();
+    let mut chars = input.chars().enumerate();
+
+    while let Some((_, ch)) = chars.next() {
+        if ch == '{' {
+            // FIXME: remove cloning of enumerator
+            if let Some((var, jumps)) = parse_variable(&mut chars.clone()) {
+                if let Some(variable_value) = get_variable(vars, &var) {
+                    push_value(&mut output, &variable_value.to_string());
+
+                    for _ in 0..jumps {
+                        chars.next();
+                    }
+
+                    continue;
+                }
+
+                return Err(RequestParseError::VariableNotFound(var));
+            }
+        } else if ch == '\\' && parse_escaped_braces(&mut chars) {
+            output.push_str("{{");
+
+            continue;
+        }
+
+        output.push(ch);
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod test_interpolate_variables {
+    use super::interpolate_variables;
+    use crate::error::RequestParseError;
+
+    #[test]
+    fn it_should_replace_variables() {
+        let vars = std::collections::HashMap::from([("name".to_owned(), "mads".into())]);
+
+        let output = interpolate_variables("{\n  \"name\": \"{{ name }}\"\n} \\{{name}}", &vars)
+            .expect("it to interpolate");
+
+        assert_eq!("{\n  \"name\": \"mads\"\n} {{name}}", output);
+    }
+
+    #[test]
+    fn it_should_raise_if_variable_isnt_found() {
+        let err = interpolate_variables("{{name}}", &std::collections::HashMap::new())
+            .expect_err("it to return an error");
+
+        assert!(matches!(err, RequestParseError::VariableNotFound(var) if var == "name"));
+    }
+}
+
+#[cfg(test)]
+mod test_interpolate_variables_with {
+    use super::interpolate_variables_with;
+
+    #[test]
+    fn it_should_push_values_using_the_callback() {
+        let vars = std::collections::HashMap::from([("name".to_owned(), "mads".into())]);
+
+        let output = interpolate_variables_with("{{name}}-{{ name }}", &vars, |output, value| {
+            output.push_str(&value.to_uppercase());
+        })
+        .expect("it to interpolate");
+
+        assert_eq!("MADS-MADS", output);
+    }
+}
@@ -1,10 +1,14 @@
-use crate::variables::parse_variable;
+use crate::{error::RequestParseError, value::get_variable, variables::parse_variable};
 
+/// Reads the version until the next whitespace.
+///
+/// Missing variables are only raised as errors if `strict` is enabled.
 #[inline]
-pub fn parse_http_version(
+fn parse_version_string(
     chars: &mut core::iter::Enumerate<core::str::Chars>,
-    vars: &std::collections::HashMap<String, String>,
-) -> Option<http::version::Version> {
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
+    strict: bool,
+) -> Result<String, RequestParseError> {
     let mut version = String::new();
 
     while let Some((_, ch)) = chars.next() {
@@ -17,9 +21,9 @@ pub fn parse_http_version(
         } else if ch == '{' {
             // FIXME: remove clone
             if let Some((var, jumps)) = parse_variable(&mut chars.clone())
-                && let Some(var_value) = vars.get(&var)
+                && let Some(var_value) = get_variable(vars, &var)
             {
-                version.push_str(var_value);
+                version.push_str(&var_value.to_string());
 
                 for _ in 0..jumps {
                     chars.next();
@@ -28,16 +32,19 @@ pub fn parse_http_version(
                 continue;
             }
 
-            // NOTE: should variable not existing raise an error?
+            if strict && let Some((var, _)) = parse_variable(&mut chars.clone()) {
+                return Err(RequestParseError::VariableNotFound(var));
+            }
         }
 
         version.push(ch);
     }
 
-    if version.is_empty() {
-        return None;
-    }
+    Ok(version)
+}
 
+#[inline]
+fn to_http_version(version: &str) -> Option<http::version::Version> {
     match version.to_lowercase().trim() {
         "http/0.9" => Some(http::Version::HTTP_09),
         "http/1.0" | "http/1" => Some(http::Version::HTTP_10),
@@ -48,12 +55,27 @@ pub fn parse_http_version(
     }
 }
 
+#[inline]
+pub fn parse_http_version(
+    chars: &mut core::iter::Enumerate<core::str::Chars>,
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
+) -> Option<http::version::Version> {
+    let version = parse_version_string(chars, vars, false).ok()?;
+
+    if version.is_empty() {
+        return None;
+    }
+
+    to_http_version(&version)
+}
+
 #[cfg(test)]
 mod test_parse_http_version {
     use crate::{to_enum_chars, version::parse_http_version};
 
-    static EMPTY_VARS: std::sync::LazyLock<std::collections::HashMap<String, String>> =
-        std::sync::LazyLock::new(std::collections::HashMap::new);
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
 
     const HTTP_0_9_INPUTS: [&str; 5] = [
         "http/0.9",
@@ -196,7 +218,7 @@ mod test_parse_http_version {
         let mut vars = std::collections::HashMap::new();
 
         for version in HTTP_0_9_INPUTS {
-            vars.insert("version".to_owned(), version.to_owned());
+            vars.insert("version".to_owned(), version.to_owned().into());
 
             assert_eq!(
                 Some(http::Version::HTTP_09),
@@ -210,7 +232,7 @@ mod test_parse_http_version {
         }
 
         for version in HTTP_1_0_INPUTS {
-            vars.insert("version".to_owned(), version.to_owned());
+            vars.insert("version".to_owned(), version.to_owned().into());
 
             assert_eq!(
                 Some(http::Version::HTTP_10),
@@ -224,7 +246,7 @@ mod test_parse_http_version {
         }
 
         for version in HTTP_1_1_INPUTS {
-            vars.insert("version".to_owned(), version.to_owned());
+            vars.insert("version".to_owned(), version.to_owned().into());
 
             assert_eq!(
                 Some(http::Version::HTTP_11),
@@ -238,7 +260,7 @@ mod test_parse_http_version {
         }
 
         for version in HTTP_2_0_INPUTS {
-            vars.insert("version".to_owned(), version.to_owned());
+            vars.insert("version".to_owned(), version.to_owned().into());
 
             assert_eq!(
                 Some(http::Version::HTTP_2),
@@ -252,7 +274,7 @@ mod test_parse_http_version {
         }
 
         for version in HTTP_3_0_INPUTS {
-            vars.insert("version".to_owned(), version.to_owned());
+            vars.insert("version".to_owned(), version.to_owned().into());
 
             assert_eq!(
                 Some(http::Version::HTTP_3),
@@ -304,3 +326,67 @@ mod test_parse_http_version {
         );
     }
 }
+
+/// Same as [`parse_http_version`], but unknown versions and missing variables are raised as errors.
+#[inline]
+pub fn parse_http_version_strict(
+    chars: &mut core::iter::Enumerate<core::str::Chars>,
+    vars: &std::collections::HashMap<String, crate::value::VariableValue>,
+) -> Result<Option<http::version::Version>, RequestParseError> {
+    let version = parse_version_string(chars, vars, true)?;
+
+    if version.is_empty() {
+        return Ok(None);
+    }
+
+    to_http_version(&version)
+        .map(Some)
+        .ok_or(RequestParseError::InvalidHttpVersion(version))
+}
+
+#[cfg(test)]
+mod test_parse_http_version_strict {
+    use crate::{error::RequestParseError, to_enum_chars, version::parse_http_version_strict};
+
+    static EMPTY_VARS: std::sync::LazyLock<
+        std::collections::HashMap<String, crate::value::VariableValue>,
+    > = std::sync::LazyLock::new(std::collections::HashMap::new);
+
+    #[test]
+    fn it_should_parse_known_versions() {
+        let version = parse_http_version_strict(&mut to_enum_chars(" HTTP/1.1"), &EMPTY_VARS)
+            .expect("it to return a version");
+
+        assert_eq!(version, Some(http::Version::HTTP_11));
+    }
+
+    #[test]
+    fn it_should_allow_missing_version() {
+        let version = parse_http_version_strict(&mut to_enum_chars("   "), &EMPTY_VARS)
+            .expect("it not to return an error");
+
+        assert_eq!(version, None);
+    }
+
+    #[test]
+    fn it_should_reject_unknown_versions() {
+        for input in ["HTTP/1.2", "HTTP/4", "http1.1", "mads"] {
+            let error = parse_http_version_strict(&mut to_enum_chars(input), &EMPTY_VARS)
+                .expect_err("it to return an error");
+
+            assert_eq!(format!("invalid HTTP version '{input}'"), error.to_string());
+
+            assert!(
+                matches!(error, RequestParseError::InvalidHttpVersion(version) if version == input)
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_reject_missing_variables() {
+        let error = parse_http_version_strict(&mut to_enum_chars("{{version}}"), &EMPTY_VARS)
+            .expect_err("it to return an error");
+
+        assert!(matches!(error, RequestParseError::VariableNotFound(var) if var == "version"));
+    }
+}
@@ -0,0 +1,16 @@
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum RequestParseWarning {
+    VariableShadowed(String),
+}
+
+impl core::fmt::Display for RequestParseWarning {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::VariableShadowed(name) => write!(
+                f,
+                "request variable '{name}' shadows a variable declared at the top of the file"
+            ),
+        }
+    }
+}
@@ -0,0 +1,343 @@
+use cookie_store::{CookieDomain, CookieExpiration, RawCookie};
+
+const NETSCAPE_HEADER: &str =
+    "# Netscape HTTP Cookie File\n# This file was generated by hitt. Edit at your own risk.\n\n";
+
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+#[derive(Debug)]
+pub enum CookieJarError {
+    /// A line that is not a cookie in the Netscape format, starting at line 1.
+    InvalidLine(usize),
+}
+
+impl core::error::Error for CookieJarError {}
+
+impl core::fmt::Display for CookieJarError {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidLine(line) => {
+                write!(f, "line {line} is not a cookie in the Netscape format")
+            }
+        }
+    }
+}
+
+/// Cookies shared by every client of a run.
+///
+/// The jar can be read from and written to the Netscape format used by curl and browsers.
+#[derive(Debug, Default)]
+pub struct CookieJar(std::sync::RwLock<cookie_store::CookieStore>);
+
+impl reqwest::cookie::CookieStore for CookieJar {
+    #[inline]
+    fn set_cookies(
+        &self,
+        cookie_headers: &mut dyn Iterator<Item = &reqwest::header::HeaderValue>,
+        url: &reqwest::Url,
+    ) {
+        let cookies = cookie_headers.filter_map(|value| {
+            RawCookie::parse(value.to_str().ok()?)
+                .ok()
+                .map(RawCookie::into_owned)
+        });
+
+        self.0
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .store_response_cookies(cookies, url);
+    }
+
+    #[inline]
+    fn cookies(&self, url: &reqwest::Url) -> Option<reqwest::header::HeaderValue> {
+        let value = self
+            .0
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .get_request_values(url)
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        if value.is_empty() {
+            return None;
+        }
+
+        reqwest::header::HeaderValue::from_str(&value).ok()
+    }
+}
+
+/// Parses a line of a Netscape cookie file into the cookie and the url it was set by.
+#[inline]
+fn parse_netscape_line(line: &str) -> Option<(RawCookie<'static>, reqwest::Url)> {
+    let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+        Some(line) => (line, true),
+        None => (line, false),
+    };
+
+    let mut fields = line.split('\t');
+
+    let domain = fields.next()?;
+    let include_subdomains = fields.next()?;
+    let path = fields.next()?;
+    let secure = fields.next()?;
+    let expires = fields.next()?.parse::<i64>().ok()?;
+    let name = fields.next()?;
+    let value = fields.next()?;
+
+    if fields.next().is_some() || name.is_empty() {
+        return None;
+    }
+
+    let host = domain.trim_start_matches('.');
+
+    let url = reqwest::Url::parse(&format!("https://{host}{path}")).ok()?;
+
+    let mut cookie = RawCookie::build((name.to_owned(), value.to_owned()))
+        .path(path.to_owned())
+        .secure(secure.eq_ignore_ascii_case("TRUE"))
+        .http_only(http_only);
+
+    if include_subdomains.eq_ignore_ascii_case("TRUE") {
+        cookie = cookie.domain(host.to_owned());
+    }
+
+    // cookies without an expiry date end with the session
+    if expires > 0 {
+        cookie = cookie.expires(time::OffsetDateTime::from_unix_timestamp(expires).ok()?);
+    }
+
+    Some((cookie.build(), url))
+}
+
+#[cfg(test)]
+mod test_parse_netscape_line {
+    use super::parse_netscape_line;
+
+    #[test]
+    fn it_should_parse_cookies() {
+        let (cookie, url) =
+            parse_netscape_line("#HttpOnly_.mhouge.dk\tTRUE\t/api\tTRUE\t4102444800\tsession\tabc")
+                .expect("it to parse the line");
+
+        assert_eq!("https://mhouge.dk/api", url.as_str());
+        assert_eq!("session", cookie.name());
+        assert_eq!("abc", cookie.value());
+        assert_eq!(Some("mhouge.dk"), cookie.domain());
+        assert_eq!(Some("/api"), cookie.path());
+        assert_eq!(Some(true), cookie.secure());
+        assert_eq!(Some(true), cookie.http_only());
+        assert_eq!(
+            Some(4_102_444_800),
+            cookie
+                .expires_datetime()
+                .map(time::OffsetDateTime::unix_timestamp)
+        );
+    }
+
+    #[test]
+    fn it_should_parse_session_cookies() {
+        let (cookie, _url) = parse_netscape_line("localhost\tFALSE\t/\tFALSE\t0\ttheme\t")
+            .expect("it to parse the line");
+
+        assert_eq!(None, cookie.domain());
+        assert_eq!(Some(false), cookie.http_only());
+        assert_eq!(None, cookie.expires());
+    }
+
+    #[test]
+    fn it_should_reject_invalid_lines() {
+        for line in [
+            "mhouge.dk",
+            "mhouge.dk\tFALSE\t/\tFALSE\tsoon\tname\tvalue",
+            "mhouge.dk\tFALSE\t/\tFALSE\t0\t\tvalue",
+            "mhouge.dk\tFALSE\t/\tFALSE\t0\tname\tvalue\textra",
+        ] {
+            assert!(parse_netscape_line(line).is_none(), "{line}");
+        }
+    }
+}
+
+impl CookieJar {
+    /// Reads the cookies of a Netscape cookie file, skipping cookies that have expired.
+    #[inline]
+    pub fn from_netscape(input: &str) -> Result<Self, CookieJarError> {
+        let mut store = cookie_store::CookieStore::default();
+
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+
+            if line.trim().is_empty()
+                || (line.starts_with('#') && !line.starts_with(HTTP_ONLY_PREFIX))
+            {
+                continue;
+            }
+
+            let (cookie, url) =
+                parse_netscape_line(line).ok_or(CookieJarError::InvalidLine(index + 1))?;
+
+            // expired cookies are rejected by the store, like they would be by a server
+            let _ = store.insert_raw(&cookie, &url);
+        }
+
+        Ok(Self(std::sync::RwLock::new(store)))
+    }
+
+    /// Writes every cookie that has not expired in the Netscape format, including session
+    /// cookies, which get an expiry of `0`.
+    #[inline]
+    pub fn to_netscape(&self) -> String {
+        let store = self
+            .0
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+
+        let mut lines = store
+            .iter_unexpired()
+            .filter_map(|cookie| {
+                let (domain, include_subdomains) = match &cookie.domain {
+                    CookieDomain::HostOnly(domain) => (domain.clone(), "FALSE"),
+                    CookieDomain::Suffix(domain) => (format!(".{domain}"), "TRUE"),
+                    CookieDomain::NotPresent | CookieDomain::Empty => return None,
+                };
+
+                let expires = match cookie.expires {
+                    CookieExpiration::AtUtc(expires) => expires.unix_timestamp(),
+                    CookieExpiration::SessionEnd => 0,
+                };
+
+                Some(format!(
+                    "{}{domain}\t{include_subdomains}\t{}\t{}\t{expires}\t{}\t{}\n",
+                    if cookie.http_only() == Some(true) {
+                        HTTP_ONLY_PREFIX
+                    } else {
+                        ""
+                    },
+                    cookie.path.as_ref(),
+                    if cookie.secure() == Some(true) {
+                        "TRUE"
+                    } else {
+                        "FALSE"
+                    },
+                    cookie.name(),
+                    cookie.value()
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        lines.sort_unstable();
+
+        let mut output = NETSCAPE_HEADER.to_owned();
+
+        output.extend(lines);
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod test_cookie_jar {
+    use reqwest::cookie::CookieStore;
+
+    use super::{CookieJar, CookieJarError};
+
+    #[test]
+    fn it_should_send_cookies_set_by_responses() {
+        let jar = CookieJar::default();
+
+        let url = reqwest::Url::parse("http://localhost:8080/login").expect("it to be a valid url");
+
+        jar.set_cookies(
+            &mut [
+                http::HeaderValue::from_static("session=abc; Path=/; HttpOnly"),
+                http::HeaderValue::from_static("theme=dark; Path=/settings"),
+            ]
+            .iter(),
+            &url,
+        );
+
+        assert_eq!(
+            Some(http::HeaderValue::from_static("session=abc")),
+            jar.cookies(
+                &reqwest::Url::parse("http://localhost:8080/users").expect("it to be a valid url")
+            )
+        );
+
+        assert_eq!(
+            None,
+            jar.cookies(&reqwest::Url::parse("http://mhouge.dk/").expect("it to be a valid url"))
+        );
+    }
+
+    #[test]
+    fn it_should_write_netscape_format() {
+        let jar = CookieJar::default();
+
+        jar.set_cookies(
+            &mut [
+                http::HeaderValue::from_static("session=abc; Path=/; HttpOnly"),
+                http::HeaderValue::from_static(
+                    "theme=dark; Domain=mhouge.dk; Path=/; Secure; Expires=Fri, 01 Jan 2100 00:00:00 GMT",
+                ),
+            ]
+            .iter(),
+            &reqwest::Url::parse("https://api.mhouge.dk/login").expect("it to be a valid url"),
+        );
+
+        assert_eq!(
+            "# Netscape HTTP Cookie File\n# This file was generated by hitt. Edit at your own risk.\n\n\
+             #HttpOnly_api.mhouge.dk\tFALSE\t/\tFALSE\t0\tsession\tabc\n\
+             .mhouge.dk\tTRUE\t/\tTRUE\t4102444800\ttheme\tdark\n",
+            jar.to_netscape()
+        );
+    }
+
+    #[test]
+    fn it_should_read_netscape_format() {
+        let input = "# Netscape HTTP Cookie File\n\
+                     \n\
+                     #HttpOnly_api.mhouge.dk\tFALSE\t/\tFALSE\t0\tsession\tabc\n\
+                     .mhouge.dk\tTRUE\t/\tTRUE\t4102444800\ttheme\tdark\n\
+                     mhouge.dk\tFALSE\t/\tFALSE\t1\texpired\tyes\n";
+
+        let jar = CookieJar::from_netscape(input).expect("it to read the cookies");
+
+        let header = jar
+            .cookies(&reqwest::Url::parse("https://api.mhouge.dk/").expect("it to be a valid url"))
+            .expect("it to send cookies");
+
+        let mut cookies = header
+            .to_str()
+            .expect("it to be a valid header")
+            .split("; ")
+            .collect::<Vec<_>>();
+
+        cookies.sort_unstable();
+
+        assert_eq!(vec!["session=abc", "theme=dark"], cookies);
+
+        assert_eq!(
+            None,
+            jar.cookies(&reqwest::Url::parse("http://mhouge.dk/").expect("it to be a valid url"))
+        );
+
+        let output = jar.to_netscape();
+
+        assert!(output.contains("#HttpOnly_api.mhouge.dk\tFALSE\t/\tFALSE\t0\tsession\tabc\n"));
+        assert!(output.contains(".mhouge.dk\tTRUE\t/\tTRUE\t4102444800\ttheme\tdark\n"));
+        assert!(!output.contains("expired"));
+    }
+
+    #[test]
+    fn it_should_reject_invalid_lines() {
+        let error = CookieJar::from_netscape("# Netscape HTTP Cookie File\n\nnot a cookie\n")
+            .expect_err("it to reject the line");
+
+        assert_eq!(
+            "line 3 is not a cookie in the Netscape format",
+            error.to_string()
+        );
+        assert!(matches!(error, CookieJarError::InvalidLine(3)));
+    }
+}
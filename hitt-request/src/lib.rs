@@ -1,61 +1,509 @@
 use hitt_parser::HittRequest;
+use redirect::{RedirectHop, redirect_location, redirect_method, strip_redirect_headers};
+use retry::{RetryAttempt, RetryPolicy, backoff_delay, retry_after, retry_reason};
+use timing::{ConnectionEvents, TimingRecorder, Timings};
+
+pub mod cookie;
+pub mod redirect;
+pub mod retry;
+pub mod timing;
+pub mod tls;
 
 pub struct HittResponse {
     pub url: String,
     pub method: String,
     pub status_code: reqwest::StatusCode,
     pub headers: reqwest::header::HeaderMap,
+    /// Response body decoded as UTF-8, with invalid sequences replaced.
     pub body: String,
+    /// Raw bytes of the response body.
+    pub raw_body: Vec<u8>,
     pub http_version: http::version::Version,
+    /// Time until the headers were received, including the redirects that were followed.
     pub duration: core::time::Duration,
+    /// Breakdown of `duration`, connection phases are only set for new connections.
+    pub timings: Timings,
+    /// Redirect responses followed before this response, in order.
+    pub redirects: Vec<RedirectHop>,
+    /// Whether the body was longer than the maximum body size, and only its start was read.
+    pub truncated: bool,
+}
+
+/// A response whose headers have been received, but not its body.
+pub struct StreamingResponse {
+    /// The response without its body, with timings up until the headers were received.
+    pub response: HittResponse,
+    body: reqwest::Response,
+    /// Time the final request was sent, after following any redirects.
+    start: std::time::Instant,
+    headers_received: std::time::Instant,
+    redirects_duration: Option<core::time::Duration>,
+    connection_events: Option<ConnectionEvents>,
+}
+
+impl StreamingResponse {
+    /// Reads the body, passing every chunk to `on_chunk` as it arrives.
+    ///
+    /// Reading stops after `max_body_size` bytes, which marks the response as truncated.
+    #[inline]
+    pub async fn read_body(
+        self,
+        max_body_size: Option<usize>,
+        mut on_chunk: impl FnMut(&[u8]),
+    ) -> Result<HittResponse, reqwest::Error> {
+        let Self {
+            mut response,
+            mut body,
+            start,
+            headers_received,
+            redirects_duration,
+            connection_events,
+        } = self;
+
+        let mut raw_body = Vec::new();
+
+        while let Some(chunk) = body.chunk().await? {
+            let remaining =
+                max_body_size.map_or(usize::MAX, |max| max.saturating_sub(raw_body.len()));
+
+            let kept = &chunk[..chunk.len().min(remaining)];
+
+            if !kept.is_empty() {
+                on_chunk(kept);
+
+                raw_body.extend_from_slice(kept);
+            }
+
+            if kept.len() < chunk.len() {
+                response.truncated = true;
+                break;
+            }
+        }
+
+        let body_received = std::time::Instant::now();
+
+        response.timings = Timings {
+            redirects: redirects_duration,
+            ..connection_events.map_or_else(
+                || Timings {
+                    ttfb: headers_received.duration_since(start),
+                    download: body_received.duration_since(headers_received),
+                    ..Timings::default()
+                },
+                |events| events.timings(start, headers_received, body_received),
+            )
+        };
+
+        response.body = String::from_utf8_lossy(&raw_body).into_owned();
+        response.raw_body = raw_body;
+
+        Ok(response)
+    }
 }
 
+/// Executes the request, retrying it as allowed by the policy.
+///
+/// `on_retry` is called before waiting for the next attempt.
+/// Returns the response of the last attempt, the time it was sent and the events of the
+/// connection it opened, if it was measured.
 #[inline]
-pub async fn send_request(
+async fn execute_with_retries(
+    http_client: &reqwest::Client,
+    mut request: reqwest::Request,
+    timings: Option<&TimingRecorder>,
+    retry: &RetryPolicy,
+    on_retry: &mut impl FnMut(&RetryAttempt),
+) -> Result<
+    (
+        std::time::Instant,
+        Option<ConnectionEvents>,
+        reqwest::Response,
+    ),
+    reqwest::Error,
+> {
+    let mut attempt = 1;
+
+    loop {
+        // requests with a streaming body can not be cloned, so they are only sent once
+        let next_request = if attempt <= retry.retries {
+            request.try_clone()
+        } else {
+            None
+        };
+
+        let start = std::time::Instant::now();
+
+        let (result, events) = match timings {
+            Some(recorder) => {
+                let (result, events) = recorder.measure(http_client.execute(request)).await;
+
+                (result, Some(events))
+            }
+            None => (http_client.execute(request).await, None),
+        };
+
+        let Some(next_request) = next_request else {
+            return result.map(|response| (start, events, response));
+        };
+
+        let Some(reason) = retry_reason(retry, &result) else {
+            return result.map(|response| (start, events, response));
+        };
+
+        let delay = result
+            .as_ref()
+            .ok()
+            .and_then(|response| retry_after(response.headers(), std::time::SystemTime::now()))
+            .unwrap_or_else(|| backoff_delay(retry, attempt, fastrand::f64()))
+            .min(retry.max_delay);
+
+        on_retry(&RetryAttempt {
+            attempt,
+            method: next_request.method().to_string(),
+            url: next_request.url().to_string(),
+            reason,
+            delay,
+        });
+
+        // the connection of the response is released before waiting
+        drop(result);
+
+        tokio::time::sleep(delay).await;
+
+        request = next_request;
+        attempt += 1;
+    }
+}
+
+/// Sends the request, and returns the response as soon as its headers are received.
+///
+/// Redirects and retries are handled like [`send_request`].
+#[inline]
+pub async fn send_request_streaming(
     http_client: &reqwest::Client,
     input: &HittRequest,
     timeout: Option<&core::time::Duration>,
-) -> Result<HittResponse, reqwest::Error> {
-    let url = input.uri.to_string();
+    timings: Option<&TimingRecorder>,
+    max_redirects: usize,
+    retry: &RetryPolicy,
+    mut on_retry: impl FnMut(&RetryAttempt),
+) -> Result<StreamingResponse, reqwest::Error> {
+    let max_redirects = input.max_redirects.unwrap_or(max_redirects);
+
+    let mut method = input.method.clone();
+    let mut url = input.uri.to_string();
+    let mut headers = input.headers.clone();
+    let mut body = input.body.clone();
+
+    let mut redirects = Vec::new();
+
+    // start of the first request, so the duration includes the redirects
+    let mut request_start = None;
+
+    loop {
+        let mut partial_req = http_client.request(method.clone(), &url);
+
+        if let Some(http_version) = input.http_version {
+            partial_req = partial_req.version(http_version);
+        }
+
+        if !headers.is_empty() {
+            partial_req = partial_req.headers(headers.clone());
+        }
+
+        if let Some(body) = body.clone() {
+            partial_req = partial_req.body(body);
+        }
+
+        if let Some(dur) = timeout {
+            partial_req = partial_req.timeout(dur.to_owned());
+        }
+
+        let request = partial_req.build()?;
+
+        let (start, connection_events, response) =
+            execute_with_retries(http_client, request, timings, retry, &mut on_retry).await?;
+        let headers_received = std::time::Instant::now();
+        let request_start = *request_start.get_or_insert(start);
+
+        let status_code = response.status();
+
+        if redirects.len() < max_redirects
+            && let Some(location) =
+                redirect_location(response.url(), status_code, response.headers())
+        {
+            let (next_method, keep_body) = redirect_method(status_code, &method);
+
+            strip_redirect_headers(&mut headers, response.url(), &location, keep_body);
 
-    let mut partial_req = http_client.request(input.method.clone(), &url);
+            if !keep_body {
+                body = None;
+            }
 
-    if let Some(http_version) = input.http_version {
-        partial_req = partial_req.version(http_version);
+            redirects.push(RedirectHop {
+                method: method.to_string(),
+                url,
+                status_code,
+                headers: response.headers().clone(),
+                location: location.to_string(),
+                duration: headers_received.duration_since(start),
+            });
+
+            method = next_method;
+            url = location.to_string();
+
+            continue;
+        }
+
+        let redirects_duration =
+            (!redirects.is_empty()).then(|| start.duration_since(request_start));
+
+        return Ok(StreamingResponse {
+            response: HittResponse {
+                url,
+                method: method.to_string(),
+                status_code,
+                headers: response.headers().to_owned(),
+                http_version: response.version(),
+                duration: headers_received.duration_since(request_start),
+                timings: Timings::default(),
+                redirects,
+                body: String::new(),
+                raw_body: Vec::new(),
+                truncated: false,
+            },
+            body: response,
+            start,
+            headers_received,
+            redirects_duration,
+            connection_events,
+        });
     }
+}
+
+#[cfg(test)]
+mod test_send_request_streaming {
+    use std::io::{BufRead, Write};
+
+    use crate::{redirect::DEFAULT_MAX_REDIRECTS, retry::RetryPolicy, send_request_streaming};
+
+    /// Responds with a chunked body, sending the second chunk once `receiver` gets a message.
+    ///
+    /// If no message arrives within 5 seconds, the second chunk is `late` instead of `second`.
+    fn start_server(receiver: std::sync::mpsc::Receiver<()>) -> std::net::SocketAddr {
+        let listener =
+            std::net::TcpListener::bind("127.0.0.1:0").expect("it to bind to a local port");
+
+        let addr = listener.local_addr().expect("it to have a local address");
+
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("it to accept a connection");
+
+            let mut reader = std::io::BufReader::new(&stream);
+
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|len| len > 2) {
+                line.clear();
+            }
 
-    if !input.headers.is_empty() {
-        partial_req = partial_req.headers(input.headers.clone());
+            let _ = stream
+                .write_all(b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n6\r\nfirst,\r\n");
+            let _ = stream.flush();
+
+            let second = if receiver
+                .recv_timeout(core::time::Duration::from_secs(5))
+                .is_ok()
+            {
+                "second"
+            } else {
+                "late"
+            };
+
+            let _ =
+                stream.write_all(format!("{:x}\r\n{second}\r\n0\r\n\r\n", second.len()).as_bytes());
+        });
+
+        addr
     }
 
-    if input.body.is_some()
-        && let Some(body) = input.body.clone()
-    {
-        partial_req = partial_req.body(body);
+    fn build_request(addr: std::net::SocketAddr) -> hitt_parser::HittRequest {
+        hitt_parser::HittRequest {
+            method: http::Method::GET,
+            uri: http::Uri::try_from(format!("http://{addr}/")).expect("it to be a valid uri"),
+            headers: http::HeaderMap::new(),
+            body: None,
+            http_version: None,
+            secrets: Vec::new(),
+            warnings: Vec::new(),
+            name: None,
+            max_redirects: None,
+            tls: hitt_parser::tls::RequestTls::default(),
+            unix_socket: None,
+            output: None,
+        }
     }
 
-    if timeout.is_some()
-        && let Some(dur) = timeout
-    {
-        partial_req = partial_req.timeout(dur.to_owned());
+    #[tokio::test]
+    async fn it_should_pass_chunks_as_they_arrive() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let addr = start_server(receiver);
+
+        let response = send_request_streaming(
+            &reqwest::Client::new(),
+            &build_request(addr),
+            None,
+            None,
+            DEFAULT_MAX_REDIRECTS,
+            &RetryPolicy::default(),
+            |_| {},
+        )
+        .await
+        .expect("it to receive the headers");
+
+        assert!(response.response.raw_body.is_empty());
+
+        let mut chunks = Vec::new();
+
+        let response = response
+            .read_body(None, |chunk| {
+                // the server only sends the rest of the body once the first chunk is received
+                let _ = sender.send(());
+
+                chunks.push(chunk.to_vec());
+            })
+            .await
+            .expect("it to read the body");
+
+        assert_eq!(vec![b"first,".to_vec(), b"second".to_vec()], chunks);
+        assert_eq!("first,second", response.body);
+        assert!(!response.truncated);
     }
 
-    let request = partial_req.build()?;
+    #[tokio::test]
+    async fn it_should_stop_at_max_body_size() {
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let addr = start_server(receiver);
+
+        sender.send(()).expect("it to send the message");
+
+        let response = send_request_streaming(
+            &reqwest::Client::new(),
+            &build_request(addr),
+            None,
+            None,
+            DEFAULT_MAX_REDIRECTS,
+            &RetryPolicy::default(),
+            |_| {},
+        )
+        .await
+        .expect("it to receive the headers")
+        .read_body(Some(8), |_| {})
+        .await
+        .expect("it to read the body");
+
+        assert_eq!(b"first,se".to_vec(), response.raw_body);
+        assert!(response.truncated);
+    }
+
+    #[tokio::test]
+    async fn it_should_only_time_new_connections() {
+        let listener =
+            std::net::TcpListener::bind("127.0.0.1:0").expect("it to bind to a local port");
+
+        let addr = listener.local_addr().expect("it to have a local address");
+
+        // both requests are answered on the same connection
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().expect("it to accept a connection");
+
+            let mut reader = std::io::BufReader::new(stream.try_clone().expect("it to clone"));
 
-    // TODO: implement more precise benchmark?
-    let start = std::time::Instant::now();
-    let response = http_client.execute(request).await?;
-    let duration = start.elapsed();
+            for _ in 0..2 {
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|len| len > 2) {
+                    line.clear();
+                }
 
-    Ok(HittResponse {
-        url,
-        method: input.method.to_string(),
-        status_code: response.status(),
-        headers: response.headers().to_owned(),
-        http_version: response.version(),
-        duration,
-        body: response.text().await?,
-    })
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\n\r\nok");
+                let _ = stream.flush();
+            }
+        });
+
+        let recorder = crate::timing::TimingRecorder::new();
+
+        let tls_config = crate::tls::build_tls_config(
+            &crate::tls::TlsOptions::default(),
+            &crate::tls::HTTP_VERSIONS,
+        )
+        .expect("it to build the tls config");
+
+        let client = recorder
+            .install(reqwest::Client::builder(), tls_config)
+            .build()
+            .expect("it to build a client");
+
+        let mut timings = Vec::new();
+
+        for _ in 0..2 {
+            let response = send_request_streaming(
+                &client,
+                &build_request(addr),
+                None,
+                Some(&recorder),
+                DEFAULT_MAX_REDIRECTS,
+                &RetryPolicy::default(),
+                |_| {},
+            )
+            .await
+            .expect("it to receive the headers")
+            .read_body(None, |_| {})
+            .await
+            .expect("it to read the body");
+
+            timings.push(response.timings);
+        }
+
+        let first = timings.first().expect("it to exist");
+        assert!(first.tcp_connect.is_some());
+        assert_eq!(None, first.tls_handshake);
+
+        let reused = timings.get(1).expect("it to exist");
+        assert_eq!(None, reused.tcp_connect);
+    }
+}
+
+/// Sends the request, following up to `max_redirects` redirects unless the request sets its own limit.
+///
+/// Redirects are followed here so every hop can be recorded, so the client should be built
+/// using `reqwest::redirect::Policy::none()`.
+/// If the limit is reached, the last redirect response is returned.
+///
+/// Every request, including redirected ones, is retried as allowed by `retry`.
+/// `on_retry` is called for every failed attempt, before waiting for the next one.
+#[inline]
+pub async fn send_request(
+    http_client: &reqwest::Client,
+    input: &HittRequest,
+    timeout: Option<&core::time::Duration>,
+    timings: Option<&TimingRecorder>,
+    max_redirects: usize,
+    retry: &RetryPolicy,
+    on_retry: impl FnMut(&RetryAttempt),
+) -> Result<HittResponse, reqwest::Error> {
+    send_request_streaming(
+        http_client,
+        input,
+        timeout,
+        timings,
+        max_redirects,
+        retry,
+        on_retry,
+    )
+    .await?
+    .read_body(None, |_| {})
+    .await
 }
 
 #[cfg(test)]
@@ -64,7 +512,11 @@ mod test_send_request {
 
     use http::{HeaderMap, StatusCode};
 
-    use crate::send_request;
+    use crate::{
+        redirect::DEFAULT_MAX_REDIRECTS,
+        retry::{RetryPolicy, RetryReason},
+        send_request,
+    };
 
     #[tokio::test]
     async fn it_should_return_a_response() {
@@ -82,11 +534,26 @@ mod test_send_request {
             headers: HeaderMap::default(),
             body: None,
             http_version: None,
+            secrets: Vec::new(),
+            warnings: Vec::new(),
+            name: None,
+            max_redirects: None,
+            tls: hitt_parser::tls::RequestTls::default(),
+            unix_socket: None,
+            output: None,
         };
 
-        let result = send_request(&http_client, &input, timeout.as_ref())
-            .await
-            .expect("it to be successful");
+        let result = send_request(
+            &http_client,
+            &input,
+            timeout.as_ref(),
+            None,
+            DEFAULT_MAX_REDIRECTS,
+            &RetryPolicy::default(),
+            |_| {},
+        )
+        .await
+        .expect("it to be successful");
 
         assert_eq!(result.url, uri.to_string());
 
@@ -116,11 +583,26 @@ mod test_send_request {
             headers: HeaderMap::default(),
             body: Some("hello world".to_owned()),
             http_version: Some(http::Version::HTTP_11),
+            secrets: Vec::new(),
+            warnings: Vec::new(),
+            name: None,
+            max_redirects: None,
+            tls: hitt_parser::tls::RequestTls::default(),
+            unix_socket: None,
+            output: None,
         };
 
-        let result = send_request(&http_client, &input, timeout.as_ref())
-            .await
-            .expect("it to be successful");
+        let result = send_request(
+            &http_client,
+            &input,
+            timeout.as_ref(),
+            None,
+            DEFAULT_MAX_REDIRECTS,
+            &RetryPolicy::default(),
+            |_| {},
+        )
+        .await
+        .expect("it to be successful");
 
         assert_eq!(result.url, uri.to_string());
 
@@ -158,11 +640,26 @@ mod test_send_request {
             headers,
             body: Some("hello world".to_owned()),
             http_version: None,
+            secrets: Vec::new(),
+            warnings: Vec::new(),
+            name: None,
+            max_redirects: None,
+            tls: hitt_parser::tls::RequestTls::default(),
+            unix_socket: None,
+            output: None,
         };
 
-        let result = send_request(&http_client, &input, timeout.as_ref())
-            .await
-            .expect("it to be successful");
+        let result = send_request(
+            &http_client,
+            &input,
+            timeout.as_ref(),
+            None,
+            DEFAULT_MAX_REDIRECTS,
+            &RetryPolicy::default(),
+            |_| {},
+        )
+        .await
+        .expect("it to be successful");
 
         assert_eq!(result.url, uri.to_string());
 
@@ -190,11 +687,26 @@ mod test_send_request {
             headers: HeaderMap::default(),
             body: Some("hello world".to_owned()),
             http_version: None,
+            secrets: Vec::new(),
+            warnings: Vec::new(),
+            name: None,
+            max_redirects: None,
+            tls: hitt_parser::tls::RequestTls::default(),
+            unix_socket: None,
+            output: None,
         };
 
-        let result = send_request(&http_client, &input, timeout.as_ref())
-            .await
-            .expect("it to be successful");
+        let result = send_request(
+            &http_client,
+            &input,
+            timeout.as_ref(),
+            None,
+            DEFAULT_MAX_REDIRECTS,
+            &RetryPolicy::default(),
+            |_| {},
+        )
+        .await
+        .expect("it to be successful");
 
         assert_eq!(result.url, uri.to_string());
 
@@ -217,13 +729,339 @@ mod test_send_request {
             headers: HeaderMap::default(),
             body: None,
             http_version: None,
+            secrets: Vec::new(),
+            warnings: Vec::new(),
+            name: None,
+            max_redirects: None,
+            tls: hitt_parser::tls::RequestTls::default(),
+            unix_socket: None,
+            output: None,
         };
 
-        let response = send_request(&http_client, &input, timeout.as_ref())
-            .await
-            .err()
-            .expect("it to throw an error");
+        let response = send_request(
+            &http_client,
+            &input,
+            timeout.as_ref(),
+            None,
+            DEFAULT_MAX_REDIRECTS,
+            &RetryPolicy::default(),
+            |_| {},
+        )
+        .await
+        .err()
+        .expect("it to throw an error");
 
         assert!(response.is_timeout());
     }
+
+    /// Serves one connection per response, returning the request lines that were received.
+    fn serve(
+        responses: Vec<&'static str>,
+    ) -> (std::net::SocketAddr, std::thread::JoinHandle<Vec<String>>) {
+        let listener =
+            std::net::TcpListener::bind("127.0.0.1:0").expect("it to bind to a local port");
+
+        let addr = listener.local_addr().expect("it to have a local address");
+
+        let handle = std::thread::spawn(move || {
+            let mut request_lines = Vec::new();
+
+            for response in responses {
+                let (stream, _) = listener.accept().expect("it to accept a connection");
+
+                let mut reader = std::io::BufReader::new(&stream);
+
+                let mut request = String::new();
+
+                loop {
+                    let mut line = String::new();
+
+                    std::io::BufRead::read_line(&mut reader, &mut line)
+                        .expect("it to read the request");
+
+                    if line.trim().is_empty() {
+                        break;
+                    }
+
+                    request.push_str(&line);
+                }
+
+                request_lines.push(request);
+
+                std::io::Write::write_all(&mut &stream, response.as_bytes())
+                    .expect("it to write the response");
+            }
+
+            request_lines
+        });
+
+        (addr, handle)
+    }
+
+    fn build_input(uri: &str, max_redirects: Option<usize>) -> hitt_parser::HittRequest {
+        hitt_parser::HittRequest {
+            method: http::Method::GET,
+            uri: http::Uri::from_str(uri).expect("it to be a valid uri"),
+            headers: HeaderMap::from_iter([(
+                http::header::AUTHORIZATION,
+                http::HeaderValue::from_static("Bearer abc"),
+            )]),
+            body: None,
+            http_version: None,
+            secrets: Vec::new(),
+            warnings: Vec::new(),
+            name: None,
+            max_redirects,
+            tls: hitt_parser::tls::RequestTls::default(),
+            unix_socket: None,
+            output: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn it_should_record_redirects() {
+        let (addr, handle) = serve(vec![
+            "HTTP/1.1 302 Found\r\nlocation: /b\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 301 Moved Permanently\r\nlocation: http://localhost:1/c\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+        ]);
+
+        let http_client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("it to build a client");
+
+        let input = build_input(&format!("http://{addr}/a"), None);
+
+        let result = send_request(
+            &http_client,
+            &input,
+            None,
+            None,
+            1,
+            &RetryPolicy::default(),
+            |_| {},
+        )
+        .await
+        .expect("it to be successful");
+
+        assert_eq!(result.status_code, StatusCode::MOVED_PERMANENTLY);
+        assert_eq!(result.url, format!("http://{addr}/b"));
+
+        assert_eq!(result.redirects.len(), 1);
+
+        let hop = result.redirects.first().expect("it to have a hop");
+        assert_eq!(hop.method, "GET");
+        assert_eq!(hop.url, format!("http://{addr}/a"));
+        assert_eq!(hop.status_code, StatusCode::FOUND);
+        assert_eq!(hop.location, format!("http://{addr}/b"));
+
+        let redirects_duration = result.timings.redirects.expect("it to time the redirects");
+
+        assert!(redirects_duration >= hop.duration);
+        assert_eq!(result.duration, redirects_duration + result.timings.ttfb);
+
+        let requests = handle.join().expect("it to join the server");
+
+        assert!(
+            requests
+                .iter()
+                .all(|request| request.contains("authorization: Bearer abc"))
+        );
+    }
+
+    #[tokio::test]
+    async fn it_should_respect_max_redirects_of_request() {
+        let (addr, handle) = serve(vec![
+            "HTTP/1.1 302 Found\r\nlocation: /b\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+        ]);
+
+        let http_client = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .expect("it to build a client");
+
+        let input = build_input(&format!("http://{addr}/a"), Some(0));
+
+        let result = send_request(
+            &http_client,
+            &input,
+            None,
+            None,
+            DEFAULT_MAX_REDIRECTS,
+            &RetryPolicy::default(),
+            |_| {},
+        )
+        .await
+        .expect("it to be successful");
+
+        assert_eq!(result.status_code, StatusCode::FOUND);
+        assert!(result.redirects.is_empty());
+
+        handle.join().expect("it to join the server");
+    }
+
+    #[tokio::test]
+    async fn it_should_retry_status_codes() {
+        let (addr, handle) = serve(vec![
+            "HTTP/1.1 503 Service Unavailable\r\nretry-after: 0\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 502 Bad Gateway\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok",
+        ]);
+
+        let http_client = reqwest::Client::new();
+
+        let input = build_input(&format!("http://{addr}/a"), None);
+
+        let retry = RetryPolicy {
+            retries: 3,
+            base_delay: Duration::from_millis(2),
+            ..RetryPolicy::default()
+        };
+
+        let mut attempts = Vec::new();
+
+        let result = send_request(
+            &http_client,
+            &input,
+            None,
+            None,
+            DEFAULT_MAX_REDIRECTS,
+            &retry,
+            |attempt| attempts.push(attempt.clone()),
+        )
+        .await
+        .expect("it to be successful");
+
+        assert_eq!(result.status_code, StatusCode::OK);
+        assert_eq!(result.body, "ok");
+
+        assert_eq!(
+            [
+                (1, RetryReason::StatusCode(StatusCode::SERVICE_UNAVAILABLE)),
+                (2, RetryReason::StatusCode(StatusCode::BAD_GATEWAY)),
+            ],
+            [
+                attempts
+                    .first()
+                    .map(|a| (a.attempt, a.reason))
+                    .expect("it to exist"),
+                attempts
+                    .get(1)
+                    .map(|a| (a.attempt, a.reason))
+                    .expect("it to exist"),
+            ]
+        );
+
+        assert_eq!(2, attempts.len());
+
+        // retry-after takes precedence over the backoff delay
+        assert_eq!(Some(Duration::ZERO), attempts.first().map(|a| a.delay));
+
+        assert_eq!(3, handle.join().expect("it to join the server").len());
+    }
+
+    #[tokio::test]
+    async fn it_should_return_last_response_when_out_of_retries() {
+        let (addr, handle) = serve(vec![
+            "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+            "HTTP/1.1 503 Service Unavailable\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+        ]);
+
+        let http_client = reqwest::Client::new();
+
+        let input = build_input(&format!("http://{addr}/a"), None);
+
+        let retry = RetryPolicy {
+            retries: 1,
+            base_delay: Duration::from_millis(2),
+            ..RetryPolicy::default()
+        };
+
+        let mut attempts = 0;
+
+        let result = send_request(
+            &http_client,
+            &input,
+            None,
+            None,
+            DEFAULT_MAX_REDIRECTS,
+            &retry,
+            |_| attempts += 1,
+        )
+        .await
+        .expect("it to be successful");
+
+        assert_eq!(result.status_code, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(1, attempts);
+
+        handle.join().expect("it to join the server");
+    }
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn it_should_send_requests_over_unix_sockets() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to create a dir");
+
+        let socket_path = dir.path().join("api.sock");
+
+        let listener = std::os::unix::net::UnixListener::bind(&socket_path)
+            .expect("it to bind to the unix socket");
+
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("it to accept a connection");
+
+            let mut reader = std::io::BufReader::new(&stream);
+
+            let mut request = String::new();
+
+            loop {
+                let mut line = String::new();
+
+                std::io::BufRead::read_line(&mut reader, &mut line)
+                    .expect("it to read the request");
+
+                if line.trim().is_empty() {
+                    break;
+                }
+
+                request.push_str(&line);
+            }
+
+            std::io::Write::write_all(
+                &mut &stream,
+                b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok",
+            )
+            .expect("it to write the response");
+
+            request
+        });
+
+        let http_client = reqwest::Client::builder()
+            .unix_socket(socket_path)
+            .build()
+            .expect("it to build a client");
+
+        let response = send_request(
+            &http_client,
+            &build_input("http://docker.local/v1.47/containers/json?all=1", Some(0)),
+            None,
+            None,
+            DEFAULT_MAX_REDIRECTS,
+            &RetryPolicy::default(),
+            |_| {},
+        )
+        .await
+        .expect("it to be successful");
+
+        assert_eq!(StatusCode::OK, response.status_code);
+        assert_eq!("ok", response.body);
+
+        let request = handle.join().expect("it to join the server");
+
+        assert!(request.starts_with("GET /v1.47/containers/json?all=1 HTTP/1.1\r\n"));
+        assert!(request.contains("host: docker.local\r\n"));
+    }
 }
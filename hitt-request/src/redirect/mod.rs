@@ -0,0 +1,246 @@
+/// Default number of redirects to follow, matching the default of `reqwest`.
+pub const DEFAULT_MAX_REDIRECTS: usize = 10;
+
+/// Headers that are removed when a redirect points to another origin.
+const CROSS_ORIGIN_SENSITIVE_HEADERS: [http::HeaderName; 4] = [
+    http::header::AUTHORIZATION,
+    http::header::COOKIE,
+    http::header::PROXY_AUTHORIZATION,
+    http::header::WWW_AUTHENTICATE,
+];
+
+/// A redirect response that was followed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RedirectHop {
+    pub method: String,
+    pub url: String,
+    pub status_code: reqwest::StatusCode,
+    pub headers: reqwest::header::HeaderMap,
+    /// Absolute url of the `location` header.
+    pub location: String,
+    pub duration: core::time::Duration,
+}
+
+/// Returns the absolute url of the `location` header, if the response is a redirect.
+#[inline]
+pub fn redirect_location(
+    url: &reqwest::Url,
+    status_code: reqwest::StatusCode,
+    headers: &reqwest::header::HeaderMap,
+) -> Option<reqwest::Url> {
+    if !matches!(status_code.as_u16(), 301 | 302 | 303 | 307 | 308) {
+        return None;
+    }
+
+    let location = headers.get(http::header::LOCATION)?.to_str().ok()?;
+
+    url.join(location).ok()
+}
+
+#[cfg(test)]
+mod test_redirect_location {
+    use http::{HeaderMap, HeaderValue, StatusCode};
+
+    use super::redirect_location;
+
+    #[test]
+    fn it_should_resolve_location() {
+        let url = reqwest::Url::parse("https://mhouge.dk/a/b").expect("it to be a valid url");
+
+        for (location, expected) in [
+            ("/c", "https://mhouge.dk/c"),
+            ("c", "https://mhouge.dk/a/c"),
+            ("https://example.com/", "https://example.com/"),
+        ] {
+            let headers = HeaderMap::from_iter([(
+                http::header::LOCATION,
+                HeaderValue::from_static(location),
+            )]);
+
+            for status_code in [301, 302, 303, 307, 308] {
+                let output = redirect_location(
+                    &url,
+                    StatusCode::from_u16(status_code).expect("it to be a valid status code"),
+                    &headers,
+                )
+                .expect("it to return a location");
+
+                assert_eq!(expected, output.as_str());
+            }
+        }
+    }
+
+    #[test]
+    fn it_should_ignore_other_responses() {
+        let url = reqwest::Url::parse("https://mhouge.dk/").expect("it to be a valid url");
+
+        let headers =
+            HeaderMap::from_iter([(http::header::LOCATION, HeaderValue::from_static("/other"))]);
+
+        for status_code in [
+            StatusCode::OK,
+            StatusCode::NOT_MODIFIED,
+            StatusCode::NOT_FOUND,
+        ] {
+            assert_eq!(None, redirect_location(&url, status_code, &headers));
+        }
+
+        assert_eq!(
+            None,
+            redirect_location(&url, StatusCode::FOUND, &HeaderMap::new())
+        );
+    }
+}
+
+/// Returns the method of the redirected request, and whether the body should be kept.
+///
+/// `303 See Other` always switches to `GET`, while `301` and `302` only switch `POST` requests,
+/// like browsers do.
+#[inline]
+pub fn redirect_method(
+    status_code: reqwest::StatusCode,
+    method: &http::Method,
+) -> (http::Method, bool) {
+    match status_code {
+        reqwest::StatusCode::SEE_OTHER if method != http::Method::HEAD => {
+            (http::Method::GET, false)
+        }
+        reqwest::StatusCode::MOVED_PERMANENTLY | reqwest::StatusCode::FOUND
+            if method == http::Method::POST =>
+        {
+            (http::Method::GET, false)
+        }
+        _ => (method.clone(), true),
+    }
+}
+
+#[cfg(test)]
+mod test_redirect_method {
+    use http::{Method, StatusCode};
+
+    use super::redirect_method;
+
+    #[test]
+    fn it_should_switch_to_get() {
+        for (status_code, method) in [
+            (StatusCode::SEE_OTHER, Method::POST),
+            (StatusCode::SEE_OTHER, Method::PUT),
+            (StatusCode::MOVED_PERMANENTLY, Method::POST),
+            (StatusCode::FOUND, Method::POST),
+        ] {
+            assert_eq!((Method::GET, false), redirect_method(status_code, &method));
+        }
+    }
+
+    #[test]
+    fn it_should_keep_method_and_body() {
+        for (status_code, method) in [
+            (StatusCode::TEMPORARY_REDIRECT, Method::POST),
+            (StatusCode::PERMANENT_REDIRECT, Method::PATCH),
+            (StatusCode::FOUND, Method::PUT),
+            (StatusCode::SEE_OTHER, Method::HEAD),
+        ] {
+            assert_eq!(
+                (method.clone(), true),
+                redirect_method(status_code, &method)
+            );
+        }
+    }
+}
+
+#[inline]
+fn is_same_origin(a: &reqwest::Url, b: &reqwest::Url) -> bool {
+    a.scheme() == b.scheme()
+        && a.host_str() == b.host_str()
+        && a.port_or_known_default() == b.port_or_known_default()
+}
+
+/// Removes the headers that should not be sent to the redirect location.
+#[inline]
+pub fn strip_redirect_headers(
+    headers: &mut http::HeaderMap,
+    from: &reqwest::Url,
+    to: &reqwest::Url,
+    keep_body: bool,
+) {
+    if !keep_body {
+        headers.remove(http::header::CONTENT_TYPE);
+        headers.remove(http::header::CONTENT_LENGTH);
+        headers.remove(http::header::CONTENT_ENCODING);
+    }
+
+    if !is_same_origin(from, to) {
+        for name in &CROSS_ORIGIN_SENSITIVE_HEADERS {
+            headers.remove(name);
+        }
+
+        // the host header of the original request would point back to the old host
+        headers.remove(http::header::HOST);
+    }
+}
+
+#[cfg(test)]
+mod test_strip_redirect_headers {
+    use http::{HeaderMap, HeaderValue};
+
+    use super::strip_redirect_headers;
+
+    fn build_headers() -> HeaderMap {
+        HeaderMap::from_iter([
+            (
+                http::header::AUTHORIZATION,
+                HeaderValue::from_static("Bearer abc"),
+            ),
+            (
+                http::header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+            (http::header::ACCEPT, HeaderValue::from_static("*/*")),
+        ])
+    }
+
+    #[test]
+    fn it_should_keep_headers_for_same_origin() {
+        let from = reqwest::Url::parse("https://mhouge.dk/a").expect("it to be a valid url");
+        let to = reqwest::Url::parse("https://mhouge.dk:443/b").expect("it to be a valid url");
+
+        let mut headers = build_headers();
+
+        strip_redirect_headers(&mut headers, &from, &to, true);
+
+        assert_eq!(build_headers(), headers);
+    }
+
+    #[test]
+    fn it_should_remove_credentials_for_other_origins() {
+        let from = reqwest::Url::parse("https://mhouge.dk/a").expect("it to be a valid url");
+
+        for to in [
+            "https://example.com/a",
+            "http://mhouge.dk/a",
+            "https://mhouge.dk:8443/a",
+        ] {
+            let to = reqwest::Url::parse(to).expect("it to be a valid url");
+
+            let mut headers = build_headers();
+
+            strip_redirect_headers(&mut headers, &from, &to, true);
+
+            assert!(!headers.contains_key(http::header::AUTHORIZATION));
+            assert!(headers.contains_key(http::header::CONTENT_TYPE));
+            assert!(headers.contains_key(http::header::ACCEPT));
+        }
+    }
+
+    #[test]
+    fn it_should_remove_body_headers_if_body_is_dropped() {
+        let url = reqwest::Url::parse("https://mhouge.dk/a").expect("it to be a valid url");
+
+        let mut headers = build_headers();
+
+        strip_redirect_headers(&mut headers, &url, &url, false);
+
+        assert!(!headers.contains_key(http::header::CONTENT_TYPE));
+        assert!(headers.contains_key(http::header::AUTHORIZATION));
+    }
+}
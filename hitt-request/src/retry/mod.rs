@@ -0,0 +1,346 @@
+/// Status codes that are retried by default.
+pub const DEFAULT_RETRY_STATUS_CODES: [u16; 3] = [502, 503, 504];
+
+/// When and how often a failed request is sent again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of retries after the first attempt, `0` disables retries.
+    pub retries: usize,
+    /// Response status codes that are retried.
+    pub status_codes: Vec<reqwest::StatusCode>,
+    /// Delay before the first retry, which is doubled for every following retry.
+    pub base_delay: core::time::Duration,
+    /// Upper limit of the delay, including delays set using `Retry-After`.
+    pub max_delay: core::time::Duration,
+}
+
+impl Default for RetryPolicy {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            retries: 0,
+            status_codes: DEFAULT_RETRY_STATUS_CODES
+                .iter()
+                .filter_map(|status_code| reqwest::StatusCode::from_u16(*status_code).ok())
+                .collect(),
+            base_delay: core::time::Duration::from_millis(500),
+            max_delay: core::time::Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryReason {
+    ConnectionError,
+    StatusCode(reqwest::StatusCode),
+    Timeout,
+}
+
+impl core::fmt::Display for RetryReason {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::ConnectionError => write!(f, "connection error"),
+            Self::StatusCode(status_code) => write!(f, "{}", status_code.as_u16()),
+            Self::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+/// A failed attempt that is about to be retried.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryAttempt {
+    /// Number of the failed attempt, starting at 1.
+    pub attempt: usize,
+    pub method: String,
+    pub url: String,
+    pub reason: RetryReason,
+    /// Time waited before the next attempt.
+    pub delay: core::time::Duration,
+}
+
+/// Returns the reason the response or error should be retried, if the policy allows it.
+#[inline]
+pub fn retry_reason(
+    policy: &RetryPolicy,
+    result: &Result<reqwest::Response, reqwest::Error>,
+) -> Option<RetryReason> {
+    match result {
+        Ok(response) => policy
+            .status_codes
+            .contains(&response.status())
+            .then(|| RetryReason::StatusCode(response.status())),
+        Err(error) if error.is_timeout() => Some(RetryReason::Timeout),
+        Err(error) if error.is_connect() => Some(RetryReason::ConnectionError),
+        Err(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod test_retry_reason {
+    use super::{RetryPolicy, RetryReason, retry_reason};
+
+    fn build_response(status_code: u16) -> reqwest::Response {
+        reqwest::Response::from(
+            http::Response::builder()
+                .status(status_code)
+                .body(String::new())
+                .expect("it to build a response"),
+        )
+    }
+
+    #[test]
+    fn it_should_retry_status_codes_of_policy() {
+        let policy = RetryPolicy::default();
+
+        for status_code in [502, 503, 504] {
+            assert_eq!(
+                Some(RetryReason::StatusCode(
+                    reqwest::StatusCode::from_u16(status_code).expect("it to be a valid status")
+                )),
+                retry_reason(&policy, &Ok(build_response(status_code)))
+            );
+        }
+
+        for status_code in [200, 404, 429, 500] {
+            assert_eq!(
+                None,
+                retry_reason(&policy, &Ok(build_response(status_code)))
+            );
+        }
+
+        let policy = RetryPolicy {
+            status_codes: vec![reqwest::StatusCode::TOO_MANY_REQUESTS],
+            ..RetryPolicy::default()
+        };
+
+        assert!(retry_reason(&policy, &Ok(build_response(429))).is_some());
+        assert!(retry_reason(&policy, &Ok(build_response(503))).is_none());
+    }
+
+    #[tokio::test]
+    async fn it_should_retry_connection_errors() {
+        // binding and dropping the listener leaves a port nothing listens on
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .expect("it to bind to a local port")
+            .local_addr()
+            .expect("it to have a local address");
+
+        let result = reqwest::Client::new()
+            .get(format!("http://{addr}/"))
+            .send()
+            .await;
+
+        assert_eq!(
+            Some(RetryReason::ConnectionError),
+            retry_reason(&RetryPolicy::default(), &result)
+        );
+    }
+}
+
+/// Returns the delay before retrying the given attempt, starting at 1.
+///
+/// The delay doubles for every attempt, up to [`RetryPolicy::max_delay`].
+/// `jitter` is a number between `0` and `1`, which randomizes the upper half of the delay,
+/// so parallel clients do not retry at the same time.
+#[inline]
+pub fn backoff_delay(policy: &RetryPolicy, attempt: usize, jitter: f64) -> core::time::Duration {
+    let exponent = u32::try_from(attempt.saturating_sub(1)).unwrap_or(u32::MAX);
+
+    let delay = policy
+        .base_delay
+        .checked_mul(2_u32.saturating_pow(exponent))
+        .unwrap_or(policy.max_delay)
+        .min(policy.max_delay);
+
+    delay / 2 + (delay / 2).mul_f64(jitter.clamp(0.0, 1.0))
+}
+
+#[cfg(test)]
+mod test_backoff_delay {
+    use core::time::Duration;
+
+    use super::{RetryPolicy, backoff_delay};
+
+    #[test]
+    fn it_should_double_delay() {
+        let policy = RetryPolicy::default();
+
+        for (attempt, expected) in [(1, 500), (2, 1000), (3, 2000), (4, 4000)] {
+            assert_eq!(
+                Duration::from_millis(expected),
+                backoff_delay(&policy, attempt, 1.0)
+            );
+
+            assert_eq!(
+                Duration::from_millis(expected / 2),
+                backoff_delay(&policy, attempt, 0.0)
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_limit_delay() {
+        let policy = RetryPolicy::default();
+
+        for attempt in [7, 100, usize::MAX] {
+            assert_eq!(policy.max_delay, backoff_delay(&policy, attempt, 1.0));
+        }
+    }
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+#[inline]
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Parses a date such as `Sun, 06 Nov 1994 08:49:37 GMT`, the format used by HTTP headers.
+#[inline]
+fn parse_http_date(value: &str) -> Option<std::time::SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+
+    let (_weekday, date) = value.trim().split_once(", ")?;
+
+    let mut parts = date.split(' ');
+
+    let day = parts.next()?.parse::<i64>().ok()?;
+    let month = parts.next()?;
+    let year = parts.next()?.parse::<i64>().ok()?;
+    let time = parts.next()?;
+
+    if parts.next()? != "GMT" || parts.next().is_some() {
+        return None;
+    }
+
+    let month = MONTHS.iter().position(|name| *name == month)?;
+
+    let mut time_parts = time.split(':').map(|part| part.parse::<i64>().ok());
+
+    let hours = time_parts.next()??;
+    let minutes = time_parts.next()??;
+    let seconds = time_parts.next()??;
+
+    if !(1..=31).contains(&day) || hours > 23 || minutes > 59 || seconds > 60 {
+        return None;
+    }
+
+    let days = days_from_civil(year, i64::try_from(month).ok()? + 1, day);
+
+    let timestamp = u64::try_from(days * 86_400 + hours * 3600 + minutes * 60 + seconds).ok()?;
+
+    std::time::UNIX_EPOCH.checked_add(core::time::Duration::from_secs(timestamp))
+}
+
+#[cfg(test)]
+mod test_parse_http_date {
+    use super::parse_http_date;
+
+    #[test]
+    fn it_should_parse_dates() {
+        for (input, timestamp) in [
+            ("Sun, 06 Nov 1994 08:49:37 GMT", 784_111_777),
+            ("Thu, 01 Jan 1970 00:00:00 GMT", 0),
+            ("Tue, 29 Feb 2028 23:59:59 GMT", 1_835_481_599),
+        ] {
+            assert_eq!(
+                Some(std::time::UNIX_EPOCH + core::time::Duration::from_secs(timestamp)),
+                parse_http_date(input)
+            );
+        }
+    }
+
+    #[test]
+    fn it_should_reject_invalid_dates() {
+        for input in [
+            "",
+            "120",
+            "Sunday, 06-Nov-94 08:49:37 GMT",
+            "Sun, 06 Nov 1994 08:49:37",
+            "Sun, 06 Foo 1994 08:49:37 GMT",
+            "Sun, 06 Nov 1994 25:49:37 GMT",
+        ] {
+            assert_eq!(None, parse_http_date(input));
+        }
+    }
+}
+
+/// Returns the delay set by the `Retry-After` header, in either seconds or as a date.
+#[inline]
+pub fn retry_after(
+    headers: &reqwest::header::HeaderMap,
+    now: std::time::SystemTime,
+) -> Option<core::time::Duration> {
+    let value = headers
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(core::time::Duration::from_secs(seconds));
+    }
+
+    // dates in the past mean the request can be retried right away
+    Some(
+        parse_http_date(value)?
+            .duration_since(now)
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod test_retry_after {
+    use core::time::Duration;
+
+    use http::{HeaderMap, HeaderValue};
+
+    use super::retry_after;
+
+    fn build_headers(value: &'static str) -> HeaderMap {
+        HeaderMap::from_iter([(http::header::RETRY_AFTER, HeaderValue::from_static(value))])
+    }
+
+    #[test]
+    fn it_should_parse_seconds() {
+        assert_eq!(
+            Some(Duration::from_secs(120)),
+            retry_after(&build_headers("120"), std::time::SystemTime::now())
+        );
+    }
+
+    #[test]
+    fn it_should_parse_dates() {
+        let now = std::time::UNIX_EPOCH + Duration::from_secs(784_111_767);
+
+        assert_eq!(
+            Some(Duration::from_secs(10)),
+            retry_after(&build_headers("Sun, 06 Nov 1994 08:49:37 GMT"), now)
+        );
+
+        assert_eq!(
+            Some(Duration::ZERO),
+            retry_after(
+                &build_headers("Sun, 06 Nov 1994 08:49:37 GMT"),
+                now + Duration::from_secs(60)
+            )
+        );
+    }
+
+    #[test]
+    fn it_should_ignore_invalid_values() {
+        let now = std::time::SystemTime::now();
+
+        assert_eq!(None, retry_after(&HeaderMap::new(), now));
+        assert_eq!(None, retry_after(&build_headers("soon"), now));
+        assert_eq!(None, retry_after(&build_headers("-1"), now));
+    }
+}
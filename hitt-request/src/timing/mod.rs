@@ -0,0 +1,514 @@
+/// Time spent in each phase of a request.
+///
+/// Connection phases are `None` when an existing connection was reused,
+/// or when the phase did not happen (no TLS handshake for `http` urls).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Timings {
+    /// Time spent on the redirects followed before the final request, if there were any.
+    pub redirects: Option<core::time::Duration>,
+    pub dns: Option<core::time::Duration>,
+    pub tcp_connect: Option<core::time::Duration>,
+    pub tls_handshake: Option<core::time::Duration>,
+    /// Time from the connection being ready (or the request starting) until the response headers arrived.
+    pub ttfb: core::time::Duration,
+    pub download: core::time::Duration,
+}
+
+impl Timings {
+    #[inline]
+    pub fn total(&self) -> core::time::Duration {
+        self.redirects.unwrap_or_default()
+            + self.dns.unwrap_or_default()
+            + self.tcp_connect.unwrap_or_default()
+            + self.tls_handshake.unwrap_or_default()
+            + self.ttfb
+            + self.download
+    }
+}
+
+#[cfg(test)]
+mod test_timings_total {
+    use core::time::Duration;
+
+    use super::Timings;
+
+    #[test]
+    fn it_should_sum_all_phases() {
+        let timings = Timings {
+            redirects: Some(Duration::from_millis(6)),
+            dns: Some(Duration::from_millis(1)),
+            tcp_connect: Some(Duration::from_millis(2)),
+            tls_handshake: Some(Duration::from_millis(3)),
+            ttfb: Duration::from_millis(4),
+            download: Duration::from_millis(5),
+        };
+
+        assert_eq!(Duration::from_millis(21), timings.total());
+    }
+
+    #[test]
+    fn it_should_skip_missing_phases() {
+        let timings = Timings {
+            redirects: None,
+            dns: None,
+            tcp_connect: None,
+            tls_handshake: None,
+            ttfb: Duration::from_millis(4),
+            download: Duration::from_millis(5),
+        };
+
+        assert_eq!(Duration::from_millis(9), timings.total());
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ConnectionEvents {
+    dns_start: Option<std::time::Instant>,
+    dns_end: Option<std::time::Instant>,
+    connect_start: Option<std::time::Instant>,
+    tls_start: Option<std::time::Instant>,
+    connect_end: Option<std::time::Instant>,
+}
+
+impl ConnectionEvents {
+    #[inline]
+    pub(crate) fn timings(
+        &self,
+        start: std::time::Instant,
+        headers_received: std::time::Instant,
+        body_received: std::time::Instant,
+    ) -> Timings {
+        let dns = self
+            .dns_start
+            .zip(self.dns_end)
+            .map(|(dns_start, dns_end)| dns_end.saturating_duration_since(dns_start));
+
+        let tcp_start = self.dns_end.or(self.connect_start);
+
+        // the tls handshake starts once the tcp connection is established
+        let tcp_end = self.tls_start.or(self.connect_end);
+
+        let tcp_connect = tcp_start
+            .zip(tcp_end)
+            .map(|(tcp_start, tcp_end)| tcp_end.saturating_duration_since(tcp_start));
+
+        let tls_handshake = self
+            .tls_start
+            .zip(self.connect_end)
+            .map(|(tls_start, connect_end)| connect_end.saturating_duration_since(tls_start));
+
+        let ttfb_start = self.connect_end.unwrap_or(start);
+
+        Timings {
+            redirects: None,
+            dns,
+            tcp_connect,
+            tls_handshake,
+            ttfb: headers_received.saturating_duration_since(ttfb_start),
+            download: body_received.saturating_duration_since(headers_received),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_connection_events_timings {
+    use core::time::Duration;
+
+    use super::{ConnectionEvents, Timings};
+
+    #[test]
+    fn it_should_split_new_tls_connections() {
+        let start = std::time::Instant::now();
+
+        let events = ConnectionEvents {
+            connect_start: Some(start),
+            dns_start: Some(start + Duration::from_millis(1)),
+            dns_end: Some(start + Duration::from_millis(3)),
+            tls_start: Some(start + Duration::from_millis(6)),
+            connect_end: Some(start + Duration::from_millis(10)),
+        };
+
+        let timings = events.timings(
+            start,
+            start + Duration::from_millis(15),
+            start + Duration::from_millis(21),
+        );
+
+        assert_eq!(
+            Timings {
+                redirects: None,
+                dns: Some(Duration::from_millis(2)),
+                tcp_connect: Some(Duration::from_millis(3)),
+                tls_handshake: Some(Duration::from_millis(4)),
+                ttfb: Duration::from_millis(5),
+                download: Duration::from_millis(6),
+            },
+            timings
+        );
+    }
+
+    #[test]
+    fn it_should_skip_tls_for_plain_connections() {
+        let start = std::time::Instant::now();
+
+        let events = ConnectionEvents {
+            connect_start: Some(start),
+            dns_start: None,
+            dns_end: None,
+            tls_start: None,
+            connect_end: Some(start + Duration::from_millis(2)),
+        };
+
+        let timings = events.timings(
+            start,
+            start + Duration::from_millis(5),
+            start + Duration::from_millis(6),
+        );
+
+        assert_eq!(
+            Timings {
+                redirects: None,
+                dns: None,
+                tcp_connect: Some(Duration::from_millis(2)),
+                tls_handshake: None,
+                ttfb: Duration::from_millis(3),
+                download: Duration::from_millis(1),
+            },
+            timings
+        );
+    }
+
+    #[test]
+    fn it_should_handle_reused_connections() {
+        let start = std::time::Instant::now();
+
+        let timings = ConnectionEvents::default().timings(
+            start,
+            start + Duration::from_millis(4),
+            start + Duration::from_millis(5),
+        );
+
+        assert_eq!(
+            Timings {
+                redirects: None,
+                dns: None,
+                tcp_connect: None,
+                tls_handshake: None,
+                ttfb: Duration::from_millis(4),
+                download: Duration::from_millis(1),
+            },
+            timings
+        );
+    }
+}
+
+tokio::task_local! {
+    /// Events of the connection opened by the request being measured.
+    ///
+    /// The hooks of the client run inside the future of the request that opens the connection,
+    /// so concurrent requests never record events of each other's connections.
+    static CONNECTION_EVENTS: std::sync::Arc<std::sync::Mutex<ConnectionEvents>>;
+}
+
+/// Updates the events of the request being measured, if any.
+#[inline]
+fn record(update: impl FnOnce(&mut ConnectionEvents)) {
+    let _ = CONNECTION_EVENTS.try_with(|events| {
+        if let Ok(mut events) = events.lock() {
+            update(&mut events);
+        }
+    });
+}
+
+/// Records connection events of a `reqwest::Client`.
+///
+/// The recorder hooks into the dns resolver, the connector and the tls session store of the client.
+#[derive(Clone, Debug, Default)]
+pub struct TimingRecorder;
+
+impl TimingRecorder {
+    #[inline]
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Runs the future, returning the events of the connection it opened.
+    ///
+    /// No events are recorded when the future reuses an existing connection.
+    #[inline]
+    pub(crate) async fn measure<F: core::future::Future>(
+        &self,
+        future: F,
+    ) -> (F::Output, ConnectionEvents) {
+        let events = std::sync::Arc::new(std::sync::Mutex::new(ConnectionEvents::default()));
+
+        let output = CONNECTION_EVENTS
+            .scope(std::sync::Arc::clone(&events), future)
+            .await;
+
+        let events = events
+            .lock()
+            .map_or_else(|_| ConnectionEvents::default(), |events| *events);
+
+        (output, events)
+    }
+
+    /// Installs the recorder on the client builder, using the tls config built by
+    /// [`crate::tls::build_tls_config`].
+    #[inline]
+    pub fn install(
+        &self,
+        builder: reqwest::ClientBuilder,
+        mut tls_config: rustls::ClientConfig,
+    ) -> reqwest::ClientBuilder {
+        tls_config.resumption =
+            rustls::client::Resumption::store(std::sync::Arc::new(TimingSessionStore {
+                inner: rustls::client::ClientSessionMemoryCache::new(256),
+            }));
+
+        builder
+            .dns_resolver(TimingResolver)
+            .connector_layer(TimingLayer)
+            .tls_backend_preconfigured(tls_config)
+    }
+}
+
+#[cfg(test)]
+mod test_timing_recorder {
+    use core::time::Duration;
+
+    use super::{TimingRecorder, record};
+
+    #[tokio::test]
+    async fn it_should_only_record_events_of_the_measured_future() {
+        let recorder = TimingRecorder::new();
+
+        let start = std::time::Instant::now();
+
+        // events outside of a measured future are ignored
+        record(|events| events.dns_start = Some(start));
+
+        let ((), events) = recorder
+            .measure(async {
+                record(|events| {
+                    events.connect_start = Some(start);
+                    events.connect_end = Some(start + Duration::from_millis(2));
+                });
+            })
+            .await;
+
+        assert_eq!(None, events.dns_start);
+
+        let timings = events.timings(
+            start,
+            start + Duration::from_millis(3),
+            start + Duration::from_millis(3),
+        );
+
+        assert_eq!(Some(Duration::from_millis(2)), timings.tcp_connect);
+
+        let ((), events) = recorder.measure(async {}).await;
+
+        let timings = events.timings(
+            start,
+            start + Duration::from_millis(3),
+            start + Duration::from_millis(3),
+        );
+
+        assert_eq!(None, timings.tcp_connect);
+        assert_eq!(Duration::from_millis(3), timings.ttfb);
+    }
+
+    #[tokio::test]
+    async fn it_should_keep_concurrent_events_apart() {
+        let recorder = TimingRecorder::new();
+
+        let start = std::time::Instant::now();
+
+        let measure = |millis| {
+            recorder.measure(async move {
+                record(|events| events.connect_start = Some(start));
+
+                tokio::task::yield_now().await;
+
+                record(|events| events.connect_end = Some(start + Duration::from_millis(millis)));
+            })
+        };
+
+        let (((), first), ((), second)) = tokio::join!(measure(1), measure(2));
+
+        assert_eq!(Some(start + Duration::from_millis(1)), first.connect_end);
+        assert_eq!(Some(start + Duration::from_millis(2)), second.connect_end);
+    }
+
+    #[test]
+    fn it_should_install_on_client_builder() {
+        let recorder = TimingRecorder::new();
+
+        let tls_config = crate::tls::build_tls_config(
+            &crate::tls::TlsOptions::default(),
+            &crate::tls::HTTP_VERSIONS,
+        )
+        .expect("it to build the tls config");
+
+        recorder
+            .install(reqwest::Client::builder(), tls_config)
+            .build()
+            .expect("it to build a client");
+    }
+}
+
+struct TimingResolver;
+
+impl reqwest::dns::Resolve for TimingResolver {
+    #[inline]
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            record(|events| events.dns_start = Some(std::time::Instant::now()));
+
+            let addrs = tokio::net::lookup_host(format!("{}:0", name.as_str())).await?;
+
+            record(|events| events.dns_end = Some(std::time::Instant::now()));
+
+            let addrs: reqwest::dns::Addrs = Box::new(addrs);
+
+            Ok(addrs)
+        })
+    }
+}
+
+#[derive(Clone)]
+struct TimingLayer;
+
+impl<S> tower_layer::Layer<S> for TimingLayer {
+    type Service = TimingConnector<S>;
+
+    #[inline]
+    fn layer(&self, inner: S) -> Self::Service {
+        TimingConnector { inner }
+    }
+}
+
+#[derive(Clone)]
+struct TimingConnector<S> {
+    inner: S,
+}
+
+impl<S, R> tower_service::Service<R> for TimingConnector<S>
+where
+    S: tower_service::Service<R>,
+    S::Future: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = core::pin::Pin<
+        Box<dyn core::future::Future<Output = Result<Self::Response, Self::Error>> + Send>,
+    >;
+    type Response = S::Response;
+
+    #[inline]
+    fn poll_ready(
+        &mut self,
+        cx: &mut core::task::Context<'_>,
+    ) -> core::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    #[inline]
+    fn call(&mut self, req: R) -> Self::Future {
+        record(|events| events.connect_start = Some(std::time::Instant::now()));
+
+        let future = self.inner.call(req);
+
+        Box::pin(async move {
+            let output = future.await;
+
+            record(|events| events.connect_end = Some(std::time::Instant::now()));
+
+            output
+        })
+    }
+}
+
+/// Session store that marks the start of the tls handshake.
+///
+/// rustls looks up cached sessions when it starts a handshake,
+/// which is the only hook available between the tcp connect and the handshake.
+#[derive(Debug)]
+struct TimingSessionStore {
+    inner: rustls::client::ClientSessionMemoryCache,
+}
+
+impl TimingSessionStore {
+    #[inline]
+    fn mark_tls_start() {
+        record(|events| {
+            if events.tls_start.is_none() {
+                events.tls_start = Some(std::time::Instant::now());
+            }
+        });
+    }
+}
+
+impl rustls::client::ClientSessionStore for TimingSessionStore {
+    #[inline]
+    fn set_kx_hint(
+        &self,
+        server_name: rustls::pki_types::ServerName<'static>,
+        group: rustls::NamedGroup,
+    ) {
+        self.inner.set_kx_hint(server_name, group);
+    }
+
+    #[inline]
+    fn kx_hint(
+        &self,
+        server_name: &rustls::pki_types::ServerName<'_>,
+    ) -> Option<rustls::NamedGroup> {
+        Self::mark_tls_start();
+
+        self.inner.kx_hint(server_name)
+    }
+
+    #[inline]
+    fn set_tls12_session(
+        &self,
+        server_name: rustls::pki_types::ServerName<'static>,
+        value: rustls::client::Tls12ClientSessionValue,
+    ) {
+        self.inner.set_tls12_session(server_name, value);
+    }
+
+    #[inline]
+    fn tls12_session(
+        &self,
+        server_name: &rustls::pki_types::ServerName<'_>,
+    ) -> Option<rustls::client::Tls12ClientSessionValue> {
+        Self::mark_tls_start();
+
+        self.inner.tls12_session(server_name)
+    }
+
+    #[inline]
+    fn remove_tls12_session(&self, server_name: &rustls::pki_types::ServerName<'static>) {
+        self.inner.remove_tls12_session(server_name);
+    }
+
+    #[inline]
+    fn insert_tls13_ticket(
+        &self,
+        server_name: rustls::pki_types::ServerName<'static>,
+        value: rustls::client::Tls13ClientSessionValue,
+    ) {
+        self.inner.insert_tls13_ticket(server_name, value);
+    }
+
+    #[inline]
+    fn take_tls13_ticket(
+        &self,
+        server_name: &rustls::pki_types::ServerName<'static>,
+    ) -> Option<rustls::client::Tls13ClientSessionValue> {
+        Self::mark_tls_start();
+
+        self.inner.take_tls13_ticket(server_name)
+    }
+}
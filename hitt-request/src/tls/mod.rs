@@ -0,0 +1,577 @@
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject};
+
+mod pkcs12;
+
+#[derive(Debug)]
+pub enum TlsError {
+    Io(std::path::PathBuf, std::io::Error),
+    MissingCertificate(std::path::PathBuf),
+    MissingPrivateKey(std::path::PathBuf),
+    Pem(std::path::PathBuf, rustls::pki_types::pem::Error),
+    Pkcs12(std::path::PathBuf),
+    Rustls(rustls::Error),
+}
+
+impl core::error::Error for TlsError {}
+
+impl core::fmt::Display for TlsError {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(path, error) => write!(f, "unable to read '{}' - {error}", path.display()),
+            Self::MissingCertificate(path) => {
+                write!(f, "no certificates found in '{}'", path.display())
+            }
+            Self::MissingPrivateKey(path) => {
+                write!(f, "no private key found in '{}'", path.display())
+            }
+            Self::Pem(path, error) => write!(f, "invalid pem file '{}' - {error}", path.display()),
+            Self::Pkcs12(path) => write!(
+                f,
+                "unable to read pkcs12 file '{}' - the password is wrong or the file is invalid",
+                path.display()
+            ),
+            Self::Rustls(error) => write!(f, "{error}"),
+        }
+    }
+}
+
+impl From<rustls::Error> for TlsError {
+    #[inline]
+    fn from(value: rustls::Error) -> Self {
+        Self::Rustls(value)
+    }
+}
+
+/// HTTP versions supported by the client, in order of preference.
+pub const HTTP_VERSIONS: [http::Version; 2] = [http::Version::HTTP_2, http::Version::HTTP_11];
+
+/// Certificates and verification settings of a client.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct TlsOptions {
+    /// PEM file with the certificates to trust instead of the system certificates.
+    pub ca_certificate: Option<std::path::PathBuf>,
+    /// PEM file or PKCS#12 archive with the client certificate.
+    pub client_certificate: Option<std::path::PathBuf>,
+    /// PEM file with the private key of the client certificate, if it is not part of
+    /// `client_certificate`.
+    pub client_key: Option<std::path::PathBuf>,
+    /// Password of the PKCS#12 archive.
+    pub certificate_password: Option<String>,
+    /// Accept any server certificate.
+    pub insecure: bool,
+}
+
+#[inline]
+fn read_file(path: &std::path::Path) -> Result<Vec<u8>, TlsError> {
+    std::fs::read(path).map_err(|error| TlsError::Io(path.to_path_buf(), error))
+}
+
+#[inline]
+fn is_pem(data: &[u8]) -> bool {
+    data.windows(11).any(|window| window == b"-----BEGIN ")
+}
+
+/// Reads every certificate of a PEM file.
+#[inline]
+fn load_certificates(
+    path: &std::path::Path,
+    data: &[u8],
+) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let certificates = CertificateDer::pem_slice_iter(data)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| TlsError::Pem(path.to_path_buf(), error))?;
+
+    if certificates.is_empty() {
+        return Err(TlsError::MissingCertificate(path.to_path_buf()));
+    }
+
+    Ok(certificates)
+}
+
+#[cfg(test)]
+mod test_load_certificates {
+    use super::{TlsError, load_certificates};
+
+    #[test]
+    fn it_should_read_every_certificate() {
+        let bundle = [
+            include_str!("fixtures/ca.pem"),
+            include_str!("fixtures/server.pem"),
+        ]
+        .join("\n");
+
+        let certificates = load_certificates(std::path::Path::new("bundle.pem"), bundle.as_bytes())
+            .expect("it to read the certificates");
+
+        assert_eq!(2, certificates.len());
+    }
+
+    #[test]
+    fn it_should_reject_files_without_certificates() {
+        let error = load_certificates(
+            std::path::Path::new("client.key"),
+            include_bytes!("fixtures/client.key"),
+        )
+        .expect_err("it to reject the file");
+
+        assert_eq!("no certificates found in 'client.key'", error.to_string());
+        assert!(matches!(error, TlsError::MissingCertificate(_)));
+    }
+}
+
+/// Reads the client certificate chain and its private key.
+///
+/// The private key is read from `client_key`, or from the certificate file if it is not set.
+/// Files that are not PEM encoded are read as PKCS#12 archives.
+#[inline]
+fn load_identity(
+    options: &TlsOptions,
+) -> Result<Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)>, TlsError> {
+    let Some(certificate_path) = &options.client_certificate else {
+        return Ok(None);
+    };
+
+    let data = read_file(certificate_path)?;
+
+    if !is_pem(&data) {
+        let password = options.certificate_password.as_deref().unwrap_or_default();
+
+        return pkcs12::parse_pkcs12(&data, password)
+            .map(Some)
+            .ok_or_else(|| TlsError::Pkcs12(certificate_path.clone()));
+    }
+
+    let certificates = load_certificates(certificate_path, &data)?;
+
+    let (key_path, key_data) = match &options.client_key {
+        Some(key_path) => (key_path, read_file(key_path)?),
+        None => (certificate_path, data),
+    };
+
+    let key = PrivateKeyDer::from_pem_slice(&key_data).map_err(|error| match error {
+        rustls::pki_types::pem::Error::NoItemsFound => {
+            TlsError::MissingPrivateKey(key_path.clone())
+        }
+        error => TlsError::Pem(key_path.clone(), error),
+    })?;
+
+    Ok(Some((certificates, key)))
+}
+
+#[cfg(test)]
+mod test_load_identity {
+    use super::{TlsError, TlsOptions, load_identity};
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/tls/fixtures")
+            .join(name)
+    }
+
+    #[test]
+    fn it_should_read_pem_files() {
+        let options = TlsOptions {
+            client_certificate: Some(fixture("client.pem")),
+            client_key: Some(fixture("client.key")),
+            ..TlsOptions::default()
+        };
+
+        let (certificates, _key) = load_identity(&options)
+            .expect("it to read the identity")
+            .expect("it to return an identity");
+
+        assert_eq!(1, certificates.len());
+
+        assert!(
+            load_identity(&TlsOptions::default())
+                .expect("it to skip the identity")
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn it_should_read_key_from_certificate_file() {
+        let dir = tempfile::Builder::new()
+            .prefix("hitt-")
+            .rand_bytes(12)
+            .tempdir()
+            .expect("it to create a dir");
+
+        let path = dir.path().join("client.pem");
+
+        std::fs::write(
+            &path,
+            [
+                include_str!("fixtures/client.pem"),
+                include_str!("fixtures/client.key"),
+            ]
+            .join("\n"),
+        )
+        .expect("it to write the file");
+
+        let options = TlsOptions {
+            client_certificate: Some(path),
+            ..TlsOptions::default()
+        };
+
+        assert!(
+            load_identity(&options)
+                .expect("it to read the identity")
+                .is_some()
+        );
+
+        let options = TlsOptions {
+            client_certificate: Some(fixture("client.pem")),
+            ..TlsOptions::default()
+        };
+
+        let error = load_identity(&options).expect_err("it to require a private key");
+
+        assert!(
+            matches!(error, TlsError::MissingPrivateKey(path) if path == fixture("client.pem"))
+        );
+    }
+
+    #[test]
+    fn it_should_read_pkcs12_archives() {
+        let options = TlsOptions {
+            client_certificate: Some(fixture("client.p12")),
+            certificate_password: Some("hitt".to_owned()),
+            ..TlsOptions::default()
+        };
+
+        let (certificates, _key) = load_identity(&options)
+            .expect("it to read the identity")
+            .expect("it to return an identity");
+
+        assert_eq!(2, certificates.len());
+
+        let options = TlsOptions {
+            certificate_password: None,
+            ..options
+        };
+
+        let error = load_identity(&options).expect_err("it to require the password");
+
+        assert!(matches!(error, TlsError::Pkcs12(path) if path == fixture("client.p12")));
+    }
+
+    #[test]
+    fn it_should_reject_missing_files() {
+        let options = TlsOptions {
+            client_certificate: Some(fixture("missing.pem")),
+            ..TlsOptions::default()
+        };
+
+        let error = load_identity(&options).expect_err("it to reject the file");
+
+        assert!(matches!(error, TlsError::Io(path, _) if path == fixture("missing.pem")));
+    }
+}
+
+/// Verifier used by [`TlsOptions::insecure`], which accepts any certificate.
+///
+/// Handshake signatures are still verified, so the server must own the key of its certificate.
+#[derive(Debug)]
+struct InsecureVerifier {
+    algorithms: rustls::crypto::WebPkiSupportedAlgorithms,
+}
+
+impl rustls::client::danger::ServerCertVerifier for InsecureVerifier {
+    #[inline]
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &rustls::pki_types::ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: rustls::pki_types::UnixTime,
+    ) -> Result<rustls::client::danger::ServerCertVerified, rustls::Error> {
+        Ok(rustls::client::danger::ServerCertVerified::assertion())
+    }
+
+    #[inline]
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    #[inline]
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &rustls::DigitallySignedStruct,
+    ) -> Result<rustls::client::danger::HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    #[inline]
+    fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+#[inline]
+fn build_verifier(
+    options: &TlsOptions,
+    provider: &std::sync::Arc<rustls::crypto::CryptoProvider>,
+) -> Result<std::sync::Arc<dyn rustls::client::danger::ServerCertVerifier>, TlsError> {
+    if options.insecure {
+        return Ok(std::sync::Arc::new(InsecureVerifier {
+            algorithms: provider.signature_verification_algorithms,
+        }));
+    }
+
+    let Some(ca_path) = &options.ca_certificate else {
+        return Ok(std::sync::Arc::new(
+            rustls_platform_verifier::Verifier::new(std::sync::Arc::clone(provider))?,
+        ));
+    };
+
+    let mut roots = rustls::RootCertStore::empty();
+
+    for certificate in load_certificates(ca_path, &read_file(ca_path)?)? {
+        roots.add(certificate)?;
+    }
+
+    rustls::client::WebPkiServerVerifier::builder_with_provider(
+        std::sync::Arc::new(roots),
+        std::sync::Arc::clone(provider),
+    )
+    .build()
+    .map(|verifier| verifier as std::sync::Arc<dyn rustls::client::danger::ServerCertVerifier>)
+    .map_err(|error| TlsError::Rustls(rustls::Error::General(error.to_string())))
+}
+
+#[inline]
+fn alpn_protocols(http_versions: &[http::Version]) -> Vec<Vec<u8>> {
+    http_versions
+        .iter()
+        .filter_map(|version| match *version {
+            http::Version::HTTP_2 => Some(b"h2".to_vec()),
+            http::Version::HTTP_11 => Some(b"http/1.1".to_vec()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test_alpn_protocols {
+    use super::alpn_protocols;
+
+    #[test]
+    fn it_should_map_http_versions() {
+        assert_eq!(
+            vec![b"h2".to_vec(), b"http/1.1".to_vec()],
+            alpn_protocols(&super::HTTP_VERSIONS)
+        );
+
+        assert_eq!(
+            vec![b"http/1.1".to_vec()],
+            alpn_protocols(&[http::Version::HTTP_11, http::Version::HTTP_10])
+        );
+    }
+}
+
+/// Builds the tls config of a client, which can be used with
+/// `reqwest::ClientBuilder::tls_backend_preconfigured`.
+///
+/// Certificates are verified using the system certificates, unless a CA certificate is set.
+/// The ALPN protocols are derived from `http_versions`, see [`HTTP_VERSIONS`].
+#[inline]
+pub fn build_tls_config(
+    options: &TlsOptions,
+    http_versions: &[http::Version],
+) -> Result<rustls::ClientConfig, TlsError> {
+    let provider = rustls::crypto::CryptoProvider::get_default().map_or_else(
+        || std::sync::Arc::new(rustls::crypto::aws_lc_rs::default_provider()),
+        std::sync::Arc::clone,
+    );
+
+    let verifier = build_verifier(options, &provider)?;
+
+    let builder = rustls::ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier);
+
+    let mut config = match load_identity(options)? {
+        Some((certificates, key)) => builder.with_client_auth_cert(certificates, key)?,
+        None => builder.with_no_client_auth(),
+    };
+
+    config.alpn_protocols = alpn_protocols(http_versions);
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod test_build_tls_config {
+    use rustls::pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject};
+
+    use super::{TlsOptions, build_tls_config};
+
+    fn fixture(name: &str) -> std::path::PathBuf {
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("src/tls/fixtures")
+            .join(name)
+    }
+
+    /// Serves a single https connection, optionally requiring a client certificate.
+    fn serve(require_client_certificate: bool) -> reqwest::Url {
+        let provider = std::sync::Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+
+        let builder = rustls::ServerConfig::builder_with_provider(std::sync::Arc::clone(&provider))
+            .with_safe_default_protocol_versions()
+            .expect("it to support the default versions");
+
+        let builder = if require_client_certificate {
+            let mut roots = rustls::RootCertStore::empty();
+
+            roots
+                .add(
+                    CertificateDer::from_pem_slice(include_bytes!("fixtures/ca.pem"))
+                        .expect("it to be a valid certificate"),
+                )
+                .expect("it to add the certificate");
+
+            builder.with_client_cert_verifier(
+                rustls::server::WebPkiClientVerifier::builder_with_provider(
+                    std::sync::Arc::new(roots),
+                    provider,
+                )
+                .build()
+                .expect("it to build the verifier"),
+            )
+        } else {
+            builder.with_no_client_auth()
+        };
+
+        let config = builder
+            .with_single_cert(
+                vec![
+                    CertificateDer::from_pem_slice(include_bytes!("fixtures/server.pem"))
+                        .expect("it to be a valid certificate"),
+                ],
+                PrivateKeyDer::from_pem_slice(include_bytes!("fixtures/server.key"))
+                    .expect("it to be a valid key"),
+            )
+            .expect("it to build the config");
+
+        let listener =
+            std::net::TcpListener::bind("127.0.0.1:0").expect("it to bind to a local port");
+
+        let addr = listener.local_addr().expect("it to have a local address");
+
+        std::thread::spawn(move || {
+            let (stream, _) = listener.accept().expect("it to accept a connection");
+
+            let connection = rustls::ServerConnection::new(std::sync::Arc::new(config))
+                .expect("it to create a connection");
+
+            let mut stream = rustls::StreamOwned::new(connection, stream);
+
+            let mut buffer = [0; 1024];
+
+            // the handshake fails when the client rejects the certificate
+            if std::io::Read::read(&mut stream, &mut buffer).is_ok() {
+                let _ = std::io::Write::write_all(
+                    &mut stream,
+                    b"HTTP/1.1 200 OK\r\ncontent-length: 2\r\nconnection: close\r\n\r\nok",
+                );
+
+                stream.conn.send_close_notify();
+
+                let _ = std::io::Write::flush(&mut stream);
+            }
+        });
+
+        reqwest::Url::parse(&format!("https://localhost:{}/", addr.port()))
+            .expect("it to be a valid url")
+    }
+
+    async fn send(options: &TlsOptions, url: reqwest::Url) -> reqwest::Result<String> {
+        let config =
+            build_tls_config(options, &super::HTTP_VERSIONS).expect("it to build the config");
+
+        let client = reqwest::Client::builder()
+            .tls_backend_preconfigured(config)
+            .resolve(
+                "localhost",
+                ([127, 0, 0, 1], url.port().unwrap_or_default()).into(),
+            )
+            .build()
+            .expect("it to build a client");
+
+        client.get(url).send().await?.text().await
+    }
+
+    #[tokio::test]
+    async fn it_should_trust_ca_certificate() {
+        let options = TlsOptions {
+            ca_certificate: Some(fixture("ca.pem")),
+            ..TlsOptions::default()
+        };
+
+        let body = send(&options, serve(false))
+            .await
+            .expect("it to send the request");
+
+        assert_eq!("ok", body);
+
+        send(&TlsOptions::default(), serve(false))
+            .await
+            .expect_err("it to reject the unknown certificate");
+    }
+
+    #[tokio::test]
+    async fn it_should_accept_any_certificate_if_insecure() {
+        let options = TlsOptions {
+            insecure: true,
+            ..TlsOptions::default()
+        };
+
+        let body = send(&options, serve(false))
+            .await
+            .expect("it to send the request");
+
+        assert_eq!("ok", body);
+    }
+
+    #[tokio::test]
+    async fn it_should_send_client_certificate() {
+        for (client_certificate, client_key, certificate_password) in [
+            (fixture("client.pem"), Some(fixture("client.key")), None),
+            (fixture("client.p12"), None, Some("hitt".to_owned())),
+        ] {
+            let options = TlsOptions {
+                ca_certificate: Some(fixture("ca.pem")),
+                client_certificate: Some(client_certificate),
+                client_key,
+                certificate_password,
+                insecure: false,
+            };
+
+            let body = send(&options, serve(true))
+                .await
+                .expect("it to send the request");
+
+            assert_eq!("ok", body);
+        }
+
+        let options = TlsOptions {
+            ca_certificate: Some(fixture("ca.pem")),
+            ..TlsOptions::default()
+        };
+
+        send(&options, serve(true))
+            .await
+            .expect_err("it to require a client certificate");
+    }
+}
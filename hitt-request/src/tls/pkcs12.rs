@@ -0,0 +1,59 @@
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+
+/// Reads the certificate chain and private key of a DER encoded PKCS#12 archive.
+///
+/// The certificate matching the private key is returned first.
+#[inline]
+pub fn parse_pkcs12(
+    der: &[u8],
+    password: &str,
+) -> Option<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>)> {
+    let keystore = p12_keystore::KeyStore::from_pkcs12(der, password).ok()?;
+
+    let (_alias, key_chain) = keystore.private_key_chain()?;
+
+    let certificates = key_chain
+        .chain()
+        .iter()
+        .map(|certificate| CertificateDer::from(certificate.as_der().to_vec()))
+        .collect::<Vec<_>>();
+
+    if certificates.is_empty() {
+        return None;
+    }
+
+    Some((
+        certificates,
+        PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key_chain.key().to_vec())),
+    ))
+}
+
+#[cfg(test)]
+mod test_parse_pkcs12 {
+    use rustls::pki_types::{CertificateDer, pem::PemObject};
+
+    use super::parse_pkcs12;
+
+    const ARCHIVE: &[u8] = include_bytes!("fixtures/client.p12");
+
+    #[test]
+    fn it_should_read_certificates_and_key() {
+        let (certificates, _key) = parse_pkcs12(ARCHIVE, "hitt").expect("it to parse the archive");
+
+        let expected = [
+            CertificateDer::from_pem_slice(include_bytes!("fixtures/client.pem"))
+                .expect("it to be a valid certificate"),
+            CertificateDer::from_pem_slice(include_bytes!("fixtures/ca.pem"))
+                .expect("it to be a valid certificate"),
+        ];
+
+        assert_eq!(expected.as_slice(), certificates.as_slice());
+    }
+
+    #[test]
+    fn it_should_reject_wrong_passwords() {
+        assert!(parse_pkcs12(ARCHIVE, "wrong").is_none());
+
+        assert!(parse_pkcs12(b"not an archive", "hitt").is_none());
+    }
+}
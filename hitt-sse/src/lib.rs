@@ -1,8 +1,45 @@
+use eventsource_stream::{EventStreamError, Eventsource};
 use futures::StreamExt;
-use reqwest_eventsource::EventSource;
 use tokio::sync::mpsc::{UnboundedSender, error::SendError};
 
-pub type Error = reqwest_eventsource::Error;
+const EVENT_STREAM_MIME_TYPE: &str = "text/event-stream";
+
+/// Delay before the first reconnect, unless the server sets one using `retry:`.
+const DEFAULT_RECONNECT_DELAY: core::time::Duration = core::time::Duration::from_millis(300);
+
+/// Reconnect delays are doubled after every failed attempt, up until this delay.
+const MAX_RECONNECT_DELAY: core::time::Duration = core::time::Duration::from_secs(5);
+
+#[derive(Debug)]
+pub enum Error {
+    /// The HTTP request could not be completed.
+    Request(reqwest::Error),
+    /// The event stream could not be read or parsed.
+    Stream(EventStreamError<reqwest::Error>),
+    InvalidContentType(Option<reqwest::header::HeaderValue>),
+    InvalidLastEventId(String),
+    InvalidStatusCode(reqwest::StatusCode),
+    StreamEnded,
+}
+
+impl core::error::Error for Error {}
+
+impl core::fmt::Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Request(error) => write!(f, "{error}"),
+            Self::Stream(error) => write!(f, "{error}"),
+            Self::InvalidContentType(Some(content_type)) => {
+                write!(f, "Invalid header value: {content_type:?}")
+            }
+            Self::InvalidContentType(None) => write!(f, "Missing content-type header"),
+            Self::InvalidLastEventId(id) => write!(f, "Invalid `Last-Event-ID`: {id}"),
+            Self::InvalidStatusCode(status_code) => write!(f, "Invalid status code: {status_code}"),
+            Self::StreamEnded => write!(f, "Stream ended"),
+        }
+    }
+}
 
 #[derive(Debug)]
 pub enum Event {
@@ -11,22 +48,282 @@ pub enum Event {
     Error(Box<Error>),
 }
 
+#[inline]
+fn is_event_stream(content_type: &reqwest::header::HeaderValue) -> bool {
+    content_type.to_str().is_ok_and(|value| {
+        value
+            .split(';')
+            .next()
+            .is_some_and(|mime| mime.trim().eq_ignore_ascii_case(EVENT_STREAM_MIME_TYPE))
+    })
+}
+
+#[cfg(test)]
+mod test_is_event_stream {
+    use reqwest::header::HeaderValue;
+
+    use super::is_event_stream;
+
+    #[test]
+    fn it_should_accept_event_streams() {
+        for content_type in [
+            "text/event-stream",
+            "text/event-stream; charset=utf-8",
+            "Text/Event-Stream",
+        ] {
+            assert!(is_event_stream(&HeaderValue::from_static(content_type)));
+        }
+    }
+
+    #[test]
+    fn it_should_reject_other_content_types() {
+        for content_type in ["application/json", "text/plain", ""] {
+            assert!(!is_event_stream(&HeaderValue::from_static(content_type)));
+        }
+    }
+}
+
+/// Streams the chunks of the response body, ending after the first error.
+#[inline]
+fn body_chunks(
+    response: reqwest::Response,
+) -> impl futures::Stream<Item = Result<Vec<u8>, reqwest::Error>> {
+    futures::stream::unfold(Some(response), |state| async move {
+        let mut response = state?;
+
+        match response.chunk().await {
+            Ok(Some(chunk)) => Some((Ok(chunk.to_vec()), Some(response))),
+            Ok(None) => None,
+            Err(error) => Some((Err(error), None)),
+        }
+    })
+}
+
+/// Sends the request, returning the response if it is an event stream.
+#[inline]
+async fn connect(
+    http_client: &reqwest::Client,
+    url: &reqwest::Url,
+    last_event_id: &str,
+) -> Result<reqwest::Response, Error> {
+    let mut request = http_client
+        .get(url.clone())
+        .header(reqwest::header::ACCEPT, EVENT_STREAM_MIME_TYPE);
+
+    if !last_event_id.is_empty() {
+        let value = reqwest::header::HeaderValue::from_str(last_event_id)
+            .map_err(|_| Error::InvalidLastEventId(last_event_id.to_owned()))?;
+
+        request = request.header("last-event-id", value);
+    }
+
+    let response = request.send().await.map_err(Error::Request)?;
+
+    if !response.status().is_success() {
+        return Err(Error::InvalidStatusCode(response.status()));
+    }
+
+    let content_type = response.headers().get(reqwest::header::CONTENT_TYPE);
+
+    if !content_type.is_some_and(is_event_stream) {
+        return Err(Error::InvalidContentType(content_type.cloned()));
+    }
+
+    Ok(response)
+}
+
+/// Listens to the event stream, sending every event to `tx`.
+///
+/// Lost connections are reopened with the id of the last event as the `Last-Event-ID` header,
+/// waiting the delay set by the server using `retry:`, doubled after every failed attempt.
+/// The stream is closed if the server responds with an unexpected status code or content type.
+///
+/// Returns once the stream is closed, or the receiver of `tx` is dropped.
 #[inline]
 pub async fn start_sse(
+    http_client: reqwest::Client,
     url: reqwest::Url,
     tx: UnboundedSender<Event>,
 ) -> Result<(), SendError<Event>> {
-    let mut ev = EventSource::get(url);
+    let mut last_event_id = String::new();
+
+    let mut reconnect_delay = DEFAULT_RECONNECT_DELAY;
+    let mut next_delay = reconnect_delay;
+
+    loop {
+        match connect(&http_client, &url, &last_event_id).await {
+            Ok(response) => {
+                tx.send(Event::Open)?;
+
+                let mut stream = body_chunks(response).eventsource();
+
+                stream.set_last_event_id(last_event_id.clone());
+
+                let mut stream = core::pin::pin!(stream);
+
+                let mut error = Error::StreamEnded;
+
+                while let Some(event) = stream.next().await {
+                    match event {
+                        Ok(message) => {
+                            last_event_id.clone_from(&message.id);
+
+                            if let Some(retry) = message.retry {
+                                reconnect_delay = retry;
+                            }
 
-    while let Some(event) = ev.next().await {
-        match event {
-            Ok(reqwest_eventsource::Event::Open) => tx.send(Event::Open),
-            Ok(reqwest_eventsource::Event::Message(m)) => tx.send(Event::Message(m.data)),
-            Err(error) => tx.send(Event::Error(Box::new(error))),
-        }?;
+                            tx.send(Event::Message(message.data))?;
+                        }
+                        Err(stream_error) => {
+                            error = Error::Stream(stream_error);
+                            break;
+                        }
+                    }
+                }
+
+                tx.send(Event::Error(Box::new(error)))?;
+
+                next_delay = reconnect_delay;
+            }
+
+            Err(error @ Error::Request(_)) => tx.send(Event::Error(Box::new(error)))?,
+
+            Err(error) => return tx.send(Event::Error(Box::new(error))),
+        }
+
+        tokio::time::sleep(next_delay).await;
+
+        next_delay = (next_delay * 2).min(MAX_RECONNECT_DELAY.max(reconnect_delay));
+    }
+}
+
+#[cfg(test)]
+mod test_start_sse {
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use super::{Error, Event, start_sse};
+
+    /// Serves a connection for each of the responses, returning the requests it received.
+    fn serve(responses: &[&'static str]) -> (reqwest::Url, std::sync::mpsc::Receiver<String>) {
+        let listener =
+            std::net::TcpListener::bind("127.0.0.1:0").expect("it to bind to a local port");
+
+        let addr = listener.local_addr().expect("it to have a local address");
+
+        let (sender, receiver) = std::sync::mpsc::channel();
+
+        let responses = responses.to_vec();
+
+        std::thread::spawn(move || {
+            for response in responses {
+                let (mut stream, _) = listener.accept().expect("it to accept a connection");
+
+                let mut buffer = [0; 1024];
+
+                let len =
+                    std::io::Read::read(&mut stream, &mut buffer).expect("it to read the request");
+
+                let _ = sender.send(String::from_utf8_lossy(&buffer[..len]).to_lowercase());
+
+                std::io::Write::write_all(&mut stream, response.as_bytes())
+                    .expect("it to write the response");
+            }
+        });
+
+        (
+            reqwest::Url::parse(&format!("http://{addr}/")).expect("it to be a valid url"),
+            receiver,
+        )
     }
 
-    ev.close();
+    /// Collects events until `count` events are received, or the stream is closed.
+    async fn collect_events(url: reqwest::Url, count: usize) -> Vec<Event> {
+        let (tx, mut rx) = unbounded_channel();
+
+        let listener = tokio::spawn(start_sse(reqwest::Client::new(), url, tx));
+
+        let mut events = Vec::new();
 
-    Ok(())
+        while events.len() < count
+            && let Some(event) = rx.recv().await
+        {
+            events.push(event);
+        }
+
+        drop(rx);
+
+        listener.abort();
+
+        events
+    }
+
+    #[tokio::test]
+    async fn it_should_send_messages() {
+        let (url, _requests) = serve(&[
+            "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\ndata: mads\n\ndata: was here\n\n",
+        ]);
+
+        let events = collect_events(url, 4).await;
+
+        assert!(matches!(
+            events.as_slice(),
+            [
+                Event::Open,
+                Event::Message(first),
+                Event::Message(second),
+                Event::Error(error),
+            ] if first == "mads" && second == "was here" && matches!(**error, Error::StreamEnded)
+        ));
+    }
+
+    #[tokio::test]
+    async fn it_should_reconnect_with_last_event_id() {
+        let (url, requests) = serve(&[
+            "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\nid: 42\nretry: 10\ndata: first\n\n",
+            "HTTP/1.1 200 OK\r\ncontent-type: text/event-stream\r\nconnection: close\r\n\r\ndata: second\n\n",
+        ]);
+
+        let events = collect_events(url, 5).await;
+
+        assert!(matches!(
+            events.as_slice(),
+            [
+                Event::Open,
+                Event::Message(first),
+                Event::Error(_),
+                Event::Open,
+                Event::Message(second),
+            ] if first == "first" && second == "second"
+        ));
+
+        let first_request = requests.recv().expect("it to receive the first request");
+        assert!(!first_request.contains("last-event-id"));
+
+        let second_request = requests.recv().expect("it to receive the second request");
+        assert!(second_request.contains("last-event-id: 42\r\n"));
+    }
+
+    #[tokio::test]
+    async fn it_should_reject_invalid_responses() {
+        let (url, _requests) =
+            serve(&["HTTP/1.1 404 Not Found\r\ncontent-length: 0\r\nconnection: close\r\n\r\n"]);
+
+        let events = collect_events(url, usize::MAX).await;
+
+        assert!(matches!(
+            events.as_slice(),
+            [Event::Error(error)] if matches!(**error, Error::InvalidStatusCode(reqwest::StatusCode::NOT_FOUND))
+        ));
+
+        let (url, _requests) = serve(&[
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 2\r\nconnection: close\r\n\r\n{}",
+        ]);
+
+        let events = collect_events(url, usize::MAX).await;
+
+        assert!(matches!(
+            events.as_slice(),
+            [Event::Error(error)] if error.to_string() == "Invalid header value: \"application/json\""
+        ));
+    }
 }
@@ -59,7 +59,7 @@ def read_readme() -> str:
 if __name__ == "__main__":
     content = read_readme()
 
-    for command in [None, "run", "sse", "completions"]:
+    for command in [None, "run", "bench", "sse", "completions"]:
         content = generate_help_section(command, content)
 
     with open("README.md", "w") as readme_file: